@@ -0,0 +1,46 @@
+//! Inference through the MNIST example's network (784→256→128→10): one
+//! sample at a time with `predict`, against a whole batch with
+//! `forward_batch` and `forward_batch_matrix`.
+//!
+//! Run with `cargo bench --bench forward`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use ferrite_nn::{ActivationFunction, Matrix, Network};
+
+const BATCH_SIZES: [usize; 3] = [1, 32, 256];
+
+fn forward(c: &mut Criterion) {
+    let network = Network::new_seeded(
+        vec![
+            (256, 784, ActivationFunction::ReLU),
+            (128, 256, ActivationFunction::ReLU),
+            (10, 128, ActivationFunction::Softmax),
+        ],
+        1,
+    );
+    let mut rng = StdRng::seed_from_u64(2);
+
+    let mut group = c.benchmark_group("forward");
+    for batch in BATCH_SIZES {
+        let inputs: Vec<Vec<f64>> = (0..batch).map(|_| (0..784).map(|_| rng.gen::<f64>()).collect()).collect();
+        let matrix = Matrix::from_flat(batch, 784, inputs.concat());
+        group.throughput(Throughput::Elements(batch as u64));
+
+        group.bench_with_input(BenchmarkId::new("single", batch), &inputs, |bench, inputs| {
+            bench.iter(|| inputs.iter().map(|x| network.predict(black_box(x))).collect::<Vec<_>>())
+        });
+        group.bench_with_input(BenchmarkId::new("batched", batch), &inputs, |bench, inputs| {
+            bench.iter(|| network.forward_batch(black_box(inputs)))
+        });
+        group.bench_with_input(BenchmarkId::new("matrix", batch), &matrix, |bench, matrix| {
+            bench.iter(|| network.forward_batch_matrix(black_box(matrix)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, forward);
+criterion_main!(benches);
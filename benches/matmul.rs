@@ -0,0 +1,38 @@
+//! `Matrix` products at the shapes training and inference actually hit:
+//! one sample and a mini-batch through MNIST-sized layers, and square
+//! matrices of growing size.
+//!
+//! Run with `cargo bench --bench matmul`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use ferrite_nn::Matrix;
+
+/// `(rows of A, cols of A, cols of B)`.
+const SHAPES: [(usize, usize, usize); 6] = [
+    (1, 784, 128),
+    (32, 784, 128),
+    (64, 784, 256),
+    (32, 256, 10),
+    (256, 256, 256),
+    (512, 512, 512),
+];
+
+fn matmul(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut group = c.benchmark_group("matmul");
+    for (m, k, n) in SHAPES {
+        let a = Matrix::random_with(m, k, &mut rng);
+        let b = Matrix::random_with(k, n, &mut rng);
+        group.throughput(Throughput::Elements((m * k * n) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{}x{}x{}", m, k, n)), &(a, b), |bench, (a, b)| {
+            bench.iter(|| black_box(a) * black_box(b))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, matmul);
+criterion_main!(benches);
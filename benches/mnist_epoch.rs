@@ -0,0 +1,59 @@
+//! One training epoch of the MNIST example's network (784→256→128→10,
+//! Adam, batch size 32) on the first `SUBSET` training images, so changes
+//! to the training loop can be compared end to end.
+//!
+//! Reads `examples/mnist_data/` (see the `mnist` example); the benchmark is
+//! skipped when the files are missing.  Run with `cargo bench --bench mnist_epoch`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use ferrite_nn::data::idx::load_idx_pair;
+use ferrite_nn::{try_train_loop, ActivationFunction, Adam, LossType, Network, TrainConfig};
+
+const SUBSET: usize = 2_000;
+
+fn mnist_epoch(c: &mut Criterion) {
+    let (mut images, mut labels) = match load_idx_pair(
+        "examples/mnist_data/train-images-idx3-ubyte",
+        "examples/mnist_data/train-labels-idx1-ubyte",
+        10,
+    ) {
+        Ok(samples) => samples,
+        Err(e) => {
+            eprintln!("skipping mnist_epoch: {}", e);
+            return;
+        }
+    };
+    images.truncate(SUBSET);
+    labels.truncate(SUBSET);
+
+    let network = Network::new_seeded(
+        vec![
+            (256, 784, ActivationFunction::ReLU),
+            (128, 256, ActivationFunction::ReLU),
+            (10, 128, ActivationFunction::Softmax),
+        ],
+        1,
+    );
+    let mut config = TrainConfig::new(1, 32, LossType::CrossEntropy);
+    config.seed = Some(1);
+
+    let mut group = c.benchmark_group("mnist_epoch");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(images.len() as u64));
+    group.bench_function(format!("{}_samples", images.len()), |bench| {
+        // Every iteration trains the same fresh network, so each runs the same work.
+        bench.iter_batched_ref(
+            || (network.clone(), Adam::new(0.001)),
+            |(network, optimizer)| {
+                try_train_loop(network, &(&images, &labels), None, optimizer, &config, &mut [])
+                    .expect("training succeeds")
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, mnist_epoch);
+criterion_main!(benches);
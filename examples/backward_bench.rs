@@ -0,0 +1,100 @@
+//! Times the per-sample backward pass of an MNIST-sized network: the
+//! allocating `LayerOps::backward` plus accumulation it replaced, against
+//! `LayerOps::backward_into`, which adds into the gradient buffers in place.
+//! Checks that both accumulate identical gradients.
+//!
+//! Run with `cargo run --example backward_bench --release`.
+
+use std::time::Instant;
+
+use ferrite_nn::{ActivationFunction, Matrix, Network};
+
+/// A backward pass accumulating one sample's gradients into the buffers.
+type Backward = fn(&Network, &[f64], &[f64], &mut [(Matrix, Matrix)]);
+
+/// One `(weights_grad, biases_grad)` pair per layer, zeroed.
+fn zero_grads(network: &Network) -> Vec<(Matrix, Matrix)> {
+    network.layers.iter()
+        .map(|layer| {
+            let (w, b) = layer.params();
+            (Matrix::zeros(w.rows, w.cols), Matrix::zeros(b.rows, b.cols))
+        })
+        .collect()
+}
+
+/// Input of layer `i` for the sample last passed to `forward`.
+fn layer_input<'a>(network: &'a Network, input: &'a [f64], i: usize) -> &'a [f64] {
+    if i == 0 { input } else { network.layers[i - 1].output() }
+}
+
+/// The old path: fresh gradient matrices per layer and sample, then added.
+fn backward_allocating(network: &Network, input: &[f64], output_delta: &[f64], acc: &mut [(Matrix, Matrix)]) {
+    let mut delta = output_delta.to_vec();
+    for i in (0..network.layers.len()).rev() {
+        let grads = network.layers[i].backward(layer_input(network, input, i), &delta, i > 0);
+        if let Some(input_delta) = grads.input_delta {
+            delta = input_delta;
+        }
+        acc[i].0.add_assign_mat(&grads.weights);
+        acc[i].1.add_assign_mat(&grads.biases);
+    }
+}
+
+/// The current path: gradients accumulated in place.
+fn backward_in_place(network: &Network, input: &[f64], output_delta: &[f64], acc: &mut [(Matrix, Matrix)]) {
+    let mut delta = output_delta.to_vec();
+    for i in (0..network.layers.len()).rev() {
+        let input_delta = network.layers[i]
+            .backward_into(layer_input(network, input, i), &delta, false, i > 0, &mut acc[i])
+            .expect("accumulators are shaped like the layers");
+        if let Some(input_delta) = input_delta {
+            delta = input_delta;
+        }
+    }
+}
+
+/// Average seconds per sample of `backward` over `reps` backward passes of
+/// the cached sample, and the accumulated gradients.
+fn time(network: &Network, input: &[f64], delta: &[f64], reps: usize, backward: Backward) -> (f64, Vec<(Matrix, Matrix)>) {
+    let mut acc = zero_grads(network);
+    let start = Instant::now();
+    for _ in 0..reps {
+        backward(network, input, delta, &mut acc);
+    }
+    (start.elapsed().as_secs_f64() / reps as f64, acc)
+}
+
+fn main() {
+    // (hidden sizes): a small MLP, the MNIST example's network, and a wide one.
+    let shapes: [&[usize]; 3] = [&[32], &[128, 64], &[512, 256]];
+
+    println!("{:>18}  {:>14}  {:>14}  {:>7}", "hidden", "alloc (µs)", "in place (µs)", "speedup");
+    for hidden in shapes {
+        let mut specs = Vec::new();
+        let mut fan_in = 784;
+        for &size in hidden {
+            specs.push((size, fan_in, ActivationFunction::ReLU));
+            fan_in = size;
+        }
+        specs.push((10, fan_in, ActivationFunction::Softmax));
+        let mut network = Network::new_seeded(specs, 1);
+
+        let input: Vec<f64> = (0..784).map(|i| (i % 17) as f64 / 16.0).collect();
+        let output = network.forward(input.clone());
+        let delta: Vec<f64> = output.iter().enumerate().map(|(k, y)| y - f64::from(k == 3)).collect();
+        let weights: usize = network.layers.iter().map(|l| l.params().0.data.len()).sum();
+        let reps = (2e8 / weights as f64).clamp(10.0, 10_000.0) as usize;
+
+        let (alloc, expected) = time(&network, &input, &delta, reps, backward_allocating);
+        let (in_place, actual) = time(&network, &input, &delta, reps, backward_in_place);
+        assert!(expected == actual, "in-place gradients differ from the allocating ones");
+
+        println!(
+            "{:>18}  {:>14.2}  {:>14.2}  {:>6.2}x",
+            format!("784-{:?}-10", hidden),
+            alloc * 1e6,
+            in_place * 1e6,
+            alloc / in_place
+        );
+    }
+}
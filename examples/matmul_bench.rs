@@ -0,0 +1,57 @@
+//! Times `Matrix` multiplication against the plain i-k-j triple loop it
+//! replaced, and checks that both produce identical results.
+//!
+//! Run with `cargo run --example matmul_bench --release`.
+
+use std::time::Instant;
+
+use ferrite_nn::Matrix;
+
+/// The untiled kernel, kept here as the baseline.
+fn matmul_naive(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut res = Matrix::zeros(a.rows, b.cols);
+    for i in 0..a.rows {
+        let out = &mut res.data[i * b.cols..(i + 1) * b.cols];
+        for (k, &x) in a.row(i).iter().enumerate() {
+            for (o, &y) in out.iter_mut().zip(b.row(k)) {
+                *o += x * y;
+            }
+        }
+    }
+    res
+}
+
+/// Average seconds per call of `f` over `reps` calls, and its last result.
+fn time(reps: usize, mut f: impl FnMut() -> Matrix) -> (f64, Matrix) {
+    let start = Instant::now();
+    let mut out = f();
+    for _ in 1..reps {
+        out = f();
+    }
+    (start.elapsed().as_secs_f64() / reps as f64, out)
+}
+
+fn main() {
+    // (rows of A, cols of A, cols of B): a single sample and a mini-batch
+    // through an MNIST-sized layer, then square matrices of growing size.
+    let shapes = [(1, 784, 128), (32, 784, 128), (64, 64, 64), (256, 256, 256), (512, 512, 512), (1024, 1024, 1024)];
+
+    println!("{:>16}  {:>12}  {:>12}  {:>7}", "shape", "naive (ms)", "tiled (ms)", "speedup");
+    for (m, k, n) in shapes {
+        let a = Matrix::random(m, k);
+        let b = Matrix::random(k, n);
+        let reps = (2e9 / (m * k * n) as f64).clamp(1.0, 1000.0) as usize;
+
+        let (naive, expected) = time(reps, || matmul_naive(&a, &b));
+        let (tiled, actual) = time(reps, || &a * &b);
+        assert_eq!(expected, actual, "tiled product differs from the naive one");
+
+        println!(
+            "{:>16}  {:>12.3}  {:>12.3}  {:>6.2}x",
+            format!("{}x{}x{}", m, k, n),
+            naive * 1e3,
+            tiled * 1e3,
+            naive / tiled
+        );
+    }
+}
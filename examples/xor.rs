@@ -19,11 +19,11 @@ fn main() {
         vec![0.0],
     ];
 
-    let optimizer = Sgd::new(0.1);
+    let mut optimizer = Sgd::new(0.1);
     let epochs = 10000;
 
     for epoch in 0..epochs {
-        let loss = train_network(&mut network, &inputs, &expected_outputs, &optimizer, 1);
+        let loss = train_network(&mut network, &inputs, &expected_outputs, &mut optimizer, 1);
         if epoch % 1000 == 0 {
             println!("Epoch {epoch}: loss = {loss:.6}");
         }
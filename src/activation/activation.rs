@@ -1,5 +1,9 @@
 use serde::{Serialize, Deserialize};
 use std::f64::consts::{E, PI};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::activation::registry;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ActivationFunction {
@@ -15,6 +19,37 @@ pub enum ActivationFunction {
     Elu { alpha: f64 },
     Gelu,
     Swish,
+    /// Scaled ELU with the self-normalizing constants `SELU_LAMBDA` and
+    /// `SELU_ALPHA` (pair with LeCun init).
+    Selu,
+    /// `ln(1 + eˣ)`, a smooth ReLU.
+    Softplus,
+    /// `x · tanh(softplus(x))`.
+    Mish,
+    /// Piecewise-linear sigmoid `clamp(x/6 + 1/2, 0, 1)` (MobileNetV3); only
+    /// comparisons, one multiply and one add, so it maps onto integer
+    /// inference.
+    HardSigmoid,
+    /// `x · HardSigmoid(x)`, the piecewise counterpart of `Swish`.
+    HardSwish,
+    /// A user-defined activation registered under this name with
+    /// `register_activation`.  Using it before registration panics.
+    Custom(String),
+}
+
+/// SELU scale λ (Klambauer et al., 2017).
+pub const SELU_LAMBDA: f64 = 1.050_700_987_355_480_5;
+/// SELU negative-side α (Klambauer et al., 2017).
+pub const SELU_ALPHA: f64 = 1.673_263_242_354_377_3;
+
+/// `clamp(x/6 + 1/2, 0, 1)`.
+fn hard_sigmoid(x: f64) -> f64 {
+    (x / 6.0 + 0.5).clamp(0.0, 1.0)
+}
+
+/// `ln(1 + eˣ)` without overflow for large `x`.
+fn softplus(x: f64) -> f64 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
 }
 
 impl ActivationFunction {
@@ -40,6 +75,14 @@ impl ActivationFunction {
                 0.5 * x * (1.0 + (c * (x + 0.044715 * x.powi(3))).tanh())
             }
             ActivationFunction::Swish => x / (1.0 + E.powf(-x)),
+            ActivationFunction::Selu => {
+                if x > 0.0 { SELU_LAMBDA * x } else { SELU_LAMBDA * SELU_ALPHA * (x.exp() - 1.0) }
+            }
+            ActivationFunction::Softplus => softplus(x),
+            ActivationFunction::Mish => x * softplus(x).tanh(),
+            ActivationFunction::HardSigmoid => hard_sigmoid(x),
+            ActivationFunction::HardSwish => x * hard_sigmoid(x),
+            ActivationFunction::Custom(name) => registry::call(name, x, false),
         }
     }
 
@@ -79,6 +122,113 @@ impl ActivationFunction {
                 let sig = 1.0 / (1.0 + E.powf(-x));
                 sig + x * sig * (1.0 - sig)
             }
+            ActivationFunction::Selu => {
+                if x > 0.0 { SELU_LAMBDA } else { SELU_LAMBDA * SELU_ALPHA * x.exp() }
+            }
+            // d/dx softplus(x) = sigmoid(x).
+            ActivationFunction::Softplus => 1.0 / (1.0 + (-x).exp()),
+            ActivationFunction::Mish => {
+                let t = softplus(x).tanh();
+                let sig = 1.0 / (1.0 + (-x).exp());
+                t + x * (1.0 - t * t) * sig
+            }
+            // Exact piecewise slopes; the kinks at ±3 take the outer value.
+            ActivationFunction::HardSigmoid => if x > -3.0 && x < 3.0 { 1.0 / 6.0 } else { 0.0 },
+            ActivationFunction::HardSwish => {
+                if x <= -3.0 { 0.0 } else if x >= 3.0 { 1.0 } else { x / 3.0 + 0.5 }
+            }
+            ActivationFunction::Custom(name) => registry::call(name, x, true),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Text form: `relu`, `leaky_relu(0.01)`, `elu(1)`, …
+// ---------------------------------------------------------------------------
+
+/// Default `alpha` for `leaky_relu` written without an argument.
+const DEFAULT_LEAKY_ALPHA: f64 = 0.01;
+/// Default `alpha` for `elu` written without an argument.
+const DEFAULT_ELU_ALPHA: f64 = 1.0;
+
+/// Why a string is not a valid activation name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseActivationError(pub String);
+
+impl fmt::Display for ParseActivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseActivationError {}
+
+/// Snake-case name, with the parameter in parentheses for parameterized
+/// variants (`leaky_relu(0.01)`).  Round-trips through `FromStr`.
+impl fmt::Display for ActivationFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivationFunction::Sigmoid             => write!(f, "sigmoid"),
+            ActivationFunction::ReLU                => write!(f, "relu"),
+            ActivationFunction::Identity            => write!(f, "identity"),
+            ActivationFunction::Softmax             => write!(f, "softmax"),
+            ActivationFunction::Tanh                => write!(f, "tanh"),
+            ActivationFunction::LeakyReLU { alpha } => write!(f, "leaky_relu({})", alpha),
+            ActivationFunction::Elu { alpha }       => write!(f, "elu({})", alpha),
+            ActivationFunction::Gelu                => write!(f, "gelu"),
+            ActivationFunction::Swish               => write!(f, "swish"),
+            ActivationFunction::Selu                => write!(f, "selu"),
+            ActivationFunction::Softplus            => write!(f, "softplus"),
+            ActivationFunction::Mish                => write!(f, "mish"),
+            ActivationFunction::HardSigmoid         => write!(f, "hard_sigmoid"),
+            ActivationFunction::HardSwish           => write!(f, "hard_swish"),
+            ActivationFunction::Custom(name)        => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Parses the `Display` form, case-insensitively.  `leaky_relu` and `elu`
+/// take an optional `alpha` in parentheses (defaults 0.01 and 1.0).  Names
+/// registered with `register_activation` parse as `Custom`.
+impl FromStr for ActivationFunction {
+    type Err = ParseActivationError;
+
+    fn from_str(s: &str) -> Result<ActivationFunction, ParseActivationError> {
+        let s = s.trim().to_ascii_lowercase();
+        let (name, mut arg) = match s.split_once('(') {
+            Some((name, rest)) => {
+                let arg = rest.strip_suffix(')').ok_or_else(|| {
+                    ParseActivationError(format!("activation '{}' is missing a closing parenthesis", s))
+                })?;
+                let alpha = arg.trim().parse::<f64>().ok().filter(|a| a.is_finite()).ok_or_else(|| {
+                    ParseActivationError(format!("activation '{}' has an invalid parameter '{}'", s, arg.trim()))
+                })?;
+                (name.trim(), Some(alpha))
+            }
+            None => (s.as_str(), None),
+        };
+        let activation = match name {
+            "leaky_relu"   => ActivationFunction::LeakyReLU { alpha: arg.take().unwrap_or(DEFAULT_LEAKY_ALPHA) },
+            "elu"          => ActivationFunction::Elu { alpha: arg.take().unwrap_or(DEFAULT_ELU_ALPHA) },
+            "sigmoid"      => ActivationFunction::Sigmoid,
+            "relu"         => ActivationFunction::ReLU,
+            "identity"     => ActivationFunction::Identity,
+            "softmax"      => ActivationFunction::Softmax,
+            "tanh"         => ActivationFunction::Tanh,
+            "gelu"         => ActivationFunction::Gelu,
+            "swish"        => ActivationFunction::Swish,
+            "selu"         => ActivationFunction::Selu,
+            "softplus"     => ActivationFunction::Softplus,
+            "mish"         => ActivationFunction::Mish,
+            "hard_sigmoid" => ActivationFunction::HardSigmoid,
+            "hard_swish"   => ActivationFunction::HardSwish,
+            other if registry::is_activation_registered(other) => ActivationFunction::Custom(other.to_owned()),
+            other => return Err(ParseActivationError(format!("unknown activation '{}'", other))),
+        };
+        // Parameterized variants consumed their argument above.
+        if arg.is_some() {
+            return Err(ParseActivationError(format!("activation '{}' takes no parameter", name)));
         }
+        Ok(activation)
     }
 }
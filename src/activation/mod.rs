@@ -1,3 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod activation;
+pub mod registry;
 
-pub use activation::ActivationFunction;
+pub use activation::{ActivationFunction, ParseActivationError};
+pub use registry::{is_activation_registered, register_activation, ActivationFn};
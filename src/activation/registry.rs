@@ -0,0 +1,78 @@
+//! Process-wide registry of user-defined activations.
+//!
+//! A custom activation is a pair of closures — the function and its
+//! derivative — registered under a name.  `ActivationFunction::Custom(name)`
+//! refers to it, so it can be used from a `LayerSpec`, parsed with `FromStr`
+//! and saved in a model file like a built-in activation.  The closures
+//! themselves are not serialized: register the same name again before
+//! loading or building a network that uses it.  Loading a model whose
+//! activation is not registered fails rather than panicking at the first
+//! prediction.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::activation::activation::ActivationFunction;
+
+/// An element-wise function `f64 → f64` shared across threads.
+pub type ActivationFn = Arc<dyn Fn(f64) -> f64 + Send + Sync>;
+
+struct CustomActivation {
+    function: ActivationFn,
+    derivative: ActivationFn,
+}
+
+fn registry() -> &'static RwLock<HashMap<String, CustomActivation>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, CustomActivation>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers `function` (with its `derivative` w.r.t. the pre-activation)
+/// under `name`, replacing any earlier registration of the same name.
+///
+/// Names are lowercase ASCII letters, digits and `_` and must not shadow a
+/// built-in activation.
+///
+/// # Example
+/// ```no_run
+/// use ferrite_nn::{register_activation, ActivationFunction};
+///
+/// register_activation("cube", |x| x * x * x, |x| 3.0 * x * x).unwrap();
+/// let cube: ActivationFunction = "cube".parse().unwrap();
+/// assert_eq!(cube.function(2.0), 8.0);
+/// ```
+pub fn register_activation<F, D>(name: &str, function: F, derivative: D) -> Result<(), String>
+where
+    F: Fn(f64) -> f64 + Send + Sync + 'static,
+    D: Fn(f64) -> f64 + Send + Sync + 'static,
+{
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_') {
+        return Err(format!(
+            "activation name '{}' must be non-empty lowercase letters, digits and '_'",
+            name
+        ));
+    }
+    if matches!(name.parse::<ActivationFunction>(), Ok(a) if !matches!(a, ActivationFunction::Custom(_))) {
+        return Err(format!("activation name '{}' is already a built-in activation", name));
+    }
+    let custom = CustomActivation { function: Arc::new(function), derivative: Arc::new(derivative) };
+    registry().write().unwrap_or_else(|e| e.into_inner()).insert(name.to_owned(), custom);
+    Ok(())
+}
+
+/// Whether `name` has been registered with `register_activation`.
+pub fn is_activation_registered(name: &str) -> bool {
+    registry().read().unwrap_or_else(|e| e.into_inner()).contains_key(name)
+}
+
+/// Evaluates the registered activation `name` (or its derivative) at `x`.
+///
+/// # Panics
+/// Panics if `name` is not registered.
+pub(crate) fn call(name: &str, x: f64, derivative: bool) -> f64 {
+    let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+    let custom = registry.get(name).unwrap_or_else(|| {
+        panic!("custom activation '{}' is not registered; call register_activation first", name)
+    });
+    if derivative { (custom.derivative)(x) } else { (custom.function)(x) }
+}
@@ -0,0 +1,39 @@
+use std::fmt;
+
+/// Why a configuration file could not be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The document is not valid TOML or does not match the schema
+    /// (wrong types, unknown or missing keys).
+    Parse(String),
+    /// The document parsed but the values are inconsistent; one message per
+    /// problem.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e)        => write!(f, "could not read config: {}", e),
+            ConfigError::Parse(msg)   => write!(f, "invalid config: {}", msg),
+            ConfigError::Invalid(msgs) => write!(f, "invalid config: {}", msgs.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
@@ -0,0 +1,56 @@
+//! Declarative experiment configuration (requires the `toml` feature).
+//!
+//! `NetworkSpec::from_toml` and `TrainConfig::from_toml` read either a
+//! standalone document or the `[network]` / `[train]` table of a combined
+//! experiment file, so one file can describe a whole run:
+//!
+//! ```toml
+//! [network]
+//! name = "iris"
+//! loss = "cross_entropy"
+//!
+//! [[network.layers]]
+//! input_size = 4
+//! size = 16
+//! activation = "ReLU"
+//!
+//! [[network.layers]]
+//! input_size = 16
+//! size = 3
+//! activation = "Softmax"
+//!
+//! [train]
+//! epochs = 200
+//! batch_size = 16
+//! loss = "cross_entropy"
+//! lr_schedule = { cosine_restarts = { cycle_epochs = 50, min_lr = 0.001 } }
+//! ```
+//!
+//! Unknown keys are rejected (catching typos such as `batchsize`), and the parsed values are checked with
+//! `NetworkSpec::validate` / `TrainConfig::validate`.
+
+pub mod error;
+
+pub use error::ConfigError;
+
+/// Returns the `section` table of `doc` if it has one, otherwise the whole
+/// document.
+pub(crate) fn parse_section(doc: &str, section: &str) -> Result<toml::Value, ConfigError> {
+    let mut value: toml::Table = doc.parse()
+        .map_err(|e: toml::de::Error| ConfigError::Parse(e.to_string().trim_end().to_owned()))?;
+    match value.remove(section) {
+        Some(table @ toml::Value::Table(_)) => Ok(table),
+        Some(_) => Err(ConfigError::Parse(format!("`{}` must be a table", section))),
+        None => Ok(toml::Value::Table(value)),
+    }
+}
+
+/// One "unknown key" message for every key of `table` not in `allowed`.
+/// `context` is prefixed to the key, e.g. `"layers[0]."`.
+pub(crate) fn unknown_keys(table: &toml::Value, allowed: &[&str], context: &str) -> Vec<String> {
+    let Some(table) = table.as_table() else { return Vec::new() };
+    table.keys()
+        .filter(|k| !allowed.contains(&k.as_str()))
+        .map(|k| format!("unknown key `{}{}`", context, k))
+        .collect()
+}
@@ -0,0 +1,200 @@
+//! Encoding of categorical (string or integer-coded) feature columns.
+//!
+//! A `CategoricalEncoder` turns a raw row of cells — numbers and category
+//! names mixed — into the numeric input vector of a network: numeric
+//! columns pass through, categorical columns become one-hot or ordinal
+//! features.  The category mapping is serialized with the encoder; stored
+//! in a model's `InputType::Tabular`, it lets `serve::parse_tabular_input`
+//! and the Studio encode raw rows exactly as the training data was encoded.
+
+use std::collections::BTreeSet;
+
+use serde::{Serialize, Deserialize};
+
+/// How a categorical column becomes features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryEncoding {
+    /// One feature per category: 1 for the row's category, 0 elsewhere.
+    #[default]
+    OneHot,
+    /// A single feature holding the category's index.
+    Ordinal,
+}
+
+/// How one raw column is encoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ColumnEncoder {
+    /// Parsed as a number and passed through.
+    Numeric,
+    /// Looked up in `categories` (sorted); see `CategoryEncoding`.
+    Categorical { categories: Vec<String>, encoding: CategoryEncoding },
+}
+
+impl ColumnEncoder {
+    /// Number of features the column produces.
+    pub fn width(&self) -> usize {
+        match self {
+            ColumnEncoder::Categorical { categories, encoding: CategoryEncoding::OneHot } => categories.len(),
+            _ => 1,
+        }
+    }
+}
+
+/// Per-column encoders for rows of raw cells.
+///
+/// Cells are trimmed before use.  A category not seen by `fit` is an error
+/// at `transform` time, as is a non-numeric cell in a numeric column.
+///
+/// # Fields
+/// - `columns` — one encoder per raw column, in column order
+/// - `names`   — raw column names (e.g. the CSV header), if known; used by
+///   `feature_names`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoricalEncoder {
+    pub columns: Vec<ColumnEncoder>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub names: Option<Vec<String>>,
+}
+
+impl CategoricalEncoder {
+    /// Fits an encoder on `rows`: a column is categorical if any of its
+    /// cells is not a number, and its categories are the distinct trimmed
+    /// cells.
+    ///
+    /// # Errors
+    /// Returns an error if `rows` is empty or the rows differ in length.
+    pub fn fit<S: AsRef<str>>(rows: &[Vec<S>], encoding: CategoryEncoding) -> Result<CategoricalEncoder, String> {
+        CategoricalEncoder::fit_with_columns(rows, &[], encoding)
+    }
+
+    /// Same as `fit`, additionally treating the columns listed in
+    /// `categorical` as categorical even when they are numeric (e.g.
+    /// integer-coded categories such as zip codes).
+    ///
+    /// # Errors
+    /// Returns an error if `rows` is empty, the rows differ in length, or an
+    /// index in `categorical` is out of range.
+    pub fn fit_with_columns<S: AsRef<str>>(
+        rows: &[Vec<S>],
+        categorical: &[usize],
+        encoding: CategoryEncoding,
+    ) -> Result<CategoricalEncoder, String> {
+        let width = rows.first().map(Vec::len).ok_or("cannot fit an encoder to no rows")?;
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(format!("row {} has {} cells, expected {}", i + 1, row.len(), width));
+        }
+        if let Some(&column) = categorical.iter().find(|&&c| c >= width) {
+            return Err(format!("column {} is out of range for {} columns", column, width));
+        }
+        let columns = (0..width).map(|j| {
+            let cells = || rows.iter().map(|row| row[j].as_ref().trim());
+            if categorical.contains(&j) || cells().any(|cell| cell.parse::<f64>().is_err()) {
+                let categories: BTreeSet<&str> = cells().collect();
+                ColumnEncoder::Categorical {
+                    categories: categories.into_iter().map(str::to_owned).collect(),
+                    encoding,
+                }
+            } else {
+                ColumnEncoder::Numeric
+            }
+        }).collect();
+        Ok(CategoricalEncoder { columns, names: None })
+    }
+
+    /// Attaches raw column names (see `feature_names`).
+    pub fn with_names(mut self, names: Vec<String>) -> CategoricalEncoder {
+        self.names = Some(names);
+        self
+    }
+
+    /// Number of raw columns a row must have.
+    pub fn n_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Length of every vector produced by `transform`.
+    pub fn width(&self) -> usize {
+        self.columns.iter().map(ColumnEncoder::width).sum()
+    }
+
+    /// Encodes one row of raw cells.
+    ///
+    /// # Errors
+    /// Returns an error if the row has the wrong number of cells, a numeric
+    /// cell does not parse, or a category is unknown.
+    pub fn transform<S: AsRef<str>>(&self, row: &[S]) -> Result<Vec<f64>, String> {
+        if row.len() != self.columns.len() {
+            return Err(format!("expected {} cells, got {}", self.columns.len(), row.len()));
+        }
+        let mut values = Vec::with_capacity(self.width());
+        for (j, (column, cell)) in self.columns.iter().zip(row).enumerate() {
+            let cell = cell.as_ref().trim();
+            match column {
+                ColumnEncoder::Numeric => values.push(cell.parse::<f64>().map_err(|_| {
+                    format!("{}: '{}' is not a valid number", self.column_name(j), cell)
+                })?),
+                ColumnEncoder::Categorical { categories, encoding } => {
+                    let index = categories.binary_search_by(|c| c.as_str().cmp(cell)).map_err(|_| {
+                        format!("{}: unknown category '{}'", self.column_name(j), cell)
+                    })?;
+                    match encoding {
+                        CategoryEncoding::OneHot => {
+                            values.extend((0..categories.len()).map(|k| if k == index { 1.0 } else { 0.0 }));
+                        }
+                        CategoryEncoding::Ordinal => values.push(index as f64),
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Encodes every row (see `transform`).
+    pub fn transform_all<S: AsRef<str>>(&self, rows: &[Vec<S>]) -> Result<Vec<Vec<f64>>, String> {
+        rows.iter().enumerate()
+            .map(|(i, row)| self.transform(row).map_err(|e| format!("row {}: {}", i + 1, e)))
+            .collect()
+    }
+
+    /// Name of every encoded feature, in input order: the column name for
+    /// numeric and ordinal columns, `"name=category"` for one-hot ones.
+    /// Unnamed columns are called `column_<index>`.
+    pub fn feature_names(&self) -> Vec<String> {
+        let mut names = Vec::with_capacity(self.width());
+        for (j, column) in self.columns.iter().enumerate() {
+            let name = self.column_name(j);
+            match column {
+                ColumnEncoder::Categorical { categories, encoding: CategoryEncoding::OneHot } => {
+                    names.extend(categories.iter().map(|c| format!("{}={}", name, c)));
+                }
+                _ => names.push(name),
+            }
+        }
+        names
+    }
+
+    /// Serializes the encoder to a pretty-printed JSON file at `path`.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Deserializes an encoder from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<CategoricalEncoder> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(std::io::Error::other)
+    }
+
+    fn column_name(&self, j: usize) -> String {
+        self.names.as_ref()
+            .and_then(|names| names.get(j))
+            .cloned()
+            .unwrap_or_else(|| format!("column_{}", j))
+    }
+}
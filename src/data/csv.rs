@@ -0,0 +1,512 @@
+//! CSV dataset parsing.
+//!
+//! Supported format:
+//! - UTF-8, comma-separated
+//! - Optional header row (auto-detected by default: first row is a header if
+//!   it contains any non-numeric, non-empty cell; see `CsvHeader`)
+//! - Double-quoted fields with embedded commas and `""` escapes; a quoted
+//!   field cannot span lines, and an unterminated quote is an error
+//! - A leading UTF-8 byte-order mark is ignored
+//!
+//! Label modes:
+//! - `ClassIndex` — the last column is an integer class index (0-based);
+//!   the parser one-hot-encodes it into a vector of length `n_classes`.
+//! - `OneHot`     — the last `n_classes` columns are floats forming the label.
+//!
+//! `parse_csv` reads a complete in-memory file (`parse_csv_categorical`
+//! one with non-numeric feature columns); `CsvReader` streams rows
+//! from any `Read` source, and `CsvDataset` serves rows of a seekable
+//! source (e.g. a file too large for memory) through the `Dataset` trait.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::data::categorical::{CategoricalEncoder, CategoryEncoding};
+use crate::data::dataset::Dataset;
+use crate::data::Samples;
+
+// ---------------------------------------------------------------------------
+// Public types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelMode {
+    /// Last column is an integer class index; one-hot encode to `n_classes`.
+    ClassIndex { n_classes: usize },
+    /// Last `n_label_cols` columns are the label vector.
+    OneHot { n_label_cols: usize },
+}
+
+/// Whether the first row of a CSV file holds column names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvHeader {
+    /// A header if any cell of the first row is non-numeric and non-empty.
+    #[default]
+    Auto,
+    /// The first row is always a header.
+    Present,
+    /// Every row is data; a non-numeric first row is a parse error.
+    Absent,
+}
+
+#[derive(Debug)]
+pub struct CsvParseError(pub String);
+
+impl std::fmt::Display for CsvParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CsvParseError {}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// Parses CSV bytes into (inputs, labels).
+///
+/// # Arguments
+/// - `data`       — raw CSV bytes (UTF-8)
+/// - `label_mode` — how to interpret the label column(s)
+///
+/// # Returns
+/// `(inputs, labels)` where each is a `Vec<Vec<f64>>` of equal length.
+pub fn parse_csv(
+    data: &[u8],
+    label_mode: LabelMode,
+) -> Result<Samples, CsvParseError> {
+    parse_csv_with_header(data, label_mode, CsvHeader::Auto)
+}
+
+/// Same as `parse_csv`, with the header row given explicitly instead of
+/// auto-detected.
+pub fn parse_csv_with_header(
+    data: &[u8],
+    label_mode: LabelMode,
+    header: CsvHeader,
+) -> Result<Samples, CsvParseError> {
+    let (inputs, labels): Samples = CsvReader::with_header(data, label_mode, header)?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    if inputs.is_empty() {
+        return Err(CsvParseError("CSV contains no data rows after parsing".into()));
+    }
+    Ok((inputs, labels))
+}
+
+/// Names of the feature columns, taken from the header row.
+///
+/// Returns `None` when the CSV has no header (see the module docs for how
+/// one is detected) or the header has fewer columns than `label_mode`
+/// needs.  The label column(s) are left out, so the result lines up with
+/// the inputs returned by `parse_csv`.
+pub fn csv_feature_names(data: &[u8], label_mode: LabelMode) -> Option<Vec<String>> {
+    CsvReader::new(data, label_mode).ok()?.feature_names()
+}
+
+/// Parses a CSV whose feature columns may hold category names (or
+/// integer-coded categories listed in `categorical`), encoding them with a
+/// `CategoricalEncoder` fitted on the file.
+///
+/// # Arguments
+/// - `data`        — raw CSV bytes (UTF-8)
+/// - `label_mode`  — how to interpret the label column(s)
+/// - `has_header`  — whether the first row holds column names; it cannot
+///   be auto-detected here, as data rows contain text as well
+/// - `categorical` — feature columns to treat as categorical even though
+///   they are numeric; non-numeric columns always are
+/// - `encoding`    — one-hot or ordinal features for categorical columns
+///
+/// # Returns
+/// The encoded `(inputs, labels)` and the encoder, to be stored with the
+/// model (`InputType::Tabular`) so raw rows can be encoded at inference.
+/// The encoder carries the header's feature column names, if any.
+pub fn parse_csv_categorical(
+    data: &[u8],
+    label_mode: LabelMode,
+    has_header: bool,
+    categorical: &[usize],
+    encoding: CategoryEncoding,
+) -> Result<(Samples, CategoricalEncoder), CsvParseError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| CsvParseError("CSV file is not valid UTF-8".into()))?;
+    let mut lines = text.lines();
+    let header = match has_header {
+        true => lines.next().map(|line| parse_csv_row(trim_line(line), 0)).transpose()?,
+        false => None,
+    };
+
+    let mut feature_rows: Vec<Vec<String>> = Vec::new();
+    let mut labels: Vec<Vec<f64>> = Vec::new();
+    for (row_idx, line) in lines.enumerate() {
+        let line = trim_line(line);
+        if line.is_empty() {
+            continue;
+        }
+        let cells = parse_csv_row(line, row_idx + 1)?;
+        let (feature_cells, label) = split_label(&cells, row_idx + 1, label_mode)?;
+        feature_rows.push(feature_cells.to_vec());
+        labels.push(label);
+    }
+    if feature_rows.is_empty() {
+        return Err(CsvParseError("CSV contains no data rows after parsing".into()));
+    }
+
+    let mut encoder = CategoricalEncoder::fit_with_columns(&feature_rows, categorical, encoding)
+        .map_err(CsvParseError)?;
+    if let Some(header) = header {
+        let names = header.iter().take(encoder.n_columns()).map(|c| c.trim().to_owned()).collect();
+        encoder = encoder.with_names(names);
+    }
+    let inputs = encoder.transform_all(&feature_rows).map_err(CsvParseError)?;
+    Ok(((inputs, labels), encoder))
+}
+
+// ---------------------------------------------------------------------------
+// Streaming
+// ---------------------------------------------------------------------------
+
+/// Reads samples from any `Read` source one row at a time, so the CSV text
+/// is never held in memory as a whole.  Yields the same samples, in the same
+/// order, as `parse_csv` on the full text.
+///
+/// ```no_run
+/// use std::fs::File;
+/// use ferrite_nn::data::csv::{CsvReader, LabelMode};
+///
+/// let rows = CsvReader::new(File::open("big.csv").unwrap(), LabelMode::ClassIndex { n_classes: 3 }).unwrap();
+/// for sample in rows {
+///     let (input, label) = sample.unwrap();
+/// }
+/// ```
+pub struct CsvReader<R> {
+    reader: BufReader<R>,
+    label_mode: LabelMode,
+    header: Option<String>,
+    /// The first line, with its byte offset, when it is not a header.
+    pending: Option<(u64, String)>,
+    /// Byte offset of the next line to read.
+    offset: u64,
+    /// 1-based number of the last line read, not counting the header.
+    row: usize,
+    n_features: Option<usize>,
+}
+
+/// A sample with the byte offset of the line it was read from.
+type OffsetSample = (u64, (Vec<f64>, Vec<f64>));
+
+impl<R: Read> CsvReader<R> {
+    /// Reads the first line of `source` to detect a header.
+    pub fn new(source: R, label_mode: LabelMode) -> Result<CsvReader<R>, CsvParseError> {
+        CsvReader::with_header(source, label_mode, CsvHeader::Auto)
+    }
+
+    /// Same as `new`, with the header row given explicitly.
+    pub fn with_header(source: R, label_mode: LabelMode, header: CsvHeader) -> Result<CsvReader<R>, CsvParseError> {
+        let mut rows = CsvReader {
+            reader: BufReader::new(source),
+            label_mode,
+            header: None,
+            pending: None,
+            offset: 0,
+            row: 0,
+            n_features: None,
+        };
+        if let Some((offset, first)) = rows.read_line()? {
+            let is_header = match header {
+                CsvHeader::Auto => is_header(&first),
+                CsvHeader::Present => true,
+                CsvHeader::Absent => false,
+            };
+            if is_header {
+                rows.header = Some(first);
+            } else {
+                rows.pending = Some((offset, first));
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Names of the feature columns; see `csv_feature_names`.
+    pub fn feature_names(&self) -> Option<Vec<String>> {
+        let cells = parse_csv_row(trim_line(self.header.as_deref()?), 0).ok()?;
+        let n_label_cols = match self.label_mode {
+            LabelMode::ClassIndex { .. } => 1,
+            LabelMode::OneHot { n_label_cols } => n_label_cols,
+        };
+        if cells.len() <= n_label_cols {
+            return None;
+        }
+        let n_features = cells.len() - n_label_cols;
+        Some(cells.into_iter().take(n_features).map(|c| c.trim().to_owned()).collect())
+    }
+
+    /// The next sample with the byte offset of its line, skipping blank
+    /// lines; `None` at the end of the input.
+    fn next_row(&mut self) -> Result<Option<OffsetSample>, CsvParseError> {
+        loop {
+            let (offset, line) = match self.pending.take() {
+                Some(first) => first,
+                None => match self.read_line()? {
+                    Some(next) => next,
+                    None => return Ok(None),
+                },
+            };
+            self.row += 1;
+            let line = trim_line(&line);
+            if line.is_empty() {
+                continue;
+            }
+            let sample = parse_sample(&parse_csv_row(line, self.row)?, self.row, self.label_mode)?;
+            match self.n_features {
+                None => self.n_features = Some(sample.0.len()),
+                Some(n) if n != sample.0.len() => {
+                    return Err(CsvParseError(format!(
+                        "Row {}: feature count {} does not match first row's {}",
+                        self.row, sample.0.len(), n
+                    )));
+                }
+                Some(_) => {}
+            }
+            return Ok(Some((offset, sample)));
+        }
+    }
+
+    /// The next raw line with its byte offset; `None` at the end of the input.
+    fn read_line(&mut self) -> Result<Option<(u64, String)>, CsvParseError> {
+        let mut line = String::new();
+        let n = self.reader.read_line(&mut line).map_err(read_error)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        let offset = self.offset;
+        self.offset += n as u64;
+        Ok(Some((offset, line)))
+    }
+}
+
+impl<R: Read> Iterator for CsvReader<R> {
+    type Item = Result<(Vec<f64>, Vec<f64>), CsvParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_row().map(|row| row.map(|(_, sample)| sample)).transpose()
+    }
+}
+
+/// A CSV file (or any seekable source) used as a `Dataset` without loading
+/// it into memory.
+///
+/// `new` reads the source once, checking every row and recording where each
+/// one starts (8 bytes per row); `get` then seeks to the row and parses it
+/// again.  Larger-than-memory datasets can thus be passed straight to
+/// `train_loop`.
+///
+/// `get` panics if the source can no longer be read, or no longer parses,
+/// at a recorded row — e.g. when the file was modified after indexing.
+pub struct CsvDataset<R> {
+    reader: Mutex<BufReader<R>>,
+    offsets: Vec<u64>,
+    label_mode: LabelMode,
+    feature_names: Option<Vec<String>>,
+}
+
+impl CsvDataset<File> {
+    /// Opens and indexes the CSV file at `path`.
+    pub fn open(path: impl AsRef<Path>, label_mode: LabelMode) -> Result<CsvDataset<File>, CsvParseError> {
+        let file = File::open(path.as_ref()).map_err(|e| {
+            CsvParseError(format!("cannot open {}: {}", path.as_ref().display(), e))
+        })?;
+        CsvDataset::new(file, label_mode)
+    }
+}
+
+impl<R: Read + Seek> CsvDataset<R> {
+    /// Indexes `source` (see the type docs).  Fails on the first row
+    /// `parse_csv` would reject, or if there are no data rows.
+    pub fn new(source: R, label_mode: LabelMode) -> Result<CsvDataset<R>, CsvParseError> {
+        CsvDataset::from_reader(CsvReader::new(source, label_mode)?)
+    }
+
+    /// Indexes the rows `rows` has not yielded yet, e.g. a reader created
+    /// with `CsvReader::with_header`.
+    pub fn from_reader(mut rows: CsvReader<R>) -> Result<CsvDataset<R>, CsvParseError> {
+        let mut offsets = Vec::new();
+        while let Some((offset, _)) = rows.next_row()? {
+            offsets.push(offset);
+        }
+        if offsets.is_empty() {
+            return Err(CsvParseError("CSV contains no data rows after parsing".into()));
+        }
+        Ok(CsvDataset {
+            feature_names: rows.feature_names(),
+            reader: Mutex::new(rows.reader),
+            offsets,
+            label_mode: rows.label_mode,
+        })
+    }
+
+    /// Names of the feature columns; see `csv_feature_names`.
+    pub fn feature_names(&self) -> Option<&[String]> {
+        self.feature_names.as_deref()
+    }
+}
+
+impl<R: Read + Seek> Dataset for CsvDataset<R> {
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn get(&self, index: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut reader = self.reader.lock().unwrap();
+        let mut line = String::new();
+        reader.seek(SeekFrom::Start(self.offsets[index]))
+            .and_then(|_| reader.read_line(&mut line))
+            .unwrap_or_else(|e| panic!("CSV row {} could not be read: {}", index, e));
+        parse_csv_row(trim_line(&line), index + 1)
+            .and_then(|cells| parse_sample(&cells, index + 1, self.label_mode))
+            .unwrap_or_else(|e| panic!("CSV changed since it was indexed: {}", e))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+/// Returns `true` if the row looks like a header (any cell non-numeric).
+fn is_header(line: &str) -> bool {
+    let Ok(cells) = parse_csv_row(trim_line(line), 0) else {
+        return false;
+    };
+    cells.iter().any(|c| {
+        let t = c.trim();
+        !t.is_empty() && t.parse::<f64>().is_err()
+    })
+}
+
+/// Splits the cells of data row `row_num` into features and label.
+fn parse_sample(
+    cells: &[String],
+    row_num: usize,
+    label_mode: LabelMode,
+) -> Result<(Vec<f64>, Vec<f64>), CsvParseError> {
+    let (feature_cells, label) = split_label(cells, row_num, label_mode)?;
+    Ok((parse_floats(feature_cells, row_num)?, label))
+}
+
+/// The feature cells of data row `row_num` and its parsed label.
+fn split_label(
+    cells: &[String],
+    row_num: usize,
+    label_mode: LabelMode,
+) -> Result<(&[String], Vec<f64>), CsvParseError> {
+    match label_mode {
+        LabelMode::ClassIndex { n_classes } => {
+            if cells.len() < 2 {
+                return Err(CsvParseError(format!(
+                    "Row {}: expected at least 2 columns (features + class index), got {}",
+                    row_num,
+                    cells.len()
+                )));
+            }
+            let feature_cells = &cells[..cells.len() - 1];
+            let label_cell    = cells.last().unwrap();
+
+            let class_idx: usize = label_cell.trim().parse::<usize>().map_err(|_| {
+                CsvParseError(format!(
+                    "Row {}: class index '{}' is not a non-negative integer",
+                    row_num,
+                    label_cell
+                ))
+            })?;
+            if class_idx >= n_classes {
+                return Err(CsvParseError(format!(
+                    "Row {}: class index {} >= n_classes {}",
+                    row_num, class_idx, n_classes
+                )));
+            }
+            let mut one_hot = vec![0.0f64; n_classes];
+            one_hot[class_idx] = 1.0;
+            Ok((feature_cells, one_hot))
+        }
+        LabelMode::OneHot { n_label_cols } => {
+            if cells.len() < n_label_cols + 1 {
+                return Err(CsvParseError(format!(
+                    "Row {}: expected at least {} columns, got {}",
+                    row_num,
+                    n_label_cols + 1,
+                    cells.len()
+                )));
+            }
+            let split = cells.len() - n_label_cols;
+            let lbls  = parse_floats(&cells[split..], row_num)?;
+            Ok((&cells[..split], lbls))
+        }
+    }
+}
+
+fn read_error(e: std::io::Error) -> CsvParseError {
+    if e.kind() == ErrorKind::InvalidData {
+        CsvParseError("CSV file is not valid UTF-8".into())
+    } else {
+        CsvParseError(format!("CSV read error: {}", e))
+    }
+}
+
+/// `line` without the line break, surrounding whitespace and a leading
+/// byte-order mark.
+fn trim_line(line: &str) -> &str {
+    line.trim_start_matches('\u{feff}').trim()
+}
+
+/// Parses a single CSV row, handling double-quoted fields.  `row_num` only
+/// labels the error for an unterminated quote.
+fn parse_csv_row(line: &str, row_num: usize) -> Result<Vec<String>, CsvParseError> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                if in_quotes && i + 1 < chars.len() && chars[i + 1] == '"' {
+                    // Escaped quote inside quoted field.
+                    current.push('"');
+                    i += 2;
+                    continue;
+                }
+                in_quotes = !in_quotes;
+            }
+            ',' if !in_quotes => {
+                fields.push(current.clone());
+                current.clear();
+            }
+            c => current.push(c),
+        }
+        i += 1;
+    }
+    if in_quotes {
+        return Err(CsvParseError(format!("Row {}: unterminated quoted field", row_num)));
+    }
+    fields.push(current);
+    Ok(fields)
+}
+
+/// Parses a slice of string cells as `f64`, returning an error with row info on failure.
+fn parse_floats(cells: &[String], row_num: usize) -> Result<Vec<f64>, CsvParseError> {
+    cells.iter()
+        .map(|c| {
+            c.trim().parse::<f64>().map_err(|_| {
+                CsvParseError(format!(
+                    "Row {}: '{}' is not a valid number",
+                    row_num, c
+                ))
+            })
+        })
+        .collect()
+}
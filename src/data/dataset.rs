@@ -0,0 +1,225 @@
+use crate::data::csv::{parse_csv, CsvParseError, LabelMode};
+use crate::data::idx::parse_idx_pair;
+use crate::data::split::split_sizes;
+
+// ---------------------------------------------------------------------------
+// Dataset
+// ---------------------------------------------------------------------------
+
+/// Indexed `(input, label)` samples, the source `train_loop` and
+/// `DataLoader` read from.
+///
+/// Samples are fetched one at a time, so an implementation may load, decode
+/// or generate them on demand instead of holding the whole set in memory.
+/// In-memory data is covered by `Split` and by any pair of sample slices,
+/// e.g. `(&inputs, &labels)`.
+pub trait Dataset {
+    /// Number of samples.
+    fn len(&self) -> usize;
+
+    /// Sample `index`, in `0..len()`.
+    ///
+    /// # Panics
+    /// May panic if `index` is out of range.
+    fn get(&self, index: usize) -> (Vec<f64>, Vec<f64>);
+
+    /// Number of labels, for implementations that store them apart from the
+    /// inputs.  Training rejects a dataset where this differs from `len()`
+    /// with `TrainError::LengthMismatch` before fetching any sample.
+    fn labels_len(&self) -> usize {
+        self.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Parallel input and label slices; the labels must be as long as the
+/// inputs (`train_loop` checks this through `labels_len`).
+impl<I, L> Dataset for (I, L)
+where
+    I: AsRef<[Vec<f64>]>,
+    L: AsRef<[Vec<f64>]>,
+{
+    fn len(&self) -> usize {
+        self.0.as_ref().len()
+    }
+
+    fn get(&self, index: usize) -> (Vec<f64>, Vec<f64>) {
+        (self.0.as_ref()[index].clone(), self.1.as_ref()[index].clone())
+    }
+
+    fn labels_len(&self) -> usize {
+        self.1.as_ref().len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Split
+// ---------------------------------------------------------------------------
+
+/// One partition of a dataset: parallel input and label vectors.
+#[derive(Debug, Clone, Default)]
+pub struct Split {
+    pub inputs: Vec<Vec<f64>>,
+    pub labels: Vec<Vec<f64>>,
+}
+
+impl Split {
+    /// # Panics
+    /// Panics if `inputs` and `labels` differ in length.
+    pub fn new(inputs: Vec<Vec<f64>>, labels: Vec<Vec<f64>>) -> Split {
+        assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+        Split { inputs, labels }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Iterates over `(input, label)` pairs in stored order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<f64>, &Vec<f64>)> {
+        self.inputs.iter().zip(self.labels.iter())
+    }
+}
+
+impl Dataset for Split {
+    fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn get(&self, index: usize) -> (Vec<f64>, Vec<f64>) {
+        (self.inputs[index].clone(), self.labels[index].clone())
+    }
+
+    fn labels_len(&self) -> usize {
+        self.labels.len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SplitDataset
+// ---------------------------------------------------------------------------
+
+/// A dataset partitioned into training, validation and test sets.
+///
+/// The constructors split **contiguously and without shuffling**: the first
+/// rows become `train`, followed by `val`, then `test`.  Shuffle the samples
+/// beforehand if the source is ordered by class.  Splits that were not
+/// requested are empty.
+#[derive(Debug, Clone, Default)]
+pub struct SplitDataset {
+    pub train: Split,
+    pub val:   Split,
+    pub test:  Split,
+}
+
+impl SplitDataset {
+    /// Splits in-memory samples.
+    ///
+    /// # Arguments
+    /// - `inputs`        — all samples
+    /// - `labels`        — corresponding targets, same length as `inputs`
+    /// - `val_fraction`  — share of samples reserved for validation, in `[0, 1]`
+    /// - `test_fraction` — share of samples reserved for testing, in `[0, 1]`
+    ///
+    /// Split sizes are rounded to the nearest sample.  See
+    /// `data::split::split_samples` for shuffled and stratified splits.
+    ///
+    /// # Panics
+    /// Panics if the lengths differ, a fraction is outside `[0, 1]`, or the
+    /// two fractions sum to more than 1.
+    pub fn from_samples(
+        inputs: Vec<Vec<f64>>,
+        labels: Vec<Vec<f64>>,
+        val_fraction: f64,
+        test_fraction: f64,
+    ) -> SplitDataset {
+        assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+        check_fractions(val_fraction, test_fraction);
+
+        let (train_n, val_n, _) = split_sizes(inputs.len(), val_fraction, test_fraction);
+
+        let mut inputs = inputs;
+        let mut labels = labels;
+        let test_inputs = inputs.split_off(train_n + val_n);
+        let test_labels = labels.split_off(train_n + val_n);
+        let val_inputs  = inputs.split_off(train_n);
+        let val_labels  = labels.split_off(train_n);
+
+        SplitDataset {
+            train: Split::new(inputs, labels),
+            val:   Split::new(val_inputs, val_labels),
+            test:  Split::new(test_inputs, test_labels),
+        }
+    }
+
+    /// Parses CSV bytes (see `data::csv::parse_csv`) and splits the rows.
+    pub fn from_csv(
+        data: &[u8],
+        label_mode: LabelMode,
+        val_fraction: f64,
+        test_fraction: f64,
+    ) -> Result<SplitDataset, CsvParseError> {
+        let (inputs, labels) = parse_csv(data, label_mode)?;
+        Ok(SplitDataset::from_samples(inputs, labels, val_fraction, test_fraction))
+    }
+
+    /// Parses an IDX image/label pair (see `data::idx::parse_idx_pair`) and
+    /// splits the samples.
+    pub fn from_idx(
+        image_bytes: &[u8],
+        label_bytes: &[u8],
+        n_classes: usize,
+        val_fraction: f64,
+        test_fraction: f64,
+    ) -> Result<SplitDataset, String> {
+        let (inputs, labels) = parse_idx_pair(image_bytes, label_bytes, n_classes)?;
+        Ok(SplitDataset::from_samples(inputs, labels, val_fraction, test_fraction))
+    }
+
+    /// Total number of samples across all three splits.
+    pub fn len(&self) -> usize {
+        self.train.len() + self.val.len() + self.test.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Width of the input vectors (0 if the dataset is empty).
+    pub fn feature_count(&self) -> usize {
+        self.first_sample().map(|(i, _)| i.len()).unwrap_or(0)
+    }
+
+    /// Width of the label vectors (0 if the dataset is empty).
+    pub fn label_count(&self) -> usize {
+        self.first_sample().map(|(_, l)| l.len()).unwrap_or(0)
+    }
+
+    fn first_sample(&self) -> Option<(&Vec<f64>, &Vec<f64>)> {
+        self.train.iter().next()
+            .or_else(|| self.val.iter().next())
+            .or_else(|| self.test.iter().next())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// # Panics
+/// Panics if a fraction is outside `[0, 1]` or the two sum to more than 1.
+pub(crate) fn check_fractions(val_fraction: f64, test_fraction: f64) {
+    assert!((0.0..=1.0).contains(&val_fraction), "val_fraction must lie in [0, 1]");
+    assert!((0.0..=1.0).contains(&test_fraction), "test_fraction must lie in [0, 1]");
+    assert!(
+        val_fraction + test_fraction <= 1.0,
+        "val_fraction + test_fraction must not exceed 1"
+    );
+}
@@ -1,3 +1,8 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+use crate::data::Samples;
+
 /// Parse a pair of IDX binary files (image + label) as used by MNIST and its
 /// derivatives (Fashion-MNIST, EMNIST, …) into `(inputs, labels)` suitable for
 /// direct use with Ferrite's training loop.
@@ -22,6 +27,10 @@
 /// bytes  8..:   N bytes, each a class index in [0, n_classes)
 /// ```
 ///
+/// Either file may be gzip-compressed (`train-images-idx3-ubyte.gz`, as
+/// MNIST is distributed); compression is detected from the leading bytes
+/// and needs the `gzip` feature.
+///
 /// # Returns
 /// `(inputs, labels)` where
 /// - `inputs[i]`  is a `Vec<f64>` of length `rows * cols`, each pixel divided
@@ -31,7 +40,10 @@ pub fn parse_idx_pair(
     image_bytes: &[u8],
     label_bytes: &[u8],
     n_classes: usize,
-) -> Result<(Vec<Vec<f64>>, Vec<Vec<f64>>), String> {
+) -> Result<Samples, String> {
+    let image_bytes = &*decompress(image_bytes, "image")?;
+    let label_bytes = &*decompress(label_bytes, "label")?;
+
     // ── Image file validation ───────────────────────────────────────────────
 
     if image_bytes.len() < 16 {
@@ -178,3 +190,45 @@ pub fn parse_idx_pair(
 
     Ok((inputs, labels))
 }
+
+/// Reads an IDX image/label file pair from disk and parses it with
+/// `parse_idx_pair` (so either file may be gzip-compressed).
+pub fn load_idx_pair(
+    image_path: impl AsRef<Path>,
+    label_path: impl AsRef<Path>,
+    n_classes: usize,
+) -> Result<Samples, String> {
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| format!("Cannot read IDX file '{}': {}", path.display(), e))
+    };
+    parse_idx_pair(&read(image_path.as_ref())?, &read(label_path.as_ref())?, n_classes)
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+/// `bytes`, gunzipped if they start with the gzip magic number.  `kind`
+/// ("image" / "label") only labels the error message.
+fn decompress<'a>(bytes: &'a [u8], kind: &str) -> Result<Cow<'a, [u8]>, String> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    gunzip(bytes).map(Cow::Owned).map_err(|e| format!("IDX {} file: {}", kind, e))
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(bytes)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("invalid gzip data: {}", e))?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    Err("gzip-compressed; decompress it first or enable the `gzip` feature".into())
+}
@@ -0,0 +1,135 @@
+//! Lazy mini-batching over a `Dataset`.
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::data::dataset::Dataset;
+use crate::data::Samples;
+
+/// Splits a `Dataset` into mini-batches, shuffled each epoch by default.
+///
+/// Only the visiting order is built up front; the samples of a batch are
+/// fetched from the dataset when the batch is reached, so a lazily loaded
+/// dataset is never held in memory as a whole.
+///
+/// With `with_weights`, each pass instead draws as many samples as the
+/// dataset holds, with replacement and in proportion to per-sample weights,
+/// e.g. `balanced_sample_weights` to see every class equally often.
+///
+/// ```no_run
+/// use ferrite_nn::{DataLoader, Split};
+///
+/// let train = Split::new(vec![vec![0.0], vec![1.0]], vec![vec![1.0], vec![0.0]]);
+/// let loader = DataLoader::new(&train, 32);
+/// for (inputs, labels) in loader.batches_seeded(7) {
+///     assert_eq!(inputs.len(), labels.len());
+/// }
+/// ```
+pub struct DataLoader<'a> {
+    dataset: &'a dyn Dataset,
+    batch_size: usize,
+    shuffle: bool,
+    weights: Option<WeightedIndex<f64>>,
+}
+
+impl<'a> DataLoader<'a> {
+    /// A shuffling loader yielding batches of `batch_size` samples (the last
+    /// one may be smaller).
+    ///
+    /// # Panics
+    /// Panics if `batch_size == 0`.
+    pub fn new(dataset: &'a dyn Dataset, batch_size: usize) -> DataLoader<'a> {
+        assert!(batch_size > 0, "batch_size must be at least 1");
+        DataLoader { dataset, batch_size, shuffle: true, weights: None }
+    }
+
+    /// With `false`, batches follow the dataset's own order.  Ignored by a
+    /// weighted loader.
+    pub fn with_shuffle(mut self, shuffle: bool) -> DataLoader<'a> {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Weighted sampling: every pass draws `dataset.len()` samples with
+    /// replacement, sample `i` with probability proportional to `weights[i]`.
+    ///
+    /// # Panics
+    /// Panics if `weights` does not hold one entry per sample, or if a
+    /// weight is negative or not finite, or all of them are 0.
+    pub fn with_weights(mut self, weights: &[f64]) -> DataLoader<'a> {
+        assert_eq!(weights.len(), self.dataset.len(), "expected one weight per sample");
+        assert!(weights.iter().all(|w| w.is_finite()), "sample weights must be finite");
+        self.weights = Some(WeightedIndex::new(weights).unwrap_or_else(|e| panic!("invalid sample weights: {}", e)));
+        self
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Number of batches per pass over the dataset.
+    pub fn len(&self) -> usize {
+        self.dataset.len().div_ceil(self.batch_size)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dataset.is_empty()
+    }
+
+    /// One pass over the dataset, shuffled with fresh entropy.
+    pub fn batches(&self) -> Batches<'a> {
+        self.batches_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Same as `batches`, but shuffled reproducibly from `seed`.
+    pub fn batches_seeded(&self, seed: u64) -> Batches<'a> {
+        self.batches_with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Same as `batches`, drawing the shuffle from `rng`.
+    pub fn batches_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Batches<'a> {
+        let order: Vec<usize> = match &self.weights {
+            Some(weights) => (0..self.dataset.len()).map(|_| weights.sample(rng)).collect(),
+            None => {
+                let mut order: Vec<usize> = (0..self.dataset.len()).collect();
+                if self.shuffle {
+                    order.shuffle(rng);
+                }
+                order
+            }
+        };
+        Batches { dataset: self.dataset, order, batch_size: self.batch_size, next: 0 }
+    }
+}
+
+/// Iterator over the `(inputs, labels)` mini-batches of one pass; see
+/// `DataLoader`.
+pub struct Batches<'a> {
+    dataset: &'a dyn Dataset,
+    order: Vec<usize>,
+    batch_size: usize,
+    next: usize,
+}
+
+impl Iterator for Batches<'_> {
+    type Item = Samples;
+
+    fn next(&mut self) -> Option<Samples> {
+        if self.next >= self.order.len() {
+            return None;
+        }
+        let end = (self.next + self.batch_size).min(self.order.len());
+        let batch = self.order[self.next..end].iter().map(|&i| self.dataset.get(i)).unzip();
+        self.next = end;
+        Some(batch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.order.len() - self.next).div_ceil(self.batch_size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Batches<'_> {}
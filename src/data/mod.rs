@@ -0,0 +1,22 @@
+pub mod dataset;
+pub mod categorical;
+pub mod csv;
+pub mod idx;
+pub mod loader;
+pub mod resample;
+pub mod scaling;
+pub mod split;
+pub mod window;
+
+pub use dataset::{Dataset, SplitDataset, Split};
+pub use loader::{DataLoader, Batches};
+pub use categorical::{CategoricalEncoder, CategoryEncoding, ColumnEncoder};
+pub use csv::{parse_csv, parse_csv_with_header, parse_csv_categorical, csv_feature_names, CsvReader, CsvDataset, CsvHeader, LabelMode, CsvParseError};
+pub use idx::{parse_idx_pair, load_idx_pair};
+pub use resample::{oversample, oversample_seeded, oversample_with_rng, undersample, undersample_seeded, undersample_with_rng, balanced_sample_weights};
+pub use scaling::{Scaling, ScalingMethod, StandardScaler, MinMaxScaler};
+pub use split::{split_samples, split_samples_seeded, split_samples_with_rng, SplitStrategy};
+pub use window::{windows, windows_normalized, WindowScale};
+
+/// Parallel `(inputs, labels)` vectors produced by the dataset parsers.
+pub type Samples = (Vec<Vec<f64>>, Vec<Vec<f64>>);
@@ -0,0 +1,102 @@
+//! Rebalancing of skewed classification data.
+//!
+//! `oversample` repeats samples of the smaller classes and `undersample`
+//! drops samples of the larger ones until every class is equally frequent;
+//! `balanced_sample_weights` gives the per-sample weights that achieve the
+//! same balance without changing the data, for `DataLoader::with_weights`
+//! or `TrainConfig::sample_weights`.  A sample's class is the argmax of its
+//! (one-hot) label, or, for single-value labels, whether the value is at
+//! least 0.5, as in `SplitStrategy::Stratified`.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::data::split::class_groups;
+use crate::data::Samples;
+
+/// Oversamples with fresh entropy: every class is topped up with samples
+/// drawn from it with replacement until it is as frequent as the largest
+/// class.  The original samples come first, in their original order,
+/// followed by the drawn copies.
+///
+/// # Panics
+/// Panics if `inputs` and `labels` differ in length.
+pub fn oversample(inputs: &[Vec<f64>], labels: &[Vec<f64>]) -> Samples {
+    oversample_with_rng(inputs, labels, &mut rand::thread_rng())
+}
+
+/// Same as `oversample`, but reproducible: the draws are seeded with `seed`.
+pub fn oversample_seeded(inputs: &[Vec<f64>], labels: &[Vec<f64>], seed: u64) -> Samples {
+    oversample_with_rng(inputs, labels, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Same as `oversample`, drawing from `rng`.
+pub fn oversample_with_rng<R: Rng + ?Sized>(
+    inputs: &[Vec<f64>],
+    labels: &[Vec<f64>],
+    rng: &mut R,
+) -> Samples {
+    assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+    let groups = class_groups(labels);
+    let target = groups.iter().map(Vec::len).max().unwrap_or(0);
+    let mut order: Vec<usize> = (0..inputs.len()).collect();
+    for group in &groups {
+        order.extend((group.len()..target).map(|_| group[rng.gen_range(0..group.len())]));
+    }
+    gather(inputs, labels, &order)
+}
+
+/// Undersamples with fresh entropy: every class keeps a random subset,
+/// without replacement, as large as the smallest class.  Kept samples stay
+/// in their original order.
+///
+/// # Panics
+/// Panics if `inputs` and `labels` differ in length.
+pub fn undersample(inputs: &[Vec<f64>], labels: &[Vec<f64>]) -> Samples {
+    undersample_with_rng(inputs, labels, &mut rand::thread_rng())
+}
+
+/// Same as `undersample`, but reproducible: the subsets are drawn from
+/// `seed`.
+pub fn undersample_seeded(inputs: &[Vec<f64>], labels: &[Vec<f64>], seed: u64) -> Samples {
+    undersample_with_rng(inputs, labels, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Same as `undersample`, drawing from `rng`.
+pub fn undersample_with_rng<R: Rng + ?Sized>(
+    inputs: &[Vec<f64>],
+    labels: &[Vec<f64>],
+    rng: &mut R,
+) -> Samples {
+    assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+    let groups = class_groups(labels);
+    let target = groups.iter().map(Vec::len).min().unwrap_or(0);
+    let mut order: Vec<usize> = groups
+        .iter()
+        .flat_map(|group| group.choose_multiple(rng, target).copied())
+        .collect();
+    order.sort_unstable();
+    gather(inputs, labels, &order)
+}
+
+/// Per-sample weights `n_samples / (n_classes · count[class])`: every class
+/// carries the same total weight and the weights average 1.  Pass them to
+/// `DataLoader::with_weights` to draw classes equally often, or to
+/// `TrainConfig::sample_weights` to weight the loss instead.
+pub fn balanced_sample_weights(labels: &[Vec<f64>]) -> Vec<f64> {
+    let groups = class_groups(labels);
+    let mut weights = vec![0.0; labels.len()];
+    for group in &groups {
+        let weight = labels.len() as f64 / (groups.len() * group.len()) as f64;
+        for &i in group {
+            weights[i] = weight;
+        }
+    }
+    weights
+}
+
+/// Copies of the samples at `order`.
+fn gather(inputs: &[Vec<f64>], labels: &[Vec<f64>], order: &[usize]) -> Samples {
+    order.iter().map(|&i| (inputs[i].clone(), labels[i].clone())).unzip()
+}
@@ -0,0 +1,377 @@
+//! Per-feature input scaling.
+//!
+//! `StandardScaler` and `MinMaxScaler` are fitted on training inputs and
+//! map raw inputs to the scaled inputs a network is trained on; each can be
+//! saved to JSON on its own.  A `Scaling` holds either one: training
+//! (`TrainConfig::input_scaling`) or `Network::set_input_scaling` records it
+//! in `ModelMetadata`, and the inference paths (`Network::predict`,
+//! `InferenceSession`, the studio Test tab) apply it to raw inputs, so
+//! callers never normalize by hand.
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+use crate::data::dataset::Dataset;
+
+/// Which statistics `Scaling::fit` computes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingMethod {
+    /// Zero mean and unit standard deviation per feature.
+    Standard,
+    /// Each feature mapped linearly onto [0, 1] over its training range.
+    MinMax,
+}
+
+// ---------------------------------------------------------------------------
+// StandardScaler
+// ---------------------------------------------------------------------------
+
+/// Standardization: `(x − mean) / std` per feature.  A constant feature
+/// (zero standard deviation) is only shifted, never divided by zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardScaler {
+    pub mean: Vec<f64>,
+    pub std: Vec<f64>,
+}
+
+impl StandardScaler {
+    /// Mean and population standard deviation of every feature of `inputs`.
+    ///
+    /// # Panics
+    /// Panics if `inputs` is empty or its rows differ in length.
+    pub fn fit(inputs: &[Vec<f64>]) -> StandardScaler {
+        StandardScaler::fit_rows(inputs.len(), |i| inputs[i].as_slice())
+    }
+
+    /// Same as `fit`, over the inputs of `dataset`.  Samples are fetched one
+    /// at a time, twice each, so a lazily loaded dataset is never
+    /// materialized.
+    ///
+    /// # Panics
+    /// Panics if `dataset` is empty or its inputs differ in length.
+    pub fn fit_dataset(dataset: &dyn Dataset) -> StandardScaler {
+        StandardScaler::fit_rows(dataset.len(), |i| dataset.get(i).0)
+    }
+
+    fn fit_rows<R: AsRef<[f64]>>(n: usize, row: impl Fn(usize) -> R) -> StandardScaler {
+        let width = fitted_width(n, &row);
+        let mut mean = vec![0.0; width];
+        for i in 0..n {
+            mean.iter_mut().zip(checked_row(&row, i, width).as_ref()).for_each(|(m, v)| *m += v);
+        }
+        mean.iter_mut().for_each(|m| *m /= n as f64);
+        let mut std = vec![0.0; width];
+        for i in 0..n {
+            std.iter_mut()
+                .zip(checked_row(&row, i, width).as_ref())
+                .zip(&mean)
+                .for_each(|((s, v), m)| *s += (v - m).powi(2));
+        }
+        std.iter_mut().for_each(|s| *s = (*s / n as f64).sqrt());
+        StandardScaler { mean, std }
+    }
+
+    /// Number of features the statistics were fitted on.
+    pub fn len(&self) -> usize {
+        self.mean.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mean.is_empty()
+    }
+
+    /// Scales `input` in place.
+    ///
+    /// # Panics
+    /// Panics if `input.len()` differs from `len()`.
+    pub fn transform_in_place(&self, input: &mut [f64]) {
+        transform(input, self.len(), |j| (self.mean[j], divisor(self.std[j])));
+    }
+
+    /// Scaled copy of `input` (see `transform_in_place`).
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        let mut scaled = input.to_vec();
+        self.transform_in_place(&mut scaled);
+        scaled
+    }
+
+    /// Scaled copies of every row of `inputs`.
+    pub fn transform_all(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().map(|x| self.transform(x)).collect()
+    }
+
+    /// Maps a scaled vector back to raw feature values.
+    pub fn inverse_transform(&self, scaled: &[f64]) -> Vec<f64> {
+        inverse(scaled, self.len(), |j| (self.mean[j], divisor(self.std[j])))
+    }
+
+    /// Serializes the scaler to a pretty-printed JSON file at `path`.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Deserializes a scaler from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<StandardScaler> {
+        load_json(path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MinMaxScaler
+// ---------------------------------------------------------------------------
+
+/// Min-max normalization: `(x − min) / (max − min)` per feature, mapping
+/// the training range onto [0, 1].  A constant feature (zero range) is
+/// only shifted, never divided by zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MinMaxScaler {
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+}
+
+impl MinMaxScaler {
+    /// Minimum and maximum of every feature of `inputs`.
+    ///
+    /// # Panics
+    /// Panics if `inputs` is empty or its rows differ in length.
+    pub fn fit(inputs: &[Vec<f64>]) -> MinMaxScaler {
+        MinMaxScaler::fit_rows(inputs.len(), |i| inputs[i].as_slice())
+    }
+
+    /// Same as `fit`, over the inputs of `dataset`, fetched one at a time.
+    ///
+    /// # Panics
+    /// Panics if `dataset` is empty or its inputs differ in length.
+    pub fn fit_dataset(dataset: &dyn Dataset) -> MinMaxScaler {
+        MinMaxScaler::fit_rows(dataset.len(), |i| dataset.get(i).0)
+    }
+
+    fn fit_rows<R: AsRef<[f64]>>(n: usize, row: impl Fn(usize) -> R) -> MinMaxScaler {
+        let width = fitted_width(n, &row);
+        let mut min = vec![f64::INFINITY; width];
+        let mut max = vec![f64::NEG_INFINITY; width];
+        for i in 0..n {
+            let x = checked_row(&row, i, width);
+            for ((lo, hi), &v) in min.iter_mut().zip(max.iter_mut()).zip(x.as_ref()) {
+                *lo = lo.min(v);
+                *hi = hi.max(v);
+            }
+        }
+        MinMaxScaler { min, max }
+    }
+
+    /// Number of features the statistics were fitted on.
+    pub fn len(&self) -> usize {
+        self.min.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.is_empty()
+    }
+
+    /// Scales `input` in place.
+    ///
+    /// # Panics
+    /// Panics if `input.len()` differs from `len()`.
+    pub fn transform_in_place(&self, input: &mut [f64]) {
+        transform(input, self.len(), |j| (self.min[j], divisor(self.max[j] - self.min[j])));
+    }
+
+    /// Scaled copy of `input` (see `transform_in_place`).
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        let mut scaled = input.to_vec();
+        self.transform_in_place(&mut scaled);
+        scaled
+    }
+
+    /// Scaled copies of every row of `inputs`.
+    pub fn transform_all(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().map(|x| self.transform(x)).collect()
+    }
+
+    /// Maps a scaled vector back to raw feature values.
+    pub fn inverse_transform(&self, scaled: &[f64]) -> Vec<f64> {
+        inverse(scaled, self.len(), |j| (self.min[j], divisor(self.max[j] - self.min[j])))
+    }
+
+    /// Serializes the scaler to a pretty-printed JSON file at `path`.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Deserializes a scaler from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<MinMaxScaler> {
+        load_json(path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scaling
+// ---------------------------------------------------------------------------
+
+/// A fitted scaler of either kind, as stored in `ModelMetadata`; `apply`
+/// maps a raw input to the scaled input the network was trained on.
+///
+/// Serialized as the scaler's fields plus a `"method"` tag, e.g.
+/// `{"method": "standard", "mean": [...], "std": [...]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Scaling {
+    Standard(StandardScaler),
+    MinMax(MinMaxScaler),
+}
+
+impl Scaling {
+    /// Fits the scaler `method` names on `inputs` (see `StandardScaler::fit`
+    /// and `MinMaxScaler::fit`).
+    ///
+    /// # Panics
+    /// Panics if `inputs` is empty or its rows differ in length.
+    pub fn fit(method: ScalingMethod, inputs: &[Vec<f64>]) -> Scaling {
+        match method {
+            ScalingMethod::Standard => Scaling::Standard(StandardScaler::fit(inputs)),
+            ScalingMethod::MinMax => Scaling::MinMax(MinMaxScaler::fit(inputs)),
+        }
+    }
+
+    /// Same as `fit`, over the inputs of `dataset`, fetched one at a time.
+    ///
+    /// # Panics
+    /// Panics if `dataset` is empty or its inputs differ in length.
+    pub fn fit_dataset(method: ScalingMethod, dataset: &dyn Dataset) -> Scaling {
+        match method {
+            ScalingMethod::Standard => Scaling::Standard(StandardScaler::fit_dataset(dataset)),
+            ScalingMethod::MinMax => Scaling::MinMax(MinMaxScaler::fit_dataset(dataset)),
+        }
+    }
+
+    pub fn method(&self) -> ScalingMethod {
+        match self {
+            Scaling::Standard(_) => ScalingMethod::Standard,
+            Scaling::MinMax(_) => ScalingMethod::MinMax,
+        }
+    }
+
+    /// Number of features the statistics were fitted on.
+    pub fn len(&self) -> usize {
+        match self {
+            Scaling::Standard(scaler) => scaler.len(),
+            Scaling::MinMax(scaler) => scaler.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Scales `input` in place.
+    ///
+    /// # Panics
+    /// Panics if `input.len()` differs from `len()`.
+    pub fn apply_in_place(&self, input: &mut [f64]) {
+        match self {
+            Scaling::Standard(scaler) => scaler.transform_in_place(input),
+            Scaling::MinMax(scaler) => scaler.transform_in_place(input),
+        }
+    }
+
+    /// Scaled copy of `input` (see `apply_in_place`).
+    pub fn apply(&self, input: &[f64]) -> Vec<f64> {
+        let mut scaled = input.to_vec();
+        self.apply_in_place(&mut scaled);
+        scaled
+    }
+
+    /// Scaled copies of every row of `inputs`.
+    pub fn apply_all(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().map(|x| self.apply(x)).collect()
+    }
+
+    /// Divides each entry of a gradient with respect to the scaled input by
+    /// its feature's divisor, giving the gradient with respect to the raw
+    /// input.
+    pub(crate) fn chain_gradient(&self, gradient: &mut [f64]) {
+        for (j, g) in gradient.iter_mut().enumerate() {
+            *g /= match self {
+                Scaling::Standard(scaler) => divisor(scaler.std[j]),
+                Scaling::MinMax(scaler) => divisor(scaler.max[j] - scaler.min[j]),
+            };
+        }
+    }
+}
+
+impl From<StandardScaler> for Scaling {
+    fn from(scaler: StandardScaler) -> Scaling {
+        Scaling::Standard(scaler)
+    }
+}
+
+impl From<MinMaxScaler> for Scaling {
+    fn from(scaler: MinMaxScaler) -> Scaling {
+        Scaling::MinMax(scaler)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+/// Width of the first of `n` rows.
+///
+/// # Panics
+/// Panics if `n == 0`.
+fn fitted_width<R: AsRef<[f64]>>(n: usize, row: &impl Fn(usize) -> R) -> usize {
+    assert!(n > 0, "cannot fit a scaling to no inputs");
+    row(0).as_ref().len()
+}
+
+/// Row `i`, which must be `width` wide.
+fn checked_row<R: AsRef<[f64]>>(row: &impl Fn(usize) -> R, i: usize, width: usize) -> R {
+    let x = row(i);
+    assert_eq!(x.as_ref().len(), width, "all inputs must have the same number of features");
+    x
+}
+
+/// A feature's spread as a divisor: 1 for a constant feature.
+fn divisor(spread: f64) -> f64 {
+    if spread > 0.0 { spread } else { 1.0 }
+}
+
+/// `(x − offset) / divisor` for every feature, `len` of them expected.
+fn transform(input: &mut [f64], len: usize, offset_and_divisor: impl Fn(usize) -> (f64, f64)) {
+    assert_eq!(
+        input.len(), len,
+        "input scaling expects {} features, got {}", len, input.len()
+    );
+    for (j, x) in input.iter_mut().enumerate() {
+        let (offset, divisor) = offset_and_divisor(j);
+        *x = (*x - offset) / divisor;
+    }
+}
+
+/// Inverse of `transform`: `x · divisor + offset`.
+fn inverse(scaled: &[f64], len: usize, offset_and_divisor: impl Fn(usize) -> (f64, f64)) -> Vec<f64> {
+    assert_eq!(
+        scaled.len(), len,
+        "input scaling expects {} features, got {}", len, scaled.len()
+    );
+    scaled.iter().enumerate().map(|(j, &x)| {
+        let (offset, divisor) = offset_and_divisor(j);
+        x * divisor + offset
+    }).collect()
+}
+
+fn save_json<T: Serialize>(value: &T, path: &str) -> std::io::Result<()> {
+    let file = std::fs::File::create(path)?;
+    let writer = std::io::BufWriter::new(file);
+    serde_json::to_writer_pretty(writer, value)
+        .map_err(std::io::Error::other)
+}
+
+fn load_json<T: DeserializeOwned>(path: &str) -> std::io::Result<T> {
+    let file = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    serde_json::from_reader(reader)
+        .map_err(std::io::Error::other)
+}
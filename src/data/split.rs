@@ -0,0 +1,131 @@
+//! Shuffled and stratified train / validation / test splitting.
+//!
+//! `SplitDataset::from_samples` splits contiguously, which puts whole
+//! classes into the validation set when the source is sorted by label.
+//! `split_samples` shuffles first, and `SplitStrategy::Stratified` also
+//! keeps each class's share the same in every split.
+
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Serialize, Deserialize};
+
+use crate::data::dataset::{check_fractions, Split, SplitDataset};
+use crate::train::loop_fn::argmax;
+
+/// How `split_samples` assigns samples to the splits.
+///
+/// - `Shuffled`   — a random permutation, then contiguous splits
+/// - `Stratified` — every class is shuffled and split on its own with the
+///   requested fractions (rounded per class), so class proportions carry
+///   over to every split.  A sample's class is the argmax of its label, or,
+///   for single-value labels, whether the value is at least 0.5 (binary
+///   targets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitStrategy {
+    Shuffled,
+    #[default]
+    Stratified,
+}
+
+/// Splits samples into training, validation and test sets with `strategy`,
+/// shuffled with fresh entropy.  Pass `test_fraction = 0.0` for a two-way
+/// split.  Samples keep a random order within each split.
+///
+/// # Arguments
+/// - `inputs`        — all samples
+/// - `labels`        — corresponding targets, same length as `inputs`
+/// - `val_fraction`  — share of samples reserved for validation, in `[0, 1]`
+/// - `test_fraction` — share of samples reserved for testing, in `[0, 1]`
+/// - `strategy`      — see `SplitStrategy`
+///
+/// # Panics
+/// Panics if the lengths differ, a fraction is outside `[0, 1]`, or the
+/// two fractions sum to more than 1.
+pub fn split_samples(
+    inputs: Vec<Vec<f64>>,
+    labels: Vec<Vec<f64>>,
+    val_fraction: f64,
+    test_fraction: f64,
+    strategy: SplitStrategy,
+) -> SplitDataset {
+    split_samples_with_rng(inputs, labels, val_fraction, test_fraction, strategy, &mut rand::thread_rng())
+}
+
+/// Same as `split_samples`, but reproducible: the shuffle is seeded with
+/// `seed`.
+pub fn split_samples_seeded(
+    inputs: Vec<Vec<f64>>,
+    labels: Vec<Vec<f64>>,
+    val_fraction: f64,
+    test_fraction: f64,
+    strategy: SplitStrategy,
+    seed: u64,
+) -> SplitDataset {
+    split_samples_with_rng(inputs, labels, val_fraction, test_fraction, strategy, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Same as `split_samples`, drawing the shuffle from `rng`.
+pub fn split_samples_with_rng<R: Rng + ?Sized>(
+    inputs: Vec<Vec<f64>>,
+    labels: Vec<Vec<f64>>,
+    val_fraction: f64,
+    test_fraction: f64,
+    strategy: SplitStrategy,
+    rng: &mut R,
+) -> SplitDataset {
+    assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+    check_fractions(val_fraction, test_fraction);
+
+    // Sample indices of each split.
+    let mut parts: [Vec<usize>; 3] = Default::default();
+    let groups = match strategy {
+        SplitStrategy::Shuffled => vec![(0..inputs.len()).collect()],
+        SplitStrategy::Stratified => class_groups(&labels),
+    };
+    for mut group in groups {
+        group.shuffle(rng);
+        let (train_n, val_n, _) = split_sizes(group.len(), val_fraction, test_fraction);
+        let mut test = group.split_off(train_n + val_n);
+        let mut val = group.split_off(train_n);
+        parts[0].append(&mut group);
+        parts[1].append(&mut val);
+        parts[2].append(&mut test);
+    }
+
+    let mut inputs = inputs;
+    let mut labels = labels;
+    let [train, val, test] = parts.map(|mut part| {
+        part.shuffle(rng);
+        Split::new(
+            part.iter().map(|&i| std::mem::take(&mut inputs[i])).collect(),
+            part.iter().map(|&i| std::mem::take(&mut labels[i])).collect(),
+        )
+    });
+    SplitDataset { train, val, test }
+}
+
+/// `(train, val, test)` sizes for `total` samples, rounded to the nearest
+/// sample as in `SplitDataset::from_samples`.
+pub(crate) fn split_sizes(total: usize, val_fraction: f64, test_fraction: f64) -> (usize, usize, usize) {
+    let test_n = (total as f64 * test_fraction).round() as usize;
+    let val_n  = ((total as f64 * val_fraction).round() as usize).min(total - test_n);
+    (total - val_n - test_n, val_n, test_n)
+}
+
+/// Sample indices grouped by class (see `SplitStrategy::Stratified`), in
+/// class order.
+pub(crate) fn class_groups(labels: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        let class = match label.as_slice() {
+            [value] => usize::from(*value >= 0.5),
+            _ => argmax(label),
+        };
+        groups.entry(class).or_default().push(i);
+    }
+    groups.into_values().collect()
+}
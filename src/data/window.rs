@@ -0,0 +1,106 @@
+//! Sliding windows over a time series, for forecasting.
+//!
+//! `windows` turns a series into supervised `(input window, target)` pairs:
+//! each input is `window` consecutive values and its label the `horizon`
+//! values that follow.  `windows_normalized` additionally rescales every
+//! pair by statistics of its own input window, so a network learns the
+//! shape of the series rather than its level; the returned `WindowScale`s
+//! map predictions back to the original units.
+
+use serde::{Serialize, Deserialize};
+
+use crate::data::scaling::ScalingMethod;
+use crate::data::Samples;
+
+/// Every `(input, target)` pair of `series`: window `i` holds
+/// `series[i..i + window]` and its label `series[i + window..i + window + horizon]`.
+/// Windows advance one step at a time, so a series of `n` values yields
+/// `n − window − horizon + 1` pairs (none if it is shorter than
+/// `window + horizon`).
+///
+/// # Panics
+/// Panics if `window` or `horizon` is 0.
+pub fn windows(series: &[f64], window: usize, horizon: usize) -> Samples {
+    assert!(window > 0, "window must be at least 1");
+    assert!(horizon > 0, "horizon must be at least 1");
+    series
+        .windows(window + horizon)
+        .map(|pair| (pair[..window].to_vec(), pair[window..].to_vec()))
+        .unzip()
+}
+
+/// Same as `windows`, with every pair normalized by `method` over its own
+/// input window (`Standard`: zero mean and unit standard deviation;
+/// `MinMax`: the window's range onto [0, 1]).  The target is rescaled with
+/// the same statistics.  Returns the pairs and one `WindowScale` per pair.
+///
+/// # Panics
+/// Panics if `window` or `horizon` is 0.
+pub fn windows_normalized(
+    series: &[f64],
+    window: usize,
+    horizon: usize,
+    method: ScalingMethod,
+) -> (Samples, Vec<WindowScale>) {
+    let (mut inputs, mut labels) = windows(series, window, horizon);
+    let scales = inputs
+        .iter_mut()
+        .zip(labels.iter_mut())
+        .map(|(input, label)| {
+            let scale = WindowScale::fit(input, method);
+            scale.normalize_in_place(input);
+            scale.normalize_in_place(label);
+            scale
+        })
+        .collect();
+    ((inputs, labels), scales)
+}
+
+/// Affine normalization of one window: `(x − offset) / scale`.
+///
+/// # Fields
+/// - `offset` — subtracted first (the window's mean or minimum)
+/// - `scale`  — divided by next (its standard deviation or range; 1 for a
+///   constant window)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowScale {
+    pub offset: f64,
+    pub scale: f64,
+}
+
+impl WindowScale {
+    /// Statistics of `window` for `method`.  Use it on the latest window of
+    /// a series to prepare a forecasting input the way `windows_normalized`
+    /// prepared the training data.
+    ///
+    /// # Panics
+    /// Panics if `window` is empty.
+    pub fn fit(window: &[f64], method: ScalingMethod) -> WindowScale {
+        assert!(!window.is_empty(), "cannot fit a scale to an empty window");
+        let n = window.len() as f64;
+        let (offset, spread) = match method {
+            ScalingMethod::Standard => {
+                let mean = window.iter().sum::<f64>() / n;
+                let var = window.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+                (mean, var.sqrt())
+            }
+            ScalingMethod::MinMax => {
+                let min = window.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = window.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                (min, max - min)
+            }
+        };
+        WindowScale { offset, scale: if spread > 0.0 { spread } else { 1.0 } }
+    }
+
+    /// Normalizes `values` in place.
+    pub fn normalize_in_place(&self, values: &mut [f64]) {
+        values.iter_mut().for_each(|x| *x = (*x - self.offset) / self.scale);
+    }
+
+    /// Maps normalized values (e.g. a network's forecast) back to the
+    /// series' units.
+    pub fn denormalize(&self, values: &[f64]) -> Vec<f64> {
+        values.iter().map(|x| x * self.scale + self.offset).collect()
+    }
+}
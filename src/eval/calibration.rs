@@ -0,0 +1,87 @@
+use crate::activation::activation::ActivationFunction;
+use crate::data::dataset::Split;
+use crate::network::network::Network;
+
+/// Outcome of `calibrate_temperature`.
+///
+/// # Fields
+/// - `temperature` — fitted temperature, now stored in the model metadata
+/// - `nll_before`  — mean negative log-likelihood on the split at T = 1
+/// - `nll_after`   — mean negative log-likelihood at the fitted temperature
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    pub temperature: f64,
+    pub nll_before: f64,
+    pub nll_after: f64,
+}
+
+/// Search range for the temperature.  Well outside what trained models need
+/// (typically 0.5–3); the bounds only guard against degenerate splits.
+const MIN_TEMPERATURE: f64 = 0.05;
+const MAX_TEMPERATURE: f64 = 20.0;
+
+/// Fits a Softmax temperature on `validation` and stores it in the model
+/// metadata (created if missing), after which `predict`, `forward_batch` and
+/// `InferenceSession` divide the output logits by it.
+///
+/// The temperature minimizes the mean negative log-likelihood of the labels
+/// (one-hot or soft), so confidences match observed accuracy; predicted
+/// classes are unchanged.  Use a held-out split, not the training data —
+/// training NLL is overconfident by construction.  Any previously stored
+/// temperature is ignored while fitting.
+///
+/// # Panics
+/// Panics if the output layer is not Softmax, `validation` is empty, or the
+/// label width differs from the output size.
+pub fn calibrate_temperature(network: &mut Network, validation: &Split) -> Calibration {
+    assert!(
+        network.layers.last().and_then(|l| l.activation()) == Some(&ActivationFunction::Softmax),
+        "temperature calibration requires a Softmax output layer"
+    );
+    assert!(!validation.is_empty(), "calibration split is empty");
+
+    let logits: Vec<Vec<f64>> = validation.inputs.iter().map(|x| network.logits(x)).collect();
+    for (z, label) in logits.iter().zip(validation.labels.iter()) {
+        assert_eq!(z.len(), label.len(), "label width must match the output size");
+    }
+    let nll = |t: f64| mean_nll(&logits, &validation.labels, t);
+
+    // The NLL is convex in 1/T, hence unimodal in ln T: golden-section search.
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = (MIN_TEMPERATURE.ln(), MAX_TEMPERATURE.ln());
+    let mut a = hi - ratio * (hi - lo);
+    let mut b = lo + ratio * (hi - lo);
+    let (mut fa, mut fb) = (nll(a.exp()), nll(b.exp()));
+    while hi - lo > 1e-5 {
+        if fa < fb {
+            hi = b;
+            b = a;
+            fb = fa;
+            a = hi - ratio * (hi - lo);
+            fa = nll(a.exp());
+        } else {
+            lo = a;
+            a = b;
+            fa = fb;
+            b = lo + ratio * (hi - lo);
+            fb = nll(b.exp());
+        }
+    }
+    let temperature = ((lo + hi) / 2.0).exp();
+
+    network.metadata.get_or_insert_with(Default::default).temperature = Some(temperature);
+    Calibration { temperature, nll_before: nll(1.0), nll_after: nll(temperature) }
+}
+
+/// Mean of `-Σ y_k · log softmax(z / t)_k` over all samples.
+fn mean_nll(logits: &[Vec<f64>], labels: &[Vec<f64>], t: f64) -> f64 {
+    let total: f64 = logits.iter().zip(labels.iter())
+        .map(|(z, y)| {
+            // log-softmax via log-sum-exp for numerical stability.
+            let max_z = z.iter().cloned().fold(f64::NEG_INFINITY, f64::max) / t;
+            let log_sum = z.iter().map(|v| (v / t - max_z).exp()).sum::<f64>().ln() + max_z;
+            z.iter().zip(y.iter()).map(|(v, p)| -p * (v / t - log_sum)).sum::<f64>()
+        })
+        .sum();
+    total / logits.len() as f64
+}
@@ -0,0 +1,54 @@
+use crate::data::dataset::Split;
+use crate::eval::report::EvalReport;
+use crate::loss::loss::Loss;
+use crate::loss::loss_type::LossType;
+use crate::network::network::Network;
+use crate::metrics::classification::{class_metrics, confusion_matrix};
+
+/// Runs `network` over every sample of `dataset` and summarizes the results.
+/// Uses the inference path, so a calibrated temperature is applied.
+///
+/// Labels are treated as classes when they are at least two wide (the true
+/// and predicted classes are the argmax of label and output), or when they
+/// are a single value and `loss` is `BinaryCrossEntropy` (classes 0/1 at a
+/// 0.5 threshold).  Anything else is treated as regression and only the mean
+/// loss is reported.
+///
+/// # Panics
+/// Panics if output and label widths disagree with what `loss` expects
+/// (same conditions as training).
+pub fn evaluate(network: &mut Network, dataset: &Split, loss: LossType) -> EvalReport {
+    let n = dataset.len();
+    let label_width = dataset.labels.first().map(|l| l.len()).unwrap_or(0);
+    let n_classes = match label_width {
+        0 => 0,
+        1 if loss == LossType::BinaryCrossEntropy => 2,
+        1 => 0,
+        w => w,
+    };
+
+    let outputs: Vec<Vec<f64>> = dataset.inputs.iter().map(|x| network.predict(x)).collect();
+    let total_loss: f64 = outputs.iter().zip(dataset.labels.iter())
+        .map(|(output, label)| loss.loss(output, label))
+        .sum();
+    let confusion = if n_classes > 0 {
+        confusion_matrix(&outputs, &dataset.labels)
+    } else {
+        Vec::new()
+    };
+
+    let accuracy = if n_classes > 0 && n > 0 {
+        let correct: usize = (0..n_classes).map(|c| confusion[c][c]).sum();
+        Some(correct as f64 / n as f64)
+    } else {
+        None
+    };
+
+    EvalReport {
+        samples: n,
+        loss: if n > 0 { total_loss / n as f64 } else { 0.0 },
+        accuracy,
+        per_class: class_metrics(&confusion),
+        confusion,
+    }
+}
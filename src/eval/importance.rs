@@ -0,0 +1,112 @@
+use rand::seq::SliceRandom;
+
+use crate::data::dataset::Split;
+use crate::loss::loss::Loss;
+use crate::loss::loss_type::LossType;
+use crate::network::network::Network;
+use crate::train::loop_fn::argmax;
+
+/// Number of shuffles averaged per feature by `permutation_importance`.
+pub const PERMUTATION_REPEATS: usize = 5;
+
+/// Score used by `permutation_importance`.
+///
+/// - `Loss`     — mean loss of the given type (lower is better)
+/// - `Accuracy` — fraction of correct predictions, using argmax for
+///   multi-column labels and a 0.5 threshold for single-column ones (higher
+///   is better)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Loss(LossType),
+    Accuracy,
+}
+
+impl Metric {
+    /// Evaluates the metric for `network` on the given samples.
+    pub fn score(&self, network: &Network, inputs: &[Vec<f64>], labels: &[Vec<f64>]) -> f64 {
+        if inputs.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = inputs.iter().zip(labels.iter())
+            .map(|(input, label)| {
+                let output = network.predict(input);
+                match *self {
+                    Metric::Loss(loss) => loss.loss(&output, label),
+                    Metric::Accuracy if label.len() == 1 => {
+                        ((output[0] >= 0.5) == (label[0] >= 0.5)) as u8 as f64
+                    }
+                    Metric::Accuracy => (argmax(&output) == argmax(label)) as u8 as f64,
+                }
+            })
+            .sum();
+        total / inputs.len() as f64
+    }
+
+    /// `true` for metrics where larger values are better.
+    pub fn higher_is_better(&self) -> bool {
+        matches!(self, Metric::Accuracy)
+    }
+}
+
+/// Importance of one input feature, as measured by `permutation_importance`.
+///
+/// # Fields
+/// - `feature`    — 0-based input column
+/// - `importance` — mean metric degradation when the column is shuffled
+///   (positive = the model relies on the feature; around 0 = unused;
+///   negative = noise that the model is better off without)
+/// - `std_dev`    — standard deviation of the degradation across shuffles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeatureImportance {
+    pub feature: usize,
+    pub importance: f64,
+    pub std_dev: f64,
+}
+
+/// Measures how much each input feature matters to `network` on `dataset`.
+///
+/// For every feature column the values are shuffled across samples
+/// (`PERMUTATION_REPEATS` times, breaking the feature's relation to the
+/// label while keeping its distribution) and the drop in `metric` relative
+/// to the unshuffled baseline is recorded.  Returns one entry per feature,
+/// most important first.
+///
+/// Cost is `features × PERMUTATION_REPEATS` passes over the dataset, so this
+/// is meant for tabular models rather than raw images.
+pub fn permutation_importance(network: &Network, dataset: &Split, metric: Metric) -> Vec<FeatureImportance> {
+    let n_features = dataset.inputs.first().map(|x| x.len()).unwrap_or(0);
+    let baseline = metric.score(network, &dataset.inputs, &dataset.labels);
+    let degradation = |score: f64| if metric.higher_is_better() { baseline - score } else { score - baseline };
+
+    let mut rng = rand::thread_rng();
+    let mut inputs = dataset.inputs.clone();
+    let mut order: Vec<usize> = (0..inputs.len()).collect();
+
+    let mut importances: Vec<FeatureImportance> = (0..n_features)
+        .map(|feature| {
+            let column: Vec<f64> = dataset.inputs.iter().map(|x| x[feature]).collect();
+            let drops: Vec<f64> = (0..PERMUTATION_REPEATS)
+                .map(|_| {
+                    order.shuffle(&mut rng);
+                    for (input, &src) in inputs.iter_mut().zip(order.iter()) {
+                        input[feature] = column[src];
+                    }
+                    degradation(metric.score(network, &inputs, &dataset.labels))
+                })
+                .collect();
+            // Restore the column before moving on to the next feature.
+            for (input, &value) in inputs.iter_mut().zip(column.iter()) {
+                input[feature] = value;
+            }
+
+            let mean = drops.iter().sum::<f64>() / drops.len() as f64;
+            let var = drops.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / drops.len() as f64;
+            FeatureImportance { feature, importance: mean, std_dev: var.sqrt() }
+        })
+        .collect();
+
+    importances.sort_by(|a, b| {
+        b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    importances
+}
@@ -0,0 +1,9 @@
+pub mod report;
+pub mod evaluator;
+pub mod calibration;
+pub mod importance;
+
+pub use report::{EvalReport, ClassMetrics};
+pub use evaluator::evaluate;
+pub use calibration::{calibrate_temperature, Calibration};
+pub use importance::{permutation_importance, FeatureImportance, Metric};
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use crate::metrics::classification::{averaged_metrics, Average, AveragedMetrics};
+pub use crate::metrics::classification::ClassMetrics;
+
+/// Result of `eval::evaluate` on one dataset split.
+///
+/// The classification fields are only populated when the labels describe
+/// classes (see `evaluate`); for regression targets `accuracy` is `None`
+/// and `per_class` / `confusion` are empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    /// Number of evaluated samples.
+    pub samples: usize,
+    /// Mean loss over all samples.
+    pub loss: f64,
+    /// Fraction of samples classified correctly, in [0, 1].
+    pub accuracy: Option<f64>,
+    /// One entry per class, in class order.
+    pub per_class: Vec<ClassMetrics>,
+    /// `confusion[truth][predicted]` sample counts.
+    pub confusion: Vec<Vec<usize>>,
+}
+
+impl EvalReport {
+    /// Number of classes (0 for regression reports).
+    pub fn n_classes(&self) -> usize {
+        self.confusion.len()
+    }
+
+    /// Precision / recall / F1 averaged over the classes, if this is a
+    /// classification report.
+    pub fn averaged(&self, average: Average) -> Option<AveragedMetrics> {
+        if self.confusion.is_empty() {
+            return None;
+        }
+        Some(averaged_metrics(&self.confusion, average))
+    }
+
+    /// Unweighted mean of the per-class F1 scores, if this is a
+    /// classification report.
+    pub fn macro_f1(&self) -> Option<f64> {
+        self.averaged(Average::Macro).map(|m| m.f1)
+    }
+}
+
+/// Plain-text rendering for terminals: summary lines, then the per-class
+/// table and confusion matrix for classification reports.
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Samples:  {}", self.samples)?;
+        writeln!(f, "Loss:     {:.6}", self.loss)?;
+        if let Some(acc) = self.accuracy {
+            let correct: usize = (0..self.n_classes()).map(|c| self.confusion[c][c]).sum();
+            writeln!(f, "Accuracy: {:.2}% ({}/{})", acc * 100.0, correct, self.samples)?;
+        }
+        if let Some(macro_f1) = self.macro_f1() {
+            writeln!(f, "Macro F1: {:.4}", macro_f1)?;
+        }
+        if self.per_class.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:>6}  {:>9}  {:>9}  {:>9}  {:>8}", "Class", "Precision", "Recall", "F1", "Support")?;
+        for c in &self.per_class {
+            writeln!(
+                f,
+                "{:>6}  {:>9.4}  {:>9.4}  {:>9.4}  {:>8}",
+                c.class, c.precision, c.recall, c.f1, c.support
+            )?;
+        }
+
+        // Column width fits the largest count (and the header labels).
+        let width = self.confusion.iter().flatten().max()
+            .map(|m| m.to_string().len())
+            .unwrap_or(1)
+            .max(self.n_classes().to_string().len() + 2);
+
+        writeln!(f)?;
+        writeln!(f, "Confusion matrix (rows = true, columns = predicted):")?;
+        write!(f, "{:>w$}", "", w = width)?;
+        for c in 0..self.n_classes() {
+            write!(f, " {:>w$}", format!("P:{}", c), w = width)?;
+        }
+        writeln!(f)?;
+        for (truth, row) in self.confusion.iter().enumerate() {
+            write!(f, "{:>w$}", format!("T:{}", truth), w = width)?;
+            for count in row {
+                write!(f, " {:>w$}", count, w = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,570 @@
+use std::any::Any;
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::layers::ops::{LayerGradients, LayerOps};
+use crate::math::matrix::Matrix;
+
+/// Multi-head scaled dot-product self-attention over a sequence.
+///
+/// A sample is `seq_len` token vectors of `model_dim` values each, flattened
+/// token by token (index `t·model_dim + i`); the output has the same shape.
+/// Each of the `heads` heads attends with `model_dim / heads` dimensions:
+///
+/// `Q, K, V = X·Wq + bq, X·Wk + bk, X·Wv + bv`,
+/// `head_h = softmax(Q_h·K_hᵀ / √d_h)·V_h`,
+/// `Y = concat(head_1 … head_H)·Wo + bo`.
+///
+/// With `causal` set, token `t` only attends to tokens `0..=t`, as needed
+/// for next-token prediction.  There are no positional encodings: add them
+/// to the inputs if order matters.
+///
+/// # Fields
+/// - `weights` — `[Wq | Wk | Wv | Wo]`, `model_dim × 4·model_dim`
+/// - `biases`  — `[bq | bk | bv | bo]`, `1 × 4·model_dim`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfAttention {
+    pub seq_len: usize,
+    pub model_dim: usize,
+    pub heads: usize,
+    #[serde(default)]
+    pub causal: bool,
+    pub weights: Matrix,
+    pub biases: Matrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip)]
+    cache: AttentionCache,
+}
+
+impl SelfAttention {
+    /// Self-attention over `seq_len` tokens of `model_dim` values with
+    /// `heads` heads (1 for single-head attention), Xavier-initialized.
+    ///
+    /// # Panics
+    /// Panics if a size is 0 or `heads` does not divide `model_dim`.
+    pub fn new(seq_len: usize, model_dim: usize, heads: usize) -> SelfAttention {
+        SelfAttention::new_with_rng(seq_len, model_dim, heads, &mut rand::thread_rng())
+    }
+
+    /// `new` with the initial weights drawn from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(seq_len: usize, model_dim: usize, heads: usize, rng: &mut R) -> SelfAttention {
+        check_dims(seq_len, model_dim, heads);
+        SelfAttention {
+            seq_len,
+            model_dim,
+            heads,
+            causal: false,
+            weights: Matrix::from_flat(model_dim, 4 * model_dim, attention_weights(model_dim, rng)),
+            biases: Matrix::zeros(1, 4 * model_dim),
+            name: None,
+            cache: AttentionCache::default(),
+        }
+    }
+
+    /// Restricts every token to attend to itself and earlier tokens only.
+    pub fn with_causal(mut self, causal: bool) -> SelfAttention {
+        self.causal = causal;
+        self
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> SelfAttention {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn dims(&self) -> AttentionDims {
+        AttentionDims { seq_len: self.seq_len, model_dim: self.model_dim, heads: self.heads, causal: self.causal }
+    }
+
+    fn run(&self, input: &[f64]) -> AttentionCache {
+        check_input_len(self, input);
+        attention_forward(self.dims(), input, &self.weights.data, &self.biases.data)
+    }
+}
+
+impl LayerOps for SelfAttention {
+    fn kind(&self) -> &'static str {
+        "self_attention"
+    }
+
+    fn input_size(&self) -> usize {
+        self.seq_len * self.model_dim
+    }
+
+    fn output_size(&self) -> usize {
+        self.seq_len * self.model_dim
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.cache = self.run(input);
+        self.cache.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.cache.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.run(input).output;
+    }
+
+    fn backward(&self, input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let d = self.model_dim;
+        let weights = &self.weights.data;
+        let mut dw = vec![0.0; weights.len()];
+        let mut db = vec![0.0; 4 * d];
+        let mut dx = vec![0.0; input.len()];
+        attention_backward(self.dims(), input, &self.cache, output_delta, weights, &mut dw, &mut db, &mut dx);
+        LayerGradients {
+            weights: Matrix::from_flat(d, 4 * d, dw),
+            biases: Matrix::from_flat(1, 4 * d, db),
+            input_delta: want_input_delta.then_some(dx),
+        }
+    }
+
+    /// Per-token projections plus the score and mixing products.
+    fn macs(&self) -> usize {
+        self.seq_len * self.weights.data.len() + score_macs(self.seq_len, self.model_dim)
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.weights, &self.biases)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.weights, &mut self.biases)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("attention layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A small transformer encoder block: self-attention and a position-wise
+/// feed-forward network, each wrapped in a residual connection.
+///
+/// `Y1 = X + SelfAttention(X)`, `Y = Y1 + ReLU(Y1·W1 + b1)·W2 + b2`, with
+/// the same input/output layout as `SelfAttention`.  There is no layer
+/// normalization, which is fine for the shallow stacks this is meant for.
+///
+/// All parameters are packed into one row each, so optimizers and weight
+/// decay treat them like any other layer's:
+///
+/// # Fields
+/// - `weights` — `1 × (4·d² + 2·d·ff_dim)`: `[Wq | Wk | Wv | Wo]` (row-major
+///   `d × 4d`), then `W1` (`d × ff_dim`), then `W2` (`ff_dim × d`)
+/// - `biases`  — `1 × (5·d + ff_dim)`: `[bq | bk | bv | bo]`, `b1`, `b2`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformerBlock {
+    pub seq_len: usize,
+    pub model_dim: usize,
+    pub heads: usize,
+    pub ff_dim: usize,
+    #[serde(default)]
+    pub causal: bool,
+    pub weights: Matrix,
+    pub biases: Matrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip)]
+    cache: BlockCache,
+}
+
+impl TransformerBlock {
+    /// A block over `seq_len` tokens of `model_dim` values, with `heads`
+    /// attention heads and a feed-forward hidden width of `ff_dim`
+    /// (typically `4 · model_dim`).
+    ///
+    /// # Panics
+    /// Panics if a size is 0 or `heads` does not divide `model_dim`.
+    pub fn new(seq_len: usize, model_dim: usize, heads: usize, ff_dim: usize) -> TransformerBlock {
+        TransformerBlock::new_with_rng(seq_len, model_dim, heads, ff_dim, &mut rand::thread_rng())
+    }
+
+    /// `new` with the initial weights drawn from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(
+        seq_len: usize,
+        model_dim: usize,
+        heads: usize,
+        ff_dim: usize,
+        rng: &mut R,
+    ) -> TransformerBlock {
+        check_dims(seq_len, model_dim, heads);
+        assert!(ff_dim > 0, "transformer ff_dim must be at least 1");
+        let mut weights = attention_weights(model_dim, rng);
+        weights.extend(Matrix::he_with(model_dim, ff_dim, rng).data);
+        weights.extend(Matrix::xavier_with(ff_dim, model_dim, rng).data);
+        TransformerBlock {
+            seq_len,
+            model_dim,
+            heads,
+            ff_dim,
+            causal: false,
+            weights: Matrix::from_flat(1, weights.len(), weights),
+            biases: Matrix::zeros(1, 5 * model_dim + ff_dim),
+            name: None,
+            cache: BlockCache::default(),
+        }
+    }
+
+    /// Restricts every token to attend to itself and earlier tokens only.
+    pub fn with_causal(mut self, causal: bool) -> TransformerBlock {
+        self.causal = causal;
+        self
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> TransformerBlock {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn dims(&self) -> AttentionDims {
+        AttentionDims { seq_len: self.seq_len, model_dim: self.model_dim, heads: self.heads, causal: self.causal }
+    }
+
+    /// `(attention, W1, W2)` slices of a packed weight row.
+    fn split_weights<'a>(&self, w: &'a [f64]) -> (&'a [f64], &'a [f64], &'a [f64]) {
+        let d = self.model_dim;
+        let (attn, rest) = w.split_at(4 * d * d);
+        let (w1, w2) = rest.split_at(d * self.ff_dim);
+        (attn, w1, w2)
+    }
+
+    /// `(attention, b1, b2)` slices of a packed bias row.
+    fn split_biases<'a>(&self, b: &'a [f64]) -> (&'a [f64], &'a [f64], &'a [f64]) {
+        let (attn, rest) = b.split_at(4 * self.model_dim);
+        let (b1, b2) = rest.split_at(self.ff_dim);
+        (attn, b1, b2)
+    }
+
+    fn run(&self, input: &[f64]) -> BlockCache {
+        check_input_len(self, input);
+        let (t, d, ff) = (self.seq_len, self.model_dim, self.ff_dim);
+        let (attn_w, w1, w2) = self.split_weights(&self.weights.data);
+        let (attn_b, b1, b2) = self.split_biases(&self.biases.data);
+
+        let attention = attention_forward(self.dims(), input, attn_w, attn_b);
+        let residual: Vec<f64> = input.iter().zip(&attention.output).map(|(x, a)| x + a).collect();
+        let hidden_pre = linear(&residual, t, d, w1, ff, 0, ff, b1);
+        let hidden: Vec<f64> = hidden_pre.iter().map(|z| z.max(0.0)).collect();
+        let ffn = linear(&hidden, t, ff, w2, d, 0, d, b2);
+        let output = residual.iter().zip(&ffn).map(|(r, f)| r + f).collect();
+        BlockCache { attention, residual, hidden_pre, hidden, output }
+    }
+}
+
+impl LayerOps for TransformerBlock {
+    fn kind(&self) -> &'static str {
+        "transformer_block"
+    }
+
+    fn input_size(&self) -> usize {
+        self.seq_len * self.model_dim
+    }
+
+    fn output_size(&self) -> usize {
+        self.seq_len * self.model_dim
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.cache = self.run(input);
+        self.cache.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.cache.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.run(input).output;
+    }
+
+    fn backward(&self, input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let (t, d, ff) = (self.seq_len, self.model_dim, self.ff_dim);
+        let cache = &self.cache;
+        let (attn_w, w1, w2) = self.split_weights(&self.weights.data);
+
+        let mut dw = vec![0.0; self.weights.cols];
+        let mut db = vec![0.0; self.biases.cols];
+        let (dw_attn, dw_rest) = dw.split_at_mut(4 * d * d);
+        let (dw1, dw2) = dw_rest.split_at_mut(d * ff);
+        let (db_attn, db_rest) = db.split_at_mut(4 * d);
+        let (db1, db2) = db_rest.split_at_mut(ff);
+
+        // Y = Y1 + FFN(Y1): the residual passes dY straight through.
+        let mut d_residual = output_delta.to_vec();
+        let mut d_hidden = vec![0.0; t * ff];
+        linear_backward(&cache.hidden, t, ff, output_delta, d, w2, d, 0, dw2, db2, &mut d_hidden);
+        for (g, z) in d_hidden.iter_mut().zip(&cache.hidden_pre) {
+            if *z <= 0.0 {
+                *g = 0.0;
+            }
+        }
+        linear_backward(&cache.residual, t, d, &d_hidden, ff, w1, ff, 0, dw1, db1, &mut d_residual);
+
+        // Y1 = X + Attention(X).
+        let mut dx = d_residual.clone();
+        attention_backward(self.dims(), input, &cache.attention, &d_residual, attn_w, dw_attn, db_attn, &mut dx);
+
+        LayerGradients {
+            weights: Matrix::from_flat(1, self.weights.cols, dw),
+            biases: Matrix::from_flat(1, self.biases.cols, db),
+            input_delta: want_input_delta.then_some(dx),
+        }
+    }
+
+    /// Per-token projections plus the score and mixing products.
+    fn macs(&self) -> usize {
+        self.seq_len * self.weights.data.len() + score_macs(self.seq_len, self.model_dim)
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.weights, &self.biases)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.weights, &mut self.biases)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("transformer block serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Attention math on flat row-major buffers
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+struct AttentionDims {
+    seq_len: usize,
+    model_dim: usize,
+    heads: usize,
+    causal: bool,
+}
+
+/// Forward-pass intermediates kept for `attention_backward`; every buffer is
+/// `seq_len × model_dim` except `probs` (`heads × seq_len × seq_len`).
+#[derive(Debug, Clone, Default)]
+struct AttentionCache {
+    q: Vec<f64>,
+    k: Vec<f64>,
+    v: Vec<f64>,
+    probs: Vec<f64>,
+    concat: Vec<f64>,
+    output: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BlockCache {
+    attention: AttentionCache,
+    residual: Vec<f64>,
+    hidden_pre: Vec<f64>,
+    hidden: Vec<f64>,
+    output: Vec<f64>,
+}
+
+/// Xavier-initialized `[Wq | Wk | Wv | Wo]`, row-major `d × 4d`.
+fn attention_weights<R: Rng + ?Sized>(d: usize, rng: &mut R) -> Vec<f64> {
+    let blocks: Vec<Matrix> = (0..4).map(|_| Matrix::xavier_with(d, d, rng)).collect();
+    (0..d).flat_map(|i| blocks.iter().flat_map(move |m| m.row(i).iter().copied())).collect()
+}
+
+/// Multiply-adds of `Q·Kᵀ` and `softmax(·)·V` over all heads (the full
+/// `seq_len × seq_len` score matrix, even when causal).
+fn score_macs(seq_len: usize, d: usize) -> usize {
+    2 * seq_len * seq_len * d
+}
+
+/// `x · W[:, col_off..col_off + d_out] + b[col_off..]` for `rows` rows of
+/// `x` (`rows × d_in`), where `w` is row-major with `w_cols` columns.
+#[allow(clippy::too_many_arguments)]
+fn linear(x: &[f64], rows: usize, d_in: usize, w: &[f64], w_cols: usize, col_off: usize, d_out: usize, b: &[f64]) -> Vec<f64> {
+    let mut out = Vec::with_capacity(rows * d_out);
+    for r in 0..rows {
+        let mut y = b[col_off..col_off + d_out].to_vec();
+        for (i, xv) in x[r * d_in..(r + 1) * d_in].iter().enumerate() {
+            let w_row = &w[i * w_cols + col_off..i * w_cols + col_off + d_out];
+            y.iter_mut().zip(w_row).for_each(|(o, wv)| *o += xv * wv);
+        }
+        out.extend(y);
+    }
+    out
+}
+
+/// Backward of `linear`: accumulates into `dw` / `db` (same layout as `w` /
+/// `b`) and adds `dy · Wᵀ` into `dx`.
+#[allow(clippy::too_many_arguments)]
+fn linear_backward(
+    x: &[f64],
+    rows: usize,
+    d_in: usize,
+    dy: &[f64],
+    d_out: usize,
+    w: &[f64],
+    w_cols: usize,
+    col_off: usize,
+    dw: &mut [f64],
+    db: &mut [f64],
+    dx: &mut [f64],
+) {
+    for r in 0..rows {
+        let g = &dy[r * d_out..(r + 1) * d_out];
+        db[col_off..col_off + d_out].iter_mut().zip(g).for_each(|(b, gv)| *b += gv);
+        for i in 0..d_in {
+            let span = i * w_cols + col_off..i * w_cols + col_off + d_out;
+            let xv = x[r * d_in + i];
+            dw[span.clone()].iter_mut().zip(g).for_each(|(dwv, gv)| *dwv += xv * gv);
+            dx[r * d_in + i] += w[span].iter().zip(g).map(|(wv, gv)| wv * gv).sum::<f64>();
+        }
+    }
+}
+
+fn attention_forward(dims: AttentionDims, x: &[f64], w: &[f64], b: &[f64]) -> AttentionCache {
+    let AttentionDims { seq_len: t, model_dim: d, heads, causal } = dims;
+    let dh = d / heads;
+    let scale = 1.0 / (dh as f64).sqrt();
+    let q = linear(x, t, d, w, 4 * d, 0, d, b);
+    let k = linear(x, t, d, w, 4 * d, d, d, b);
+    let v = linear(x, t, d, w, 4 * d, 2 * d, d, b);
+
+    let mut probs = vec![0.0; heads * t * t];
+    let mut concat = vec![0.0; t * d];
+    for h in 0..heads {
+        let cols = h * dh..(h + 1) * dh;
+        for i in 0..t {
+            let row = &mut probs[(h * t + i) * t..(h * t + i + 1) * t];
+            let visible = if causal { i + 1 } else { t };
+            for (j, p) in row.iter_mut().enumerate().take(visible) {
+                *p = scale * (0..dh).map(|c| q[i * d + cols.start + c] * k[j * d + cols.start + c]).sum::<f64>();
+            }
+            let max = row[..visible].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let mut sum = 0.0;
+            for p in row[..visible].iter_mut() {
+                *p = (*p - max).exp();
+                sum += *p;
+            }
+            row[..visible].iter_mut().for_each(|p| *p /= sum);
+            // Masked entries stay exactly 0.
+            for (j, &p) in row[..visible].iter().enumerate() {
+                for c in cols.clone() {
+                    concat[i * d + c] += p * v[j * d + c];
+                }
+            }
+        }
+    }
+    let output = linear(&concat, t, d, w, 4 * d, 3 * d, d, b);
+    AttentionCache { q, k, v, probs, concat, output }
+}
+
+/// Backward of `attention_forward`: accumulates parameter gradients into
+/// `dw` / `db` and adds ∂L/∂x into `dx`.
+#[allow(clippy::too_many_arguments)]
+fn attention_backward(
+    dims: AttentionDims,
+    x: &[f64],
+    cache: &AttentionCache,
+    dy: &[f64],
+    w: &[f64],
+    dw: &mut [f64],
+    db: &mut [f64],
+    dx: &mut [f64],
+) {
+    let AttentionDims { seq_len: t, model_dim: d, heads, .. } = dims;
+    let dh = d / heads;
+    let scale = 1.0 / (dh as f64).sqrt();
+
+    let mut d_concat = vec![0.0; t * d];
+    linear_backward(&cache.concat, t, d, dy, d, w, 4 * d, 3 * d, dw, db, &mut d_concat);
+
+    let (mut dq, mut dk, mut dv) = (vec![0.0; t * d], vec![0.0; t * d], vec![0.0; t * d]);
+    for h in 0..heads {
+        let cols = h * dh..(h + 1) * dh;
+        for i in 0..t {
+            let probs = &cache.probs[(h * t + i) * t..(h * t + i + 1) * t];
+            let d_probs: Vec<f64> = (0..t)
+                .map(|j| cols.clone().map(|c| d_concat[i * d + c] * cache.v[j * d + c]).sum())
+                .collect();
+            for (j, &p) in probs.iter().enumerate() {
+                for c in cols.clone() {
+                    dv[j * d + c] += p * d_concat[i * d + c];
+                }
+            }
+            // Softmax Jacobian: dS_j = p_j · (dP_j − Σ_u p_u · dP_u).
+            let dot: f64 = probs.iter().zip(&d_probs).map(|(p, g)| p * g).sum();
+            for (j, (&p, &g)) in probs.iter().zip(&d_probs).enumerate() {
+                let ds = p * (g - dot) * scale;
+                if ds == 0.0 {
+                    continue;
+                }
+                for c in cols.clone() {
+                    dq[i * d + c] += ds * cache.k[j * d + c];
+                    dk[j * d + c] += ds * cache.q[i * d + c];
+                }
+            }
+        }
+    }
+    linear_backward(x, t, d, &dq, d, w, 4 * d, 0, dw, db, dx);
+    linear_backward(x, t, d, &dk, d, w, 4 * d, d, dw, db, dx);
+    linear_backward(x, t, d, &dv, d, w, 4 * d, 2 * d, dw, db, dx);
+}
+
+fn check_dims(seq_len: usize, model_dim: usize, heads: usize) {
+    assert!(seq_len > 0 && model_dim > 0 && heads > 0, "attention sizes must be at least 1");
+    assert!(model_dim.is_multiple_of(heads), "heads ({}) must divide model_dim ({})", heads, model_dim);
+}
+
+fn check_input_len(layer: &dyn LayerOps, input: &[f64]) {
+    assert_eq!(
+        input.len(),
+        layer.input_size(),
+        "{} expects {} inputs, got {}",
+        layer.name().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| layer.kind().into()),
+        layer.input_size(),
+        input.len()
+    );
+}
@@ -1,6 +1,12 @@
-use crate::{math::matrix::Matrix, activation::activation::ActivationFunction};
+use std::any::Any;
+
+use crate::{math::matrix::{gemm_tiled, Matrix, ShapeError}, math::vector::{add_outer, mat_vec, outer, vec_mat}, math::init::WeightInit, activation::activation::ActivationFunction};
+use crate::layers::ops::{LayerGradients, LayerOps};
+use rand::Rng;
 use serde::{Serialize, Deserialize};
 
+/// Fully connected layer: `a = σ(x·W + b)`.  The first `LayerOps`
+/// implementation, and the only one `NetworkSpec` builds.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Layer{
     pub size: usize,
@@ -10,21 +16,53 @@ pub struct Layer{
     pre_neurons: Matrix,  // pre-activation values (z = Wx + b) needed for correct derivative
     pub weights: Matrix,
     pub biases: Matrix,
-    pub activator: ActivationFunction
+    pub activator: ActivationFunction,
+    /// Optional human-readable name (e.g. `"hidden2"`) used in diagnostics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// L2 strength for this layer's weights, overriding `TrainConfig::l2`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l2: Option<f64>,
+    /// Softmax layers only: `false` entries are excluded from the softmax
+    /// (their logits treated as −∞), so they always get probability 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mask: Option<Vec<bool>>,
 }
 
+/// The dense `Layer` under the name other layer types follow.
+pub type Dense = Layer;
+
 impl Layer {
     pub fn new(size: usize, input_size: usize, activation: ActivationFunction) -> Layer {
+        Layer::new_with_rng(size, input_size, activation, &mut rand::thread_rng())
+    }
+
+    /// `new` with the initial weights drawn from `rng`; pass a seeded
+    /// generator for reproducible initialization.
+    pub fn new_with_rng<R: Rng + ?Sized>(
+        size: usize,
+        input_size: usize,
+        activation: ActivationFunction,
+        rng: &mut R,
+    ) -> Layer {
+        // Default scheme by downstream activation: He before ReLU, Xavier
+        // otherwise (see `WeightInit::for_activation`).
+        let init = WeightInit::for_activation(&activation);
+        Layer::new_with_init(size, input_size, activation, init, rng)
+    }
+
+    /// `new_with_rng` with an explicit weight initialization scheme.
+    /// Biases are always initialized to zero — a standard safe default.
+    pub fn new_with_init<R: Rng + ?Sized>(
+        size: usize,
+        input_size: usize,
+        activation: ActivationFunction,
+        init: WeightInit,
+        rng: &mut R,
+    ) -> Layer {
         let neurons = Matrix::zeros(1, size);
         let pre_neurons = Matrix::zeros(1, size);
-        // Choose weight initialization scheme based on the downstream activation:
-        //   ReLU  → He init   (variance = 2 / fan_in)
-        //   other → Xavier init (variance = 1 / fan_in)
-        // Biases are always initialized to zero — a standard safe default.
-        let weights = match activation {
-            ActivationFunction::ReLU => Matrix::he(input_size, size),
-            _ => Matrix::xavier(input_size, size),
-        };
+        let weights = init.weights(input_size, size, rng);
         let biases = Matrix::zeros(1, size);
 
         Layer {
@@ -33,47 +71,187 @@ impl Layer {
             pre_neurons,
             weights,
             biases,
-            activator: activation
+            activator: activation,
+            name: None,
+            l2: None,
+            output_mask: None,
+        }
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Layer {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Restricts a Softmax layer to the outputs whose `mask` entry is `true`;
+    /// the others are masked to −∞ before normalization, in training and
+    /// at inference.
+    ///
+    /// # Panics
+    /// Panics if the layer is not Softmax, `mask.len()` differs from the
+    /// layer size, or no output is allowed.
+    pub fn with_output_mask(mut self, mask: Vec<bool>) -> Layer {
+        assert_eq!(self.activator, ActivationFunction::Softmax, "an output mask requires a Softmax layer");
+        check_mask(&mask, self.size);
+        self.output_mask = Some(mask);
+        self
+    }
+
+    /// Regularizes this layer's weights with `l2` instead of the global
+    /// `TrainConfig::l2` (0 exempts the layer).
+    pub fn with_l2(mut self, l2: f64) -> Layer {
+        self.l2 = Some(l2);
+        self
+    }
+
+    /// `"layer 'hidden2'"` if the layer is named, otherwise `"layer {index}"`.
+    /// `index` is the 0-based position of the layer in its network.
+    pub fn label(&self, index: usize) -> String {
+        match self.name {
+            Some(ref name) => format!("layer '{}'", name),
+            None => format!("layer {}", index),
         }
     }
 
     pub fn feed_from(&mut self, input: Vec<f64>) -> Vec<f64> {
+        self.feed(&input)
+    }
+
+    /// `feed_from` over a borrowed input.
+    fn feed(&mut self, input: &[f64]) -> Vec<f64> {
         // z = W·x + b  (shape 1×size)
-        let z = Matrix::from_data(vec![input]) * self.weights.clone() + self.biases.clone();
+        let mut z = Matrix::from_flat(1, self.size, vec_mat(input, &self.weights));
+        z.add_assign_mat(&self.biases);
 
         // Apply activation — Softmax requires the full vector; all others are element-wise.
         let a = match &self.activator {
             ActivationFunction::Softmax => {
-                // Numerically stable softmax: subtract max(z) before exp to
-                // prevent overflow while preserving the output distribution.
-                let logits = &z.data[0];
-                let max_z = logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-                let exps: Vec<f64> = logits.iter().map(|&v| (v - max_z).exp()).collect();
-                let sum_exps: f64 = exps.iter().sum();
-                let softmax: Vec<f64> = exps.iter().map(|&e| e / sum_exps).collect();
-                Matrix::from_data(vec![softmax])
+                let mut softmax = z.data.clone();
+                self.softmax(&mut softmax);
+                Matrix::from_flat(1, softmax.len(), softmax)
             }
             _ => z.map(|x| self.activator.function(x)),
         };
 
         self.pre_neurons = z;
         self.neurons = a.clone();
-        a.data[0].clone()
+        a.data.clone()
+    }
+
+    /// Inference-only forward pass: writes this layer's activations for
+    /// `input` into `output` (cleared first) without touching the cached
+    /// training state, so it only needs `&self`.
+    ///
+    /// # Panics
+    /// Panics if `input.len()` differs from the layer's input size.
+    pub fn infer_into(&self, input: &[f64], output: &mut Vec<f64>) {
+        self.linear_into(input, output);
+        self.activate_in_place(output, 1.0);
+    }
+
+    /// Inference-only forward pass over a batch: returns one activation
+    /// vector per input.  The batch goes through one blocked matrix product
+    /// instead of one vector-matrix product per sample, which is what makes
+    /// batching pay off for larger layers.
+    ///
+    /// # Panics
+    /// Panics if any input's length differs from the layer's input size.
+    pub fn infer_batch(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let mut outputs = self.linear_batch(inputs);
+        for output in &mut outputs {
+            self.activate_in_place(output, 1.0);
+        }
+        outputs
+    }
+
+    /// Writes the pre-activation values `z = x·W + b` for `input` into
+    /// `output` (cleared first).
+    pub(crate) fn linear_into(&self, input: &[f64], output: &mut Vec<f64>) {
+        self.check_input_len(input);
+        output.clear();
+        output.extend_from_slice(&self.biases.data);
+        for (x, row) in input.iter().zip(self.weights.row_iter()) {
+            for (o, w) in output.iter_mut().zip(row.iter()) {
+                *o += x * w;
+            }
+        }
+    }
+
+    /// Batched `linear_into`: the inputs are packed into one matrix and
+    /// multiplied by the weights in a single product (see `linear_matrix`).
+    pub(crate) fn linear_batch(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().for_each(|input| self.check_input_len(input));
+        self.linear_matrix(&Matrix::from_flat(inputs.len(), self.weights.rows, inputs.concat())).to_rows()
+    }
+
+    /// `Z = X·W + b` for a `batch × input_size` matrix `X`.  Every row starts
+    /// from b and accumulates X·W on top, so it sums in the same order as
+    /// `linear_into` and matches it exactly.
+    fn linear_matrix(&self, inputs: &Matrix) -> Matrix {
+        let mut z = Matrix::zeros(inputs.rows, self.size);
+        z.add_assign_mat(&self.biases);
+        gemm_tiled(inputs.view(), self.weights.view(), &mut z);
+        z
+    }
+
+    /// Applies the activation to pre-activation values in place.  For Softmax
+    /// the logits are divided by `temperature` first (temperature scaling);
+    /// other activations ignore it.
+    pub(crate) fn activate_in_place(&self, z: &mut [f64], temperature: f64) {
+        match &self.activator {
+            ActivationFunction::Softmax => {
+                if temperature != 1.0 {
+                    z.iter_mut().for_each(|v| *v /= temperature);
+                }
+                self.softmax(z);
+            }
+            act => z.iter_mut().for_each(|v| *v = act.function(*v)),
+        }
+    }
+
+    /// Softmax over `logits`, honouring `output_mask`.
+    fn softmax(&self, logits: &mut [f64]) {
+        match &self.output_mask {
+            Some(mask) => masked_softmax_in_place(logits, mask),
+            None => softmax_in_place(logits),
+        }
+    }
+
+    fn check_input_len(&self, input: &[f64]) {
+        assert_eq!(
+            input.len(),
+            self.weights.rows,
+            "{} expects {} inputs, got {}",
+            self.name.as_ref().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| "layer".into()),
+            self.weights.rows,
+            input.len()
+        );
     }
 
     /// Computes gradient adjustments. Returns (weights_grad, biases_grad).
     /// `next_layer_delta` is ∂L/∂a for this layer (error in activation space).
     pub fn compute_gradients(
         &self,
-        next_layer_delta: Matrix,
+        next_layer_delta: &Matrix,
         inputs: &Matrix,
     ) -> (Matrix, Matrix) {
         // Use pre-activation z so that derivative(z) = σ'(z) is computed correctly
-        let act_derivative = self.pre_neurons.map(|x| self.activator.derivative(x));
+        let mut layer_delta = self.pre_neurons.map(|x| self.activator.derivative(x));
         // Element-wise (Hadamard) product: δ = error ⊙ σ'(z)
-        let layer_delta = hadamard(&next_layer_delta, &act_derivative);
+        layer_delta.hadamard_assign(next_layer_delta);
+        self.compute_logit_gradients(layer_delta, inputs)
+    }
 
-        let weights_adjustment = inputs.transpose() * layer_delta.clone();
+    /// Like `compute_gradients`, but `layer_delta` is already ∂L/∂z (error in
+    /// pre-activation space), so the activation derivative is not applied.
+    /// Used for fused loss/activation pairs such as Sigmoid + BCE.
+    pub fn compute_logit_gradients(
+        &self,
+        layer_delta: Matrix,
+        inputs: &Matrix,
+    ) -> (Matrix, Matrix) {
+        let weights_adjustment = inputs.matmul_tn(&layer_delta);
         let biases_adjustment = layer_delta;
 
         (weights_adjustment, biases_adjustment)
@@ -81,19 +259,199 @@ impl Layer {
 
     /// Applies pre-computed gradients scaled by lr.
     pub fn apply_gradients(&mut self, weights_grad: Matrix, biases_grad: Matrix, lr: f64) {
-        self.weights = self.weights.clone() - weights_grad.map(|x| x * lr);
-        self.biases = self.biases.clone() - biases_grad.map(|x| x * lr);
+        let (mut weights_grad, mut biases_grad) = (weights_grad, biases_grad);
+        weights_grad.scale_mut(lr);
+        biases_grad.scale_mut(lr);
+        self.weights.sub_assign_mat(&weights_grad);
+        self.biases.sub_assign_mat(&biases_grad);
+    }
+
+    /// δ = ∂L/∂a ⊙ σ'(z) for the cached pre-activations, as in
+    /// `compute_gradients`.
+    fn layer_delta(&self, output_delta: &[f64]) -> Vec<f64> {
+        output_delta.iter()
+            .zip(&self.pre_neurons.data)
+            .map(|(g, &z)| g * self.activator.derivative(z))
+            .collect()
+    }
+
+    /// `compute_logit_gradients` for one sample: ∂L/∂W = xᵀ·δ is the outer
+    /// product of the input and δ.
+    fn sample_gradients(&self, input: &[f64], layer_delta: Vec<f64>) -> (Matrix, Matrix) {
+        (outer(input, &layer_delta), Matrix::from_flat(1, layer_delta.len(), layer_delta))
+    }
+
+    /// Packs `(weights_grad, biases_grad)` from `compute_gradients` into
+    /// `LayerGradients`, adding ∂L/∂input = δ · Wᵀ when requested.
+    fn layer_gradients(&self, (weights, biases): (Matrix, Matrix), want_input_delta: bool) -> LayerGradients {
+        // Row i of W holds input i's outgoing weights: (δ · Wᵀ)_i = W_i · δ.
+        let input_delta = want_input_delta.then(|| mat_vec(&self.weights, &biases.data));
+        LayerGradients { weights, biases, input_delta }
+    }
+}
+
+impl LayerOps for Layer {
+    fn kind(&self) -> &'static str {
+        "dense"
+    }
+
+    fn input_size(&self) -> usize {
+        self.weights.rows
+    }
+
+    fn output_size(&self) -> usize {
+        self.size
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn activation(&self) -> Option<&ActivationFunction> {
+        Some(&self.activator)
+    }
+
+    fn l2(&self) -> Option<f64> {
+        self.l2
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.feed(input)
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.neurons.data
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, temperature: f64) {
+        self.linear_into(input, output);
+        self.activate_in_place(output, temperature);
+    }
+
+    fn predict_batch(&self, inputs: &[Vec<f64>], temperature: f64) -> Vec<Vec<f64>> {
+        let mut outputs = self.linear_batch(inputs);
+        outputs.iter_mut().for_each(|z| self.activate_in_place(z, temperature));
+        outputs
+    }
+
+    fn predict_matrix(&self, inputs: &Matrix, temperature: f64) -> Matrix {
+        assert_eq!(
+            inputs.cols,
+            self.weights.rows,
+            "{} expects {} inputs, got a batch of {} columns",
+            self.name.as_ref().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| "layer".into()),
+            self.weights.rows,
+            inputs.cols
+        );
+        let mut z = self.linear_matrix(inputs);
+        z.row_iter_mut().for_each(|row| self.activate_in_place(row, temperature));
+        z
+    }
+
+    fn pre_activation_into(&self, input: &[f64], output: &mut Vec<f64>) {
+        self.linear_into(input, output);
+    }
+
+    fn backward(&self, input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let layer_delta = self.layer_delta(output_delta);
+        self.layer_gradients(self.sample_gradients(input, layer_delta), want_input_delta)
+    }
+
+    fn backward_logits(&self, input: &[f64], logit_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        self.layer_gradients(self.sample_gradients(input, logit_delta.to_vec()), want_input_delta)
+    }
+
+    fn backward_into(
+        &self,
+        input: &[f64],
+        output_delta: &[f64],
+        is_logit: bool,
+        want_input_delta: bool,
+        (weights_grad, biases_grad): &mut (Matrix, Matrix),
+    ) -> Result<Option<Vec<f64>>, ShapeError> {
+        // The gradients are input.len() × δ.len() and 1 × δ.len(), as the
+        // matrices `backward` returns would be.
+        for (acc, shape) in [(&*weights_grad, (input.len(), output_delta.len())), (&*biases_grad, (1, output_delta.len()))] {
+            if (acc.rows, acc.cols) != shape {
+                return Err(ShapeError { op: "add", left: (acc.rows, acc.cols), right: shape });
+            }
+        }
+
+        // With a logit delta δ is the delta itself; borrow it instead of copying.
+        let activated;
+        let layer_delta: &[f64] = if is_logit {
+            output_delta
+        } else {
+            activated = self.layer_delta(output_delta);
+            &activated
+        };
+        add_outer(weights_grad, input, layer_delta);
+        biases_grad.data.iter_mut().zip(layer_delta).for_each(|(b, d)| *b += d);
+        Ok(want_input_delta.then(|| mat_vec(&self.weights, layer_delta)))
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.weights, &self.biases)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.weights, &mut self.biases)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("dense layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Numerically stable softmax: subtract max(z) before exp to prevent overflow
+/// while preserving the output distribution.
+fn softmax_in_place(logits: &mut [f64]) {
+    let max_z = logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mut sum_exps = 0.0;
+    for v in logits.iter_mut() {
+        *v = (*v - max_z).exp();
+        sum_exps += *v;
+    }
+    for v in logits.iter_mut() {
+        *v /= sum_exps;
+    }
+}
+
+/// Softmax over the entries whose `allowed` flag is set; the rest are
+/// treated as −∞ and come out as exactly 0.
+pub(crate) fn masked_softmax_in_place(logits: &mut [f64], allowed: &[bool]) {
+    let max_z = logits.iter().zip(allowed)
+        .filter(|(_, &ok)| ok)
+        .map(|(&v, _)| v)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut sum_exps = 0.0;
+    for (v, &ok) in logits.iter_mut().zip(allowed) {
+        *v = if ok { (*v - max_z).exp() } else { 0.0 };
+        sum_exps += *v;
+    }
+    for v in logits.iter_mut() {
+        *v /= sum_exps;
     }
 }
 
-/// Element-wise (Hadamard) product of two same-shape matrices.
-fn hadamard(a: &Matrix, b: &Matrix) -> Matrix {
-    assert_eq!(a.rows, b.rows);
-    assert_eq!(a.cols, b.cols);
-    let data = a.data.iter().zip(b.data.iter())
-        .map(|(row_a, row_b)| {
-            row_a.iter().zip(row_b.iter()).map(|(x, y)| x * y).collect()
-        })
-        .collect();
-    Matrix::from_data(data)
+/// Panics unless `mask` has `size` entries and allows at least one.
+pub(crate) fn check_mask(mask: &[bool], size: usize) {
+    assert_eq!(mask.len(), size, "output mask has {} entries but the layer has {} outputs", mask.len(), size);
+    assert!(mask.iter().any(|&ok| ok), "output mask must allow at least one output");
 }
@@ -1,3 +1,35 @@
+pub mod attention;
 pub mod dense;
+pub mod norm;
+pub mod ops;
+pub mod pool;
+pub mod rnn;
 
-pub use dense::Layer;
+pub use attention::{SelfAttention, TransformerBlock};
+pub use dense::{Dense, Layer};
+pub use norm::GroupNorm;
+pub use ops::{LayerGradients, LayerOps};
+pub use pool::{AvgPool2D, GlobalAvgPool, MaxPool2D};
+pub use rnn::Rnn;
+
+/// Rebuilds a layer from its serialized form, dispatching on the `type` tag
+/// (entries without one are dense layers from older model files).
+pub fn layer_from_json(mut value: serde_json::Value) -> Result<Box<dyn LayerOps>, String> {
+    let kind = match value.as_object_mut().and_then(|fields| fields.remove("type")) {
+        Some(serde_json::Value::String(kind)) => kind,
+        Some(other) => return Err(format!("layer type must be a string, got {}", other)),
+        None => "dense".to_owned(),
+    };
+    let parse_err = |e: serde_json::Error| format!("invalid {} layer: {}", kind, e);
+    match kind.as_str() {
+        "dense"           => Ok(Box::new(serde_json::from_value::<Layer>(value).map_err(parse_err)?)),
+        "max_pool2d"      => Ok(Box::new(serde_json::from_value::<MaxPool2D>(value).map_err(parse_err)?)),
+        "avg_pool2d"      => Ok(Box::new(serde_json::from_value::<AvgPool2D>(value).map_err(parse_err)?)),
+        "global_avg_pool" => Ok(Box::new(serde_json::from_value::<GlobalAvgPool>(value).map_err(parse_err)?)),
+        "group_norm"      => Ok(Box::new(serde_json::from_value::<GroupNorm>(value).map_err(parse_err)?)),
+        "rnn"             => Ok(Box::new(serde_json::from_value::<Rnn>(value).map_err(parse_err)?)),
+        "self_attention"  => Ok(Box::new(serde_json::from_value::<SelfAttention>(value).map_err(parse_err)?)),
+        "transformer_block" => Ok(Box::new(serde_json::from_value::<TransformerBlock>(value).map_err(parse_err)?)),
+        other => Err(format!("unknown layer type '{}'", other)),
+    }
+}
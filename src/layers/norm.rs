@@ -0,0 +1,232 @@
+use std::any::Any;
+
+use serde::{Serialize, Deserialize};
+
+use crate::layers::ops::{LayerGradients, LayerOps};
+use crate::math::matrix::Matrix;
+
+fn default_eps() -> f64 {
+    1e-5
+}
+
+/// Group normalization over channel-major inputs.
+///
+/// Inputs are flattened `channels × spatial` tensors (index `c·spatial + i`;
+/// `spatial` is `H·W` for images and 1 for plain feature vectors).  The
+/// channels are split into `groups` equal groups, and every group is
+/// normalized to zero mean and unit variance over its own channels and
+/// positions, then scaled and shifted per channel: `y = γ_c·x̂ + β_c`.
+///
+/// Statistics come from the single sample, so the layer behaves the same at
+/// any batch size and at inference.  `groups = 1` is layer normalization;
+/// `groups = channels` is instance normalization.
+///
+/// γ and β are exempt from the global L2 penalty (`LayerOps::l2` is 0).
+///
+/// # Fields
+/// - `weights` — per-channel scale γ, `1 × channels`, initialized to 1
+/// - `biases`  — per-channel shift β, `1 × channels`, initialized to 0
+/// - `eps`     — added to the variance for numerical stability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupNorm {
+    pub groups: usize,
+    pub channels: usize,
+    pub spatial: usize,
+    #[serde(default = "default_eps")]
+    pub eps: f64,
+    pub weights: Matrix,
+    pub biases: Matrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Normalized inputs x̂ from the last `forward`.
+    #[serde(skip)]
+    normalized: Vec<f64>,
+    /// `1 / √(σ² + eps)` of each group from the last `forward`.
+    #[serde(skip)]
+    inv_std: Vec<f64>,
+    #[serde(skip)]
+    output: Vec<f64>,
+}
+
+impl GroupNorm {
+    /// Normalization of `channels × spatial` inputs in `groups` groups.
+    ///
+    /// # Panics
+    /// Panics if a size is 0 or `groups` does not divide `channels`.
+    pub fn new(groups: usize, channels: usize, spatial: usize) -> GroupNorm {
+        assert!(groups > 0 && channels > 0 && spatial > 0, "group norm sizes must be at least 1");
+        assert!(
+            channels.is_multiple_of(groups),
+            "groups ({}) must divide channels ({})",
+            groups,
+            channels
+        );
+        GroupNorm {
+            groups,
+            channels,
+            spatial,
+            eps: default_eps(),
+            weights: Matrix::from_data(vec![vec![1.0; channels]]),
+            biases: Matrix::zeros(1, channels),
+            name: None,
+            normalized: Vec::new(),
+            inv_std: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Sets the variance epsilon (default `1e-5`).
+    pub fn with_eps(mut self, eps: f64) -> GroupNorm {
+        self.eps = eps;
+        self
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> GroupNorm {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Number of input values in one group.
+    fn group_len(&self) -> usize {
+        self.channels / self.groups * self.spatial
+    }
+
+    /// Returns `(output, x̂, inv_std per group)` for `input`.
+    fn normalize(&self, input: &[f64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        assert_eq!(
+            input.len(),
+            self.input_size(),
+            "{} expects {} channels × {} values, got {} values",
+            self.name.as_ref().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| "group norm layer".into()),
+            self.channels,
+            self.spatial,
+            input.len()
+        );
+        let n = self.group_len() as f64;
+        let mut normalized = Vec::with_capacity(input.len());
+        let mut inv_std = Vec::with_capacity(self.groups);
+        for group in input.chunks(self.group_len()) {
+            let mean = group.iter().sum::<f64>() / n;
+            let var = group.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+            let inv = 1.0 / (var + self.eps).sqrt();
+            normalized.extend(group.iter().map(|x| (x - mean) * inv));
+            inv_std.push(inv);
+        }
+        let (gamma, beta) = (&self.weights.data, &self.biases.data);
+        let output = normalized
+            .chunks(self.spatial)
+            .enumerate()
+            .flat_map(|(c, plane)| plane.iter().map(move |x| gamma[c] * x + beta[c]))
+            .collect();
+        (output, normalized, inv_std)
+    }
+}
+
+impl LayerOps for GroupNorm {
+    fn kind(&self) -> &'static str {
+        "group_norm"
+    }
+
+    fn input_size(&self) -> usize {
+        self.channels * self.spatial
+    }
+
+    fn output_size(&self) -> usize {
+        self.channels * self.spatial
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn l2(&self) -> Option<f64> {
+        Some(0.0)
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        let (output, normalized, inv_std) = self.normalize(input);
+        self.normalized = normalized;
+        self.inv_std = inv_std;
+        self.output = output;
+        self.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.normalize(input).0;
+    }
+
+    fn backward(&self, _input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let gamma = &self.weights.data;
+        let mut weights = Matrix::zeros(1, self.channels);
+        let mut biases = Matrix::zeros(1, self.channels);
+        // ∂L/∂x̂ = ∂L/∂y · γ_c.
+        let mut d_norm = Vec::with_capacity(output_delta.len());
+        for (c, (g_plane, x_plane)) in output_delta
+            .chunks(self.spatial)
+            .zip(self.normalized.chunks(self.spatial))
+            .enumerate()
+        {
+            for (g, x) in g_plane.iter().zip(x_plane) {
+                weights.data[c] += g * x;
+                biases.data[c] += g;
+                d_norm.push(g * gamma[c]);
+            }
+        }
+
+        let input_delta = want_input_delta.then(|| {
+            let n = self.group_len() as f64;
+            let mut input_delta = Vec::with_capacity(d_norm.len());
+            for ((dn, xn), inv) in d_norm
+                .chunks(self.group_len())
+                .zip(self.normalized.chunks(self.group_len()))
+                .zip(&self.inv_std)
+            {
+                // dx = inv_std / N · (N·dx̂ − Σdx̂ − x̂·Σ(dx̂·x̂)).
+                let sum: f64 = dn.iter().sum();
+                let dot: f64 = dn.iter().zip(xn).map(|(d, x)| d * x).sum();
+                input_delta.extend(dn.iter().zip(xn).map(|(d, x)| inv / n * (n * d - sum - x * dot)));
+            }
+            input_delta
+        });
+
+        LayerGradients { weights, biases, input_delta }
+    }
+
+    /// One multiply-add to normalize each value and one for the affine map.
+    fn macs(&self) -> usize {
+        2 * self.channels * self.spatial
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.weights, &self.biases)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.weights, &mut self.biases)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("group norm layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
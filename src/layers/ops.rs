@@ -0,0 +1,215 @@
+use std::any::Any;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as _;
+
+use crate::activation::activation::ActivationFunction;
+use crate::layers::dense::Layer;
+use crate::math::matrix::{Matrix, ShapeError};
+
+/// Behaviour shared by every layer type a `Network` can hold.
+///
+/// A layer maps a flat input vector to a flat output vector.  During
+/// training `forward` caches whatever `backward` needs (pre-activations,
+/// argmax indices, …) and the network backpropagates through the layers in
+/// reverse order.  The inference methods take `&self` so a shared network
+/// can serve several threads.
+///
+/// Every layer exposes exactly one `(weights, biases)` parameter pair, which
+/// is what the optimizers, weight decay and `LayerStats` operate on.
+/// Parameter-free layers (pooling, …) return empty 0×0 matrices.
+///
+/// To persist a new layer type, give it a `kind` tag, implement `to_json`
+/// and add it to `layer_from_json` in `layers`.
+pub trait LayerOps: fmt::Debug + Send + Sync {
+    /// Serialization tag, e.g. `"dense"`.
+    fn kind(&self) -> &'static str;
+
+    /// Length of the input vector this layer accepts.
+    fn input_size(&self) -> usize;
+
+    /// Length of the output vector this layer produces.
+    fn output_size(&self) -> usize;
+
+    /// Optional human-readable name used in diagnostics.
+    fn name(&self) -> Option<&str>;
+
+    fn set_name(&mut self, name: Option<String>);
+
+    /// The activation applied to the output, for layers that have one.
+    fn activation(&self) -> Option<&ActivationFunction> {
+        None
+    }
+
+    /// Per-layer L2 strength overriding `TrainConfig::l2`, if set.
+    fn l2(&self) -> Option<f64> {
+        None
+    }
+
+    /// `"layer 'hidden2'"` if the layer is named, otherwise `"layer {index}"`.
+    fn label(&self, index: usize) -> String {
+        match self.name() {
+            Some(name) => format!("layer '{}'", name),
+            None => format!("layer {}", index),
+        }
+    }
+
+    /// Training forward pass; caches the state `backward` needs.
+    fn forward(&mut self, input: &[f64]) -> Vec<f64>;
+
+    /// Output of the last `forward` call (the next layer's input during
+    /// backpropagation).
+    fn output(&self) -> &[f64];
+
+    /// Inference-only forward pass: writes the output for `input` into
+    /// `output` (cleared first) without touching the cache.  Softmax outputs
+    /// are divided by `temperature` before normalization; other layers
+    /// ignore it.
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, temperature: f64);
+
+    /// `predict_into` over a batch.
+    fn predict_batch(&self, inputs: &[Vec<f64>], temperature: f64) -> Vec<Vec<f64>> {
+        inputs.iter()
+            .map(|input| {
+                let mut output = Vec::new();
+                self.predict_into(input, &mut output, temperature);
+                output
+            })
+            .collect()
+    }
+
+    /// `predict_batch` over a `batch × input_size` matrix, returning a
+    /// `batch × output_size` matrix.  Dense layers do this as one matrix
+    /// product; the default goes through `predict_batch` row by row.
+    fn predict_matrix(&self, inputs: &Matrix, temperature: f64) -> Matrix {
+        let outputs = self.predict_batch(&inputs.to_rows(), temperature);
+        Matrix::from_flat(outputs.len(), self.output_size(), outputs.concat())
+    }
+
+    /// Output before the activation (the logits of a Softmax layer).
+    /// Layers without an activation return their regular output.
+    fn pre_activation_into(&self, input: &[f64], output: &mut Vec<f64>) {
+        self.predict_into(input, output, 1.0);
+    }
+
+    /// Backpropagates `output_delta` (∂L/∂output) for the sample last passed
+    /// to `forward`, which was `input`.  The input delta is only computed
+    /// when `want_input_delta` is set (it is not needed for the first layer).
+    fn backward(&self, input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients;
+
+    /// Like `backward`, but `logit_delta` is already ∂L/∂z, so the output
+    /// activation's derivative is skipped (fused Sigmoid + BCE).  Layers
+    /// without an activation treat it like `backward`.
+    fn backward_logits(&self, input: &[f64], logit_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        self.backward(input, logit_delta, want_input_delta)
+    }
+
+    /// Like `backward` (`backward_logits` when `is_logit`), but adds the
+    /// weight and bias gradients into `grads` instead of returning them, and
+    /// returns only the input delta.  The training loops call this once per
+    /// layer and sample.  The default goes through `backward`; dense layers
+    /// override it to accumulate without allocating the gradient matrices.
+    ///
+    /// # Errors
+    /// Returns the mismatch if a gradient does not have exactly its
+    /// accumulator's shape; a row gradient is never broadcast.
+    fn backward_into(
+        &self,
+        input: &[f64],
+        output_delta: &[f64],
+        is_logit: bool,
+        want_input_delta: bool,
+        grads: &mut (Matrix, Matrix),
+    ) -> Result<Option<Vec<f64>>, ShapeError> {
+        let layer_grads = if is_logit {
+            self.backward_logits(input, output_delta, want_input_delta)
+        } else {
+            self.backward(input, output_delta, want_input_delta)
+        };
+        grads.0.try_add_assign_exact(&layer_grads.weights)?;
+        grads.1.try_add_assign_exact(&layer_grads.biases)?;
+        Ok(layer_grads.input_delta)
+    }
+
+    /// Multiply-accumulate operations of one inference pass over a single
+    /// sample, used by `Network::cost_estimate`.  The default counts one per
+    /// weight, which is exact for dense layers (and 0 for parameter-free
+    /// ones); layers that reuse their weights across a sequence override it.
+    fn macs(&self) -> usize {
+        self.params().0.data.len()
+    }
+
+    /// The layer's `(weights, biases)`.
+    fn params(&self) -> (&Matrix, &Matrix);
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix);
+
+    fn box_clone(&self) -> Box<dyn LayerOps>;
+
+    /// The layer's serialized form, without the `type` tag.
+    fn to_json(&self) -> serde_json::Value;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Result of `LayerOps::backward` for one sample.
+///
+/// # Fields
+/// - `weights`     — gradient of the loss w.r.t. the weights
+/// - `biases`      — gradient of the loss w.r.t. the biases
+/// - `input_delta` — ∂L/∂input, to pass on to the previous layer; `None`
+///   unless requested
+#[derive(Debug, Clone)]
+pub struct LayerGradients {
+    pub weights: Matrix,
+    pub biases: Matrix,
+    pub input_delta: Option<Vec<f64>>,
+}
+
+impl dyn LayerOps {
+    /// The layer as a dense `Layer`, if it is one.
+    pub fn as_dense(&self) -> Option<&Layer> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn as_dense_mut(&mut self) -> Option<&mut Layer> {
+        self.as_any_mut().downcast_mut()
+    }
+
+    /// Number of trainable parameters.
+    pub fn param_count(&self) -> usize {
+        let (weights, biases) = self.params();
+        weights.rows * weights.cols + biases.rows * biases.cols
+    }
+}
+
+impl Clone for Box<dyn LayerOps> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Serialization: `{"type": "<kind>", ...fields}`; untagged entries are dense
+// layers written before other layer types existed.
+// ---------------------------------------------------------------------------
+
+impl Serialize for Box<dyn LayerOps> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = self.to_json();
+        if let serde_json::Value::Object(ref mut fields) = value {
+            fields.insert("type".to_owned(), serde_json::Value::from(self.kind()));
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn LayerOps> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        crate::layers::layer_from_json(value).map_err(D::Error::custom)
+    }
+}
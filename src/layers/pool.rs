@@ -0,0 +1,455 @@
+use std::any::Any;
+
+use serde::{Serialize, Deserialize};
+
+use crate::layers::ops::{LayerGradients, LayerOps};
+use crate::math::matrix::Matrix;
+
+/// 2-D max pooling over channel-major images.
+///
+/// Inputs are flattened `channels × height × width` tensors (index
+/// `c·H·W + y·W + x`, the layout a Conv2D layer produces and the one grayscale
+/// images already have with `channels = 1`).  Each `size × size` window,
+/// moved by `stride`, is reduced to its maximum; windows that would run past
+/// the edge are dropped.  The output keeps the same channel-major layout.
+///
+/// The position of every window's maximum is stored on `forward`, so
+/// `backward` routes each output gradient to exactly that input element.
+/// The layer has no trainable parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxPool2D {
+    pub channels: usize,
+    pub height: usize,
+    pub width: usize,
+    pub size: usize,
+    pub stride: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Input index of each output's maximum, from the last `forward`.
+    #[serde(skip)]
+    argmax: Vec<usize>,
+    #[serde(skip)]
+    output: Vec<f64>,
+    #[serde(skip)]
+    no_params: (Matrix, Matrix),
+}
+
+impl MaxPool2D {
+    /// Pooling with `size × size` windows moved by `stride` over
+    /// `channels × height × width` inputs.
+    ///
+    /// # Panics
+    /// Panics if `size` or `stride` is 0 or the window is larger than the
+    /// image.
+    pub fn new(channels: usize, height: usize, width: usize, size: usize, stride: usize) -> MaxPool2D {
+        check_geometry(height, width, size, stride);
+        MaxPool2D {
+            channels,
+            height,
+            width,
+            size,
+            stride,
+            name: None,
+            argmax: Vec::new(),
+            output: Vec::new(),
+            no_params: Default::default(),
+        }
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> MaxPool2D {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// `(height, width)` of each output channel.
+    pub fn output_dims(&self) -> (usize, usize) {
+        output_dims(self.height, self.width, self.size, self.stride)
+    }
+
+    /// Max-pools `input`, returning the outputs and the input index of each
+    /// output's maximum.
+    fn pool(&self, input: &[f64]) -> (Vec<f64>, Vec<usize>) {
+        check_input_len(self, input);
+        let (out_h, out_w) = self.output_dims();
+        let mut output = Vec::with_capacity(self.output_size());
+        let mut argmax = Vec::with_capacity(self.output_size());
+        for c in 0..self.channels {
+            let plane = c * self.height * self.width;
+            for oy in 0..out_h {
+                for ox in 0..out_w {
+                    let mut best = plane + oy * self.stride * self.width + ox * self.stride;
+                    for dy in 0..self.size {
+                        for dx in 0..self.size {
+                            let idx = plane + (oy * self.stride + dy) * self.width + ox * self.stride + dx;
+                            if input[idx] > input[best] {
+                                best = idx;
+                            }
+                        }
+                    }
+                    output.push(input[best]);
+                    argmax.push(best);
+                }
+            }
+        }
+        (output, argmax)
+    }
+}
+
+impl LayerOps for MaxPool2D {
+    fn kind(&self) -> &'static str {
+        "max_pool2d"
+    }
+
+    fn input_size(&self) -> usize {
+        self.channels * self.height * self.width
+    }
+
+    fn output_size(&self) -> usize {
+        let (out_h, out_w) = self.output_dims();
+        self.channels * out_h * out_w
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        let (output, argmax) = self.pool(input);
+        self.argmax = argmax;
+        self.output = output.clone();
+        output
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.pool(input).0;
+    }
+
+    fn backward(&self, _input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let input_delta = want_input_delta.then(|| {
+            let mut delta = vec![0.0; self.input_size()];
+            for (&idx, &g) in self.argmax.iter().zip(output_delta.iter()) {
+                delta[idx] += g;
+            }
+            delta
+        });
+        LayerGradients { weights: Matrix::default(), biases: Matrix::default(), input_delta }
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.no_params.0, &self.no_params.1)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.no_params.0, &mut self.no_params.1)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("pooling layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// 2-D average pooling over channel-major images.
+///
+/// Same input layout and window geometry as `MaxPool2D`, but each window is
+/// reduced to its mean, so `backward` spreads every output gradient evenly
+/// over the window.  The layer has no trainable parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvgPool2D {
+    pub channels: usize,
+    pub height: usize,
+    pub width: usize,
+    pub size: usize,
+    pub stride: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip)]
+    output: Vec<f64>,
+    #[serde(skip)]
+    no_params: (Matrix, Matrix),
+}
+
+impl AvgPool2D {
+    /// Pooling with `size × size` windows moved by `stride` over
+    /// `channels × height × width` inputs.
+    ///
+    /// # Panics
+    /// Panics if `size` or `stride` is 0 or the window is larger than the
+    /// image.
+    pub fn new(channels: usize, height: usize, width: usize, size: usize, stride: usize) -> AvgPool2D {
+        check_geometry(height, width, size, stride);
+        AvgPool2D {
+            channels,
+            height,
+            width,
+            size,
+            stride,
+            name: None,
+            output: Vec::new(),
+            no_params: Default::default(),
+        }
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> AvgPool2D {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// `(height, width)` of each output channel.
+    pub fn output_dims(&self) -> (usize, usize) {
+        output_dims(self.height, self.width, self.size, self.stride)
+    }
+
+    /// Calls `f(output_index, input_index)` for every input element of every
+    /// pooling window.
+    fn for_each_window(&self, mut f: impl FnMut(usize, usize)) {
+        let (out_h, out_w) = self.output_dims();
+        for c in 0..self.channels {
+            let plane = c * self.height * self.width;
+            for oy in 0..out_h {
+                for ox in 0..out_w {
+                    let out_idx = (c * out_h + oy) * out_w + ox;
+                    for dy in 0..self.size {
+                        for dx in 0..self.size {
+                            f(out_idx, plane + (oy * self.stride + dy) * self.width + ox * self.stride + dx);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn pool(&self, input: &[f64]) -> Vec<f64> {
+        check_input_len(self, input);
+        let scale = 1.0 / (self.size * self.size) as f64;
+        let mut output = vec![0.0; self.output_size()];
+        self.for_each_window(|o, i| output[o] += input[i] * scale);
+        output
+    }
+}
+
+impl LayerOps for AvgPool2D {
+    fn kind(&self) -> &'static str {
+        "avg_pool2d"
+    }
+
+    fn input_size(&self) -> usize {
+        self.channels * self.height * self.width
+    }
+
+    fn output_size(&self) -> usize {
+        let (out_h, out_w) = self.output_dims();
+        self.channels * out_h * out_w
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.output = self.pool(input);
+        self.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.pool(input);
+    }
+
+    fn backward(&self, _input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let input_delta = want_input_delta.then(|| {
+            let scale = 1.0 / (self.size * self.size) as f64;
+            let mut delta = vec![0.0; self.input_size()];
+            self.for_each_window(|o, i| delta[i] += output_delta[o] * scale);
+            delta
+        });
+        LayerGradients { weights: Matrix::default(), biases: Matrix::default(), input_delta }
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.no_params.0, &self.no_params.1)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.no_params.0, &mut self.no_params.1)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("pooling layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Global average pooling: reduces each channel of a channel-major
+/// `channels × height × width` input to its mean, giving one value per
+/// channel.  Placed after a conv stack it replaces the large flatten + dense
+/// head with a `channels`-wide one.  No trainable parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalAvgPool {
+    pub channels: usize,
+    pub height: usize,
+    pub width: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip)]
+    output: Vec<f64>,
+    #[serde(skip)]
+    no_params: (Matrix, Matrix),
+}
+
+impl GlobalAvgPool {
+    /// # Panics
+    /// Panics if `height` or `width` is 0.
+    pub fn new(channels: usize, height: usize, width: usize) -> GlobalAvgPool {
+        assert!(height > 0 && width > 0, "global pooling needs a non-empty image");
+        GlobalAvgPool { channels, height, width, name: None, output: Vec::new(), no_params: Default::default() }
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> GlobalAvgPool {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn pool(&self, input: &[f64]) -> Vec<f64> {
+        check_input_len(self, input);
+        let plane = self.height * self.width;
+        input.chunks(plane).map(|c| c.iter().sum::<f64>() / plane as f64).collect()
+    }
+}
+
+impl LayerOps for GlobalAvgPool {
+    fn kind(&self) -> &'static str {
+        "global_avg_pool"
+    }
+
+    fn input_size(&self) -> usize {
+        self.channels * self.height * self.width
+    }
+
+    fn output_size(&self) -> usize {
+        self.channels
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.output = self.pool(input);
+        self.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.pool(input);
+    }
+
+    fn backward(&self, _input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let input_delta = want_input_delta.then(|| {
+            let plane = self.height * self.width;
+            output_delta.iter()
+                .flat_map(|&g| vec![g / plane as f64; plane])
+                .collect()
+        });
+        LayerGradients { weights: Matrix::default(), biases: Matrix::default(), input_delta }
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.no_params.0, &self.no_params.1)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.no_params.0, &mut self.no_params.1)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("pooling layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+fn check_geometry(height: usize, width: usize, size: usize, stride: usize) {
+    assert!(size > 0 && stride > 0, "pool size and stride must be at least 1");
+    assert!(
+        size <= height && size <= width,
+        "pool size {} does not fit a {}×{} image",
+        size, height, width
+    );
+}
+
+fn output_dims(height: usize, width: usize, size: usize, stride: usize) -> (usize, usize) {
+    ((height - size) / stride + 1, (width - size) / stride + 1)
+}
+
+fn check_input_len(layer: &dyn LayerOps, input: &[f64]) {
+    assert_eq!(
+        input.len(),
+        layer.input_size(),
+        "{} expects {} inputs, got {}",
+        layer.name().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| layer.kind().into()),
+        layer.input_size(),
+        input.len()
+    );
+}
@@ -0,0 +1,234 @@
+use std::any::Any;
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::layers::ops::{LayerGradients, LayerOps};
+use crate::math::matrix::Matrix;
+use crate::math::vector::dot;
+
+/// Simple recurrent (Elman) layer: `h_t = tanh(x_t·Wx + h_{t−1}·Wh + b)`.
+///
+/// A sample is a whole sequence, flattened step by step: `steps` vectors of
+/// `input_dim` values each (index `t·input_dim + i`).  The hidden state
+/// starts at zero for every sample.  The output is the final hidden state
+/// (`hidden_size` values), or every step's hidden state (`steps ·
+/// hidden_size`) with `return_sequences`.
+///
+/// `backward` runs backpropagation through time over the sequence cached by
+/// `forward`.  With `bptt_steps = Some(k)` it is truncated to the last `k`
+/// steps: earlier steps receive no gradient, which bounds the cost and the
+/// vanishing/exploding-gradient problem on long sequences.
+///
+/// # Fields
+/// - `weights` — `Wx` stacked on top of `Wh`: `(input_dim + hidden_size) ×
+///   hidden_size`
+/// - `biases`  — `1 × hidden_size`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rnn {
+    pub input_dim: usize,
+    pub hidden_size: usize,
+    pub steps: usize,
+    pub return_sequences: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bptt_steps: Option<usize>,
+    pub weights: Matrix,
+    pub biases: Matrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Hidden states of the last `forward`: `h_0 = 0`, then one per step.
+    #[serde(skip)]
+    hidden: Vec<Vec<f64>>,
+    #[serde(skip)]
+    output: Vec<f64>,
+}
+
+impl Rnn {
+    /// An RNN over sequences of `steps` vectors of `input_dim` values, with
+    /// Xavier-initialized weights and zero biases.
+    ///
+    /// # Panics
+    /// Panics if any size is 0.
+    pub fn new(input_dim: usize, hidden_size: usize, steps: usize) -> Rnn {
+        Rnn::new_with_rng(input_dim, hidden_size, steps, &mut rand::thread_rng())
+    }
+
+    /// `new` with the initial weights drawn from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(input_dim: usize, hidden_size: usize, steps: usize, rng: &mut R) -> Rnn {
+        assert!(input_dim > 0 && hidden_size > 0 && steps > 0, "RNN sizes must be at least 1");
+        let wx = Matrix::xavier_with(input_dim, hidden_size, rng);
+        let wh = Matrix::xavier_with(hidden_size, hidden_size, rng);
+        let mut data = wx.data;
+        data.extend(wh.data);
+        Rnn {
+            input_dim,
+            hidden_size,
+            steps,
+            return_sequences: false,
+            bptt_steps: None,
+            weights: Matrix::from_flat(input_dim + hidden_size, hidden_size, data),
+            biases: Matrix::zeros(1, hidden_size),
+            name: None,
+            hidden: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Output every step's hidden state instead of only the last one.
+    pub fn with_return_sequences(mut self, return_sequences: bool) -> Rnn {
+        self.return_sequences = return_sequences;
+        self
+    }
+
+    /// Truncates backpropagation through time to the last `steps` steps.
+    pub fn with_bptt_steps(mut self, steps: usize) -> Rnn {
+        self.bptt_steps = Some(steps);
+        self
+    }
+
+    /// Sets the layer's name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Rnn {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Runs the recurrence over `input`, returning `h_0 … h_T`.
+    fn run(&self, input: &[f64]) -> Vec<Vec<f64>> {
+        assert_eq!(
+            input.len(),
+            self.input_size(),
+            "{} expects {} steps × {} inputs, got {} values",
+            self.name.as_ref().map(|n| format!("layer '{}'", n)).unwrap_or_else(|| "rnn layer".into()),
+            self.steps,
+            self.input_dim,
+            input.len()
+        );
+        let mut hidden = Vec::with_capacity(self.steps + 1);
+        hidden.push(vec![0.0; self.hidden_size]);
+        for x in input.chunks(self.input_dim) {
+            let prev = hidden.last().expect("h_0 is always present");
+            let mut h = self.biases.data.clone();
+            // x_t rows of the stacked weights first, then the h_{t−1} rows.
+            for (v, row) in x.iter().chain(prev.iter()).zip(self.weights.row_iter()) {
+                for (z, w) in h.iter_mut().zip(row.iter()) {
+                    *z += v * w;
+                }
+            }
+            h.iter_mut().for_each(|z| *z = z.tanh());
+            hidden.push(h);
+        }
+        hidden
+    }
+
+    fn collect_output(&self, hidden: &[Vec<f64>]) -> Vec<f64> {
+        if self.return_sequences {
+            hidden[1..].concat()
+        } else {
+            hidden[self.steps].clone()
+        }
+    }
+}
+
+impl LayerOps for Rnn {
+    fn kind(&self) -> &'static str {
+        "rnn"
+    }
+
+    fn input_size(&self) -> usize {
+        self.steps * self.input_dim
+    }
+
+    fn output_size(&self) -> usize {
+        if self.return_sequences { self.steps * self.hidden_size } else { self.hidden_size }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn forward(&mut self, input: &[f64]) -> Vec<f64> {
+        self.hidden = self.run(input);
+        self.output = self.collect_output(&self.hidden);
+        self.output.clone()
+    }
+
+    fn output(&self) -> &[f64] {
+        &self.output
+    }
+
+    fn predict_into(&self, input: &[f64], output: &mut Vec<f64>, _temperature: f64) {
+        *output = self.collect_output(&self.run(input));
+    }
+
+    fn backward(&self, input: &[f64], output_delta: &[f64], want_input_delta: bool) -> LayerGradients {
+        let (d, h) = (self.input_dim, self.hidden_size);
+        let mut weights = Matrix::zeros(d + h, h);
+        let mut biases = Matrix::zeros(1, h);
+        let mut input_delta = vec![0.0; self.input_size()];
+
+        let first = self.steps.saturating_sub(self.bptt_steps.unwrap_or(self.steps));
+        // ∂L/∂h_t carried back from step t + 1.
+        let mut dh_next = vec![0.0; h];
+        for t in (first..self.steps).rev() {
+            let mut dh = dh_next;
+            if self.return_sequences {
+                dh.iter_mut().zip(&output_delta[t * h..(t + 1) * h]).for_each(|(a, g)| *a += g);
+            } else if t + 1 == self.steps {
+                dh.iter_mut().zip(output_delta).for_each(|(a, g)| *a += g);
+            }
+            // Through tanh: dz = dh ⊙ (1 − h_t²).
+            let dz: Vec<f64> = dh.iter().zip(&self.hidden[t + 1]).map(|(g, ht)| g * (1.0 - ht * ht)).collect();
+
+            let x_t = &input[t * d..(t + 1) * d];
+            let h_prev = &self.hidden[t];
+            for (row, v) in weights.row_iter_mut().zip(x_t.iter().chain(h_prev.iter())) {
+                row.iter_mut().zip(&dz).for_each(|(w, g)| *w += v * g);
+            }
+            biases.data.iter_mut().zip(&dz).for_each(|(b, g)| *b += g);
+
+            // dx_t = dz · Wxᵀ and dh_{t−1} = dz · Whᵀ.
+            let back = |row: &[f64]| dot(row, &dz);
+            if want_input_delta {
+                for (dx, row) in input_delta[t * d..(t + 1) * d].iter_mut().zip(self.weights.row_iter().take(d)) {
+                    *dx = back(row);
+                }
+            }
+            dh_next = self.weights.row_iter().skip(d).map(back).collect();
+        }
+
+        LayerGradients { weights, biases, input_delta: want_input_delta.then_some(input_delta) }
+    }
+
+    /// The weights are applied once per step.
+    fn macs(&self) -> usize {
+        self.steps * self.weights.data.len()
+    }
+
+    fn params(&self) -> (&Matrix, &Matrix) {
+        (&self.weights, &self.biases)
+    }
+
+    fn params_mut(&mut self) -> (&mut Matrix, &mut Matrix) {
+        (&mut self.weights, &mut self.biases)
+    }
+
+    fn box_clone(&self) -> Box<dyn LayerOps> {
+        Box::new(self.clone())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("rnn layer serializes to JSON")
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
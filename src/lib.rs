@@ -5,22 +5,88 @@ pub mod network;
 pub mod loss;
 pub mod optim;
 pub mod train;
+pub mod data;
+pub mod preprocess;
+pub mod eval;
+pub mod metrics;
+pub mod serve;
+#[cfg(feature = "toml")]
+pub mod config;
 
 // Convenience re-exports
-pub use math::matrix::Matrix;
-pub use activation::activation::ActivationFunction;
-pub use layers::dense::Layer;
+pub use math::matrix::{Matrix, ShapeError};
+pub use math::view::{MatrixView, MatrixViewMut};
+pub use math::init::WeightInit;
+pub use activation::activation::{ActivationFunction, ParseActivationError};
+pub use activation::registry::{is_activation_registered, register_activation, ActivationFn};
+pub use layers::dense::{Dense, Layer};
+pub use layers::ops::{LayerGradients, LayerOps};
+pub use layers::norm::GroupNorm;
+pub use layers::pool::{AvgPool2D, GlobalAvgPool, MaxPool2D};
+pub use layers::rnn::Rnn;
+pub use layers::attention::{SelfAttention, TransformerBlock};
 pub use network::network::Network;
+pub use network::ensemble::Ensemble;
+pub use network::autoencoder::Autoencoder;
+pub use network::cost::{CostEstimate, LayerCost};
+pub use network::graph::{Graph, GraphHead};
+pub use network::session::InferenceSession;
+pub use network::registry::{ModelRegistry, ModelEntry};
 pub use network::metadata::{ModelMetadata, InputType};
-pub use network::spec::{NetworkSpec, LayerSpec};
+pub use preprocess::polynomial::PolynomialFeatures;
+pub use preprocess::text::{TextVectorizer, TermWeighting};
+pub use network::spec::{NetworkSpec, LayerSpec, SpecError};
+pub use loss::loss::Loss;
 pub use loss::mse::MseLoss;
 pub use loss::cross_entropy::CrossEntropyLoss;
 pub use loss::bce::BceLoss;
 pub use loss::mae::MaeLoss;
 pub use loss::huber::HuberLoss;
 pub use loss::loss_type::LossType;
+pub use loss::reduction::Reduction;
 pub use optim::sgd::Sgd;
-pub use train::trainer::train_network;
+pub use optim::adam::Adam;
+pub use optim::optimizer::Optimizer;
+pub use optim::state::OptimizerState;
+pub use train::trainer::{train_network, try_train_network};
 pub use train::epoch_stats::EpochStats;
-pub use train::train_config::TrainConfig;
-pub use train::loop_fn::train_loop;
+pub use train::batch_stats::BatchStats;
+pub use train::outcome::TrainOutcome;
+pub use train::layer_stats::LayerStats;
+pub use train::layer_timing::LayerTiming;
+pub use train::layer_norms::LayerNorms;
+pub use train::train_config::{TrainConfig, SampleSchedule, SampleTransform};
+pub use train::augment::{gaussian_noise, ImageAugment};
+pub use train::loop_fn::{train_loop, train_loop_with_callbacks, try_train_loop};
+pub use train::error::{NonFinitePolicy, NonFiniteSource, TrainError};
+pub use train::callback::TrainCallback;
+pub use train::early_stopping::EarlyStopping;
+pub use train::reporter::ConsoleReporter;
+pub use train::checkpoint::{Checkpoint, CheckpointWriter};
+pub use train::lr_finder::{lr_finder, suggested_learning_rate, LrFinderConfig};
+pub use train::grad_check::{grad_check, GradCheck};
+pub use train::lr_schedule::LrSchedule;
+pub use train::sample_order::SampleOrder;
+pub use train::snapshot::train_snapshot_ensemble;
+pub use train::distill::distill;
+pub use train::graph::{train_graph, try_train_graph};
+pub use train::autoencoder::{train_autoencoder, try_train_autoencoder};
+pub use data::dataset::{Dataset, SplitDataset, Split};
+pub use data::loader::{DataLoader, Batches};
+pub use data::categorical::{CategoricalEncoder, CategoryEncoding};
+pub use data::resample::{oversample, oversample_seeded, undersample, undersample_seeded, balanced_sample_weights};
+pub use data::scaling::{Scaling, ScalingMethod, StandardScaler, MinMaxScaler};
+pub use data::split::{split_samples, split_samples_seeded, SplitStrategy};
+pub use data::window::{windows, windows_normalized, WindowScale};
+pub use eval::report::EvalReport;
+pub use metrics::classification::{
+    classification_report, Average, AveragedMetrics, ClassMetrics, ClassificationReport,
+};
+pub use eval::evaluator::evaluate;
+pub use eval::calibration::{calibrate_temperature, Calibration};
+pub use eval::importance::{permutation_importance, FeatureImportance, Metric};
+pub use serve::store::ModelStore;
+pub use serve::prediction::Prediction;
+pub use serve::error::ServeError;
+#[cfg(feature = "toml")]
+pub use config::ConfigError;
@@ -17,4 +17,28 @@ impl BceLoss {
             .map(|(p, y)| (p - y) / ((p + EPS) * (1.0 - p + EPS)))
             .collect()
     }
+
+    /// Combined Sigmoid+BCE gradient w.r.t. the pre-activation z: p - y.
+    ///
+    /// The sigmoid derivative p·(1-p) cancels the denominator of
+    /// `derivative`, so this avoids dividing by a vanishing p·(1-p) for
+    /// saturated outputs.  Follows the same sum convention as `derivative`;
+    /// the backward pass must then skip the output layer's sigmoid derivative
+    /// (see `Layer::compute_logit_gradients`).
+    pub fn logit_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter()).map(|(p, y)| p - y).collect()
+    }
+
+    /// Per-output terms -(y·log(p+ε) + (1-y)·log(1-p+ε)), before any reduction.
+    pub fn elementwise(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(p, y)| -(y * (p + EPS).ln() + (1.0 - y) * (1.0 - p + EPS).ln()))
+            .collect()
+    }
+
+    /// Gradient of the summed terms; identical to `derivative`, which already
+    /// follows the sum convention.
+    pub fn elementwise_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        BceLoss::derivative(predicted, expected)
+    }
 }
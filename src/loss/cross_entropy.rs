@@ -31,4 +31,58 @@ impl CrossEntropyLoss {
             .map(|(p, e)| p - e)
             .collect()
     }
+
+    /// Per-class terms -expected[i] * log(predicted[i] + eps), before any
+    /// reduction (`loss` is their sum).
+    pub fn elementwise(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(p, e)| -e * (p + EPS).ln())
+            .collect()
+    }
+
+    /// Gradient of the summed terms w.r.t. the logits; identical to
+    /// `derivative`, which already follows the sum convention.
+    pub fn elementwise_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        CrossEntropyLoss::derivative(predicted, expected)
+    }
+
+    /// Class-weighted cross-entropy:
+    ///   L = -sum(weights[i] * expected[i] * log(predicted[i] + eps))
+    ///
+    /// Raising the weight of a rare class makes each of its samples count for
+    /// more, countering the pull towards the majority class.
+    pub fn weighted_loss(predicted: &[f64], expected: &[f64], weights: &[f64]) -> f64 {
+        predicted.iter().zip(expected.iter()).zip(weights.iter())
+            .map(|((p, e), w)| -w * e * (p + EPS).ln())
+            .sum()
+    }
+
+    /// Gradient of the combined Softmax + weighted cross-entropy w.r.t. the
+    /// logits:
+    ///   ∂L/∂z_j = predicted[j] * sum(weights[i] * expected[i]) - weights[j] * expected[j]
+    ///
+    /// For a one-hot target of class `c` this is `weights[c] * (predicted -
+    /// expected)`, i.e. the unweighted gradient scaled by the class weight.
+    pub fn weighted_derivative(predicted: &[f64], expected: &[f64], weights: &[f64]) -> Vec<f64> {
+        let target_weight: f64 = expected.iter().zip(weights.iter()).map(|(e, w)| e * w).sum();
+        predicted.iter().zip(expected.iter()).zip(weights.iter())
+            .map(|((p, e), w)| p * target_weight - w * e)
+            .collect()
+    }
+
+    /// Inverse-frequency class weights for one-hot `labels`:
+    /// `n_samples / (n_classes * count[c])`, so every class carries the same
+    /// total weight.  Classes absent from `labels` get weight 0.
+    pub fn balanced_weights(labels: &[Vec<f64>]) -> Vec<f64> {
+        let n_classes = labels.first().map(|l| l.len()).unwrap_or(0);
+        let mut counts = vec![0.0; n_classes];
+        for label in labels {
+            for (count, e) in counts.iter_mut().zip(label.iter()) {
+                *count += e;
+            }
+        }
+        counts.iter()
+            .map(|&c| if c > 0.0 { labels.len() as f64 / (n_classes as f64 * c) } else { 0.0 })
+            .collect()
+    }
 }
@@ -30,4 +30,20 @@ impl HuberLoss {
             })
             .collect()
     }
+
+    /// Per-output terms h(p - y), before any reduction.
+    pub fn elementwise(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(p, y)| {
+                let x = p - y;
+                if x.abs() <= DELTA { 0.5 * x * x } else { DELTA * (x.abs() - 0.5 * DELTA) }
+            })
+            .collect()
+    }
+
+    /// Gradient of the summed terms; identical to `derivative`, which already
+    /// follows the sum convention.
+    pub fn elementwise_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        HuberLoss::derivative(predicted, expected)
+    }
 }
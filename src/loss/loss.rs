@@ -0,0 +1,97 @@
+use crate::loss::bce::BceLoss;
+use crate::loss::cross_entropy::CrossEntropyLoss;
+use crate::loss::huber::HuberLoss;
+use crate::loss::loss_type::LossType;
+use crate::loss::mae::MaeLoss;
+use crate::loss::mse::MseLoss;
+
+/// A per-sample loss function, as used by `train_loop`.
+///
+/// Implemented by the five built-in loss structs and by `LossType` (which
+/// dispatches to them).  The trait is object-safe: set
+/// `TrainConfig::custom_loss` to a `Box<dyn Loss>` to train with a loss of
+/// your own.
+pub trait Loss: Send + Sync {
+    /// Scalar loss of one sample.
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64;
+
+    /// Gradient of `loss` w.r.t. the network output — the delta that starts
+    /// backpropagation.
+    ///
+    /// A Softmax output layer passes this delta through unchanged (see
+    /// `ActivationFunction::derivative`), so a loss used after Softmax must
+    /// return the gradient w.r.t. the logits, as `CrossEntropyLoss` does.
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64>;
+}
+
+impl Loss for MseLoss {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        MseLoss::loss(predicted, expected)
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        MseLoss::derivative(predicted, expected)
+    }
+}
+
+impl Loss for CrossEntropyLoss {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        CrossEntropyLoss::loss(predicted, expected)
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        CrossEntropyLoss::derivative(predicted, expected)
+    }
+}
+
+impl Loss for BceLoss {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        BceLoss::loss(predicted, expected)
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        BceLoss::derivative(predicted, expected)
+    }
+}
+
+impl Loss for MaeLoss {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        MaeLoss::loss(predicted, expected)
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        MaeLoss::derivative(predicted, expected)
+    }
+}
+
+impl Loss for HuberLoss {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        HuberLoss::loss(predicted, expected)
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        HuberLoss::derivative(predicted, expected)
+    }
+}
+
+impl Loss for LossType {
+    fn loss(&self, predicted: &[f64], expected: &[f64]) -> f64 {
+        match self {
+            LossType::Mse                => MseLoss::loss(predicted, expected),
+            LossType::CrossEntropy       => CrossEntropyLoss::loss(predicted, expected),
+            LossType::BinaryCrossEntropy => BceLoss::loss(predicted, expected),
+            LossType::Mae                => MaeLoss::loss(predicted, expected),
+            LossType::Huber              => HuberLoss::loss(predicted, expected),
+        }
+    }
+
+    fn derivative(&self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        match self {
+            LossType::Mse                => MseLoss::derivative(predicted, expected),
+            LossType::CrossEntropy       => CrossEntropyLoss::derivative(predicted, expected),
+            LossType::BinaryCrossEntropy => BceLoss::derivative(predicted, expected),
+            LossType::Mae                => MaeLoss::derivative(predicted, expected),
+            LossType::Huber              => HuberLoss::derivative(predicted, expected),
+        }
+    }
+}
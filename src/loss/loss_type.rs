@@ -1,14 +1,25 @@
 use serde::{Serialize, Deserialize};
 
+use crate::loss::bce::BceLoss;
+use crate::loss::cross_entropy::CrossEntropyLoss;
+use crate::loss::huber::HuberLoss;
+use crate::loss::mae::MaeLoss;
+use crate::loss::mse::MseLoss;
+use crate::loss::reduction::Reduction;
+
 /// Selects which loss function the training loop uses.
 ///
 /// - `Mse`                — Mean-squared error; pair with Identity or Sigmoid output.
 /// - `CrossEntropy`       — Categorical cross-entropy; pair with Softmax output.
 ///   The gradient is the combined Softmax+CE gradient (predicted - expected),
 ///   which matches the convention in `CrossEntropyLoss::derivative()`.
-/// - `BinaryCrossEntropy` — Binary cross-entropy; pair with Sigmoid output.
+/// - `BinaryCrossEntropy` — Binary cross-entropy; pair with Sigmoid output
+///   (one output per independent yes/no label).
 /// - `Mae`                — Mean absolute error; pair with Identity output.
 /// - `Huber`              — Huber loss (δ=1.0); pair with Identity output.
+///
+/// `train_loop` reports accuracy for the two classification losses (see
+/// `EpochStats::train_accuracy`) and only the loss for the others.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LossType {
@@ -18,3 +29,35 @@ pub enum LossType {
     Mae,
     Huber,
 }
+
+impl LossType {
+    /// Per-output loss terms for one sample (`Reduction::None`).
+    pub fn elementwise(self, predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        match self {
+            LossType::Mse                => MseLoss::elementwise(predicted, expected),
+            LossType::CrossEntropy       => CrossEntropyLoss::elementwise(predicted, expected),
+            LossType::BinaryCrossEntropy => BceLoss::elementwise(predicted, expected),
+            LossType::Mae                => MaeLoss::elementwise(predicted, expected),
+            LossType::Huber              => HuberLoss::elementwise(predicted, expected),
+        }
+    }
+
+    /// One sample's loss under `reduction`; `None` for `Reduction::None`.
+    pub fn reduced_loss(self, predicted: &[f64], expected: &[f64], reduction: Reduction) -> Option<f64> {
+        reduction.reduce(&self.elementwise(predicted, expected))
+    }
+
+    /// Gradient of `reduced_loss` w.r.t. the network output (w.r.t. the
+    /// logits for `CrossEntropy`, as in `CrossEntropyLoss::derivative`).
+    /// With `Reduction::None` these are the gradients of the individual terms.
+    pub fn reduced_derivative(self, predicted: &[f64], expected: &[f64], reduction: Reduction) -> Vec<f64> {
+        let grad = match self {
+            LossType::Mse                => MseLoss::elementwise_derivative(predicted, expected),
+            LossType::CrossEntropy       => CrossEntropyLoss::elementwise_derivative(predicted, expected),
+            LossType::BinaryCrossEntropy => BceLoss::elementwise_derivative(predicted, expected),
+            LossType::Mae                => MaeLoss::elementwise_derivative(predicted, expected),
+            LossType::Huber              => HuberLoss::elementwise_derivative(predicted, expected),
+        };
+        reduction.scale_gradient(grad)
+    }
+}
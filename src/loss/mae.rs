@@ -19,4 +19,21 @@ impl MaeLoss {
             })
             .collect()
     }
+
+    /// Per-output terms |p - y|, before any reduction.
+    pub fn elementwise(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(p, y)| (p - y).abs())
+            .collect()
+    }
+
+    /// Subgradient of the summed terms: sign(p - y)  (0 when equal).
+    pub fn elementwise_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(p, y)| {
+                let diff = p - y;
+                if diff > 0.0 { 1.0 } else if diff < 0.0 { -1.0 } else { 0.0 }
+            })
+            .collect()
+    }
 }
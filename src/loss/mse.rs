@@ -15,4 +15,18 @@ impl MseLoss {
             .map(|(a, b)| a - b)
             .collect()
     }
+
+    /// Per-output terms (p - y)², before any reduction.
+    pub fn elementwise(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .collect()
+    }
+
+    /// Gradient of the summed terms: 2·(p - y).
+    pub fn elementwise_derivative(predicted: &[f64], expected: &[f64]) -> Vec<f64> {
+        predicted.iter().zip(expected.iter())
+            .map(|(a, b)| 2.0 * (a - b))
+            .collect()
+    }
 }
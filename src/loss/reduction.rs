@@ -0,0 +1,44 @@
+use serde::{Serialize, Deserialize};
+
+/// How the per-output terms of one sample's loss are combined.
+///
+/// - `Mean` — average over outputs; makes losses comparable across output
+///   sizes and loss types
+/// - `Sum`  — total over outputs
+/// - `None` — no reduction: the per-output terms themselves (see
+///   `LossType::elementwise`); has no scalar value
+///
+/// The built-in losses historically use their own conventions (`LossType`'s
+/// `loss` functions: cross-entropy sums over classes, the others average)
+/// and their `derivative` functions do not always match those conventions;
+/// `LossType::reduced_loss` / `reduced_derivative` apply one reduction
+/// consistently to both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reduction {
+    Mean,
+    Sum,
+    None,
+}
+
+impl Reduction {
+    /// Combines per-output terms; `None` for `Reduction::None`.
+    pub fn reduce(self, terms: &[f64]) -> Option<f64> {
+        match self {
+            Reduction::Mean if terms.is_empty() => Some(0.0),
+            Reduction::Mean => Some(terms.iter().sum::<f64>() / terms.len() as f64),
+            Reduction::Sum  => Some(terms.iter().sum()),
+            Reduction::None => None,
+        }
+    }
+
+    /// Scales the gradient of the summed terms to the gradient of the reduced
+    /// loss.  `None` leaves the per-output gradients as they are.
+    pub fn scale_gradient(self, mut grad: Vec<f64>) -> Vec<f64> {
+        if self == Reduction::Mean && !grad.is_empty() {
+            let inv_n = 1.0 / grad.len() as f64;
+            grad.iter_mut().for_each(|g| *g *= inv_n);
+        }
+        grad
+    }
+}
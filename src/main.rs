@@ -2,7 +2,124 @@
 // All neural network logic lives in the library (src/lib.rs and its modules).
 // Run examples with:
 //   cargo run --example xor
+//
+// Subcommands:
+//   ferrite-nn models [DIR]   -- list saved models (default: trained_models/)
+//   ferrite-nn importance MODEL CSV
+//                             -- permutation feature importance on a CSV file
+
+use std::time::SystemTime;
+
+use ferrite_nn::{ActivationFunction, LossType, Metric, ModelRegistry, Network, Split,
+                 permutation_importance};
+use ferrite_nn::data::{parse_csv, LabelMode};
+use ferrite_nn::network::registry::DEFAULT_MODEL_DIR;
+
 fn main() {
-    println!("ferrite-nn: a from-scratch neural network library in Rust.");
-    println!("Run `cargo run --example xor` to see the XOR demo.");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        Some("models") => {
+            let dir = args.get(1).map(|s| s.as_str()).unwrap_or(DEFAULT_MODEL_DIR);
+            list_models(dir);
+        }
+        Some("importance") if args.len() == 3 => feature_importance(&args[1], &args[2]),
+        _ => {
+            println!("ferrite-nn: a from-scratch neural network library in Rust.");
+            println!("Run `cargo run --example xor` to see the XOR demo.");
+            println!("Run `ferrite-nn models [DIR]` to list saved models.");
+            println!("Run `ferrite-nn importance MODEL.json DATA.csv` to rank input features.");
+        }
+    }
+}
+
+fn list_models(dir: &str) {
+    let registry = match ModelRegistry::open(dir) {
+        Ok(r)  => r,
+        Err(e) => {
+            eprintln!("Cannot read '{}': {}", dir, e);
+            std::process::exit(1);
+        }
+    };
+    if registry.entries().is_empty() {
+        println!("No models found in {}/", dir);
+        return;
+    }
+
+    println!("{:<24}  {:>10}  {:>10}  Metrics", "Name", "Size", "Created");
+    println!("{}", "─".repeat(72));
+    for entry in registry.entries() {
+        let metrics = entry.metadata.as_ref()
+            .and_then(|m| m.metrics.as_ref())
+            .map(|m| {
+                m.iter()
+                    .map(|(k, v)| format!("{}={:.4}", k, v))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            })
+            .unwrap_or_default();
+        println!(
+            "{:<24}  {:>10}  {:>10}  {}",
+            entry.name,
+            format_size(entry.size_bytes),
+            entry.created.map(format_age).unwrap_or_else(|| "—".into()),
+            metrics,
+        );
+        if let Some(desc) = entry.metadata.as_ref().and_then(|m| m.description.as_ref()) {
+            println!("{:<24}  {}", "", desc);
+        }
+    }
+}
+
+/// Labels in the CSV are a class index in the last column for classifiers
+/// (Softmax output), otherwise the last `output_size` columns.  Classifiers
+/// are scored by accuracy, everything else by MSE.
+fn feature_importance(model_path: &str, csv_path: &str) {
+    let fail = |msg: String| -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    };
+    let network = Network::load_json(model_path)
+        .unwrap_or_else(|e| fail(format!("Cannot load model '{}': {}", model_path, e)));
+    let Some(output) = network.layers.last() else { fail("Model has no layers".into()) };
+    let bytes = std::fs::read(csv_path)
+        .unwrap_or_else(|e| fail(format!("Cannot read '{}': {}", csv_path, e)));
+
+    let classifier = output.activation() == Some(&ActivationFunction::Softmax);
+    let (label_mode, metric) = if classifier {
+        (LabelMode::ClassIndex { n_classes: output.output_size() }, Metric::Accuracy)
+    } else {
+        (LabelMode::OneHot { n_label_cols: output.output_size() }, Metric::Loss(LossType::Mse))
+    };
+    let (inputs, labels) = parse_csv(&bytes, label_mode)
+        .unwrap_or_else(|e| fail(format!("Cannot parse '{}': {}", csv_path, e)));
+    let dataset = Split::new(inputs, labels);
+
+    let baseline = metric.score(&network, &dataset.inputs, &dataset.labels);
+    let metric_name = if classifier { "accuracy" } else { "MSE" };
+    println!("Baseline {}: {:.4} on {} samples", metric_name, baseline, dataset.len());
+    println!();
+    println!("{:>8}  {:>12}  {:>10}", "Feature", "Importance", "Std dev");
+    println!("{}", "─".repeat(34));
+    for f in permutation_importance(&network, &dataset, metric) {
+        println!("{:>8}  {:>+12.5}  {:>10.5}", f.feature, f.importance, f.std_dev);
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024        => format!("{} B", b),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b                    => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// Coarse "time ago" string, e.g. `5m ago`, `3d ago`.
+fn format_age(time: SystemTime) -> String {
+    let secs = SystemTime::now().duration_since(time).map(|d| d.as_secs()).unwrap_or(0);
+    match secs {
+        s if s < 60        => "just now".into(),
+        s if s < 3_600     => format!("{}m ago", s / 60),
+        s if s < 86_400    => format!("{}h ago", s / 3_600),
+        s                  => format!("{}d ago", s / 86_400),
+    }
 }
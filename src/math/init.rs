@@ -0,0 +1,111 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::activation::activation::ActivationFunction;
+use crate::math::matrix::Matrix;
+use crate::math::vector::dot;
+
+/// Weight initialization scheme for a layer's `fan_in × fan_out` weight
+/// matrix.  Biases are always initialized to zero.
+///
+/// Serialized in snake_case (`"he"`, `"xavier_uniform"`, `{"uniform": 0.05}`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightInit {
+    /// Normal with variance 2 / n (`Matrix::he`); suits ReLU-family layers.
+    He,
+    /// Normal with variance 1 / n (`Matrix::xavier`); suits Sigmoid, Tanh
+    /// and linear layers.
+    Xavier,
+    /// Glorot uniform: U(−a, a) with a = √(6 / (fan_in + fan_out)).
+    XavierUniform,
+    /// Normal with variance 1 / fan_in; suits SELU-style activations.
+    LeCun,
+    /// U(−limit, limit).
+    Uniform(f64),
+    /// All weights zero.  Every neuron then learns the same thing; only
+    /// useful for output layers or experiments.
+    Zeros,
+    /// A random (semi-)orthogonal matrix: orthonormal columns when
+    /// `fan_in ≥ fan_out`, orthonormal rows otherwise.
+    Orthogonal,
+}
+
+impl WeightInit {
+    /// The scheme `Layer::new` picks when none is given: He before ReLU,
+    /// Xavier otherwise.
+    pub fn for_activation(activation: &ActivationFunction) -> WeightInit {
+        match activation {
+            ActivationFunction::ReLU => WeightInit::He,
+            _ => WeightInit::Xavier,
+        }
+    }
+
+    /// Draws a `fan_in × fan_out` weight matrix.
+    pub fn weights<R: Rng + ?Sized>(&self, fan_in: usize, fan_out: usize, rng: &mut R) -> Matrix {
+        match *self {
+            WeightInit::He => Matrix::he_with(fan_in, fan_out, rng),
+            WeightInit::Xavier => Matrix::xavier_with(fan_in, fan_out, rng),
+            WeightInit::XavierUniform => {
+                let limit = (6.0 / (fan_in + fan_out) as f64).sqrt();
+                uniform(fan_in, fan_out, limit, rng)
+            }
+            WeightInit::LeCun => {
+                let std_dev = (1.0 / fan_in as f64).sqrt();
+                Matrix::from_data(
+                    (0..fan_in)
+                        .map(|_| (0..fan_out).map(|_| Matrix::sample_standard_normal(rng) * std_dev).collect())
+                        .collect(),
+                )
+            }
+            WeightInit::Uniform(limit) => uniform(fan_in, fan_out, limit, rng),
+            WeightInit::Zeros => Matrix::zeros(fan_in, fan_out),
+            WeightInit::Orthogonal => orthogonal(fan_in, fan_out, rng),
+        }
+    }
+
+    /// Problems with the scheme's parameters (a non-positive or non-finite
+    /// uniform limit).
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            WeightInit::Uniform(limit) if !(limit.is_finite() && limit > 0.0) => {
+                Err(format!("uniform init limit must be a positive number, got {}", limit))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn uniform<R: Rng + ?Sized>(rows: usize, cols: usize, limit: f64, rng: &mut R) -> Matrix {
+    Matrix::from_data(
+        (0..rows)
+            .map(|_| (0..cols).map(|_| rng.gen_range(-limit..=limit)).collect())
+            .collect(),
+    )
+}
+
+/// Gram–Schmidt over the shorter dimension of a standard-normal matrix.
+fn orthogonal<R: Rng + ?Sized>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
+    // Orthonormalize `count` vectors of length `len`, then lay them out as
+    // columns (tall matrix) or rows (wide matrix).
+    let (count, len) = if rows >= cols { (cols, rows) } else { (rows, cols) };
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(count);
+    while basis.len() < count {
+        let mut v: Vec<f64> = (0..len).map(|_| Matrix::sample_standard_normal(rng)).collect();
+        for u in &basis {
+            let proj = dot(&v, u);
+            v.iter_mut().zip(u).for_each(|(a, b)| *a -= proj * b);
+        }
+        let norm = v.iter().map(|a| a * a).sum::<f64>().sqrt();
+        // A (vanishingly unlikely) near-dependent draw is simply redrawn.
+        if norm > 1e-8 {
+            v.iter_mut().for_each(|a| *a /= norm);
+            basis.push(v);
+        }
+    }
+    if rows >= cols {
+        Matrix::from_data((0..rows).map(|i| basis.iter().map(|u| u[i]).collect()).collect())
+    } else {
+        Matrix::from_data(basis)
+    }
+}
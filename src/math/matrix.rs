@@ -1,13 +1,22 @@
 use rand::prelude::*;
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 use std::f64::consts::PI;
-use std::ops::{Add, Sub, Mul};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use std::ops::{Add, Sub, Mul, Index, IndexMut, Range};
+
+use crate::math::vector::dot;
+use crate::math::view::{MatrixView, MatrixViewMut};
+
+/// Dense `rows × cols` matrix of `f64`, stored row-major in one contiguous
+/// buffer: element `(i, j)` is `data[i * cols + j]`.  Index with `m[(i, j)]`
+/// or take whole rows with `row` / `row_iter`.
+///
+/// Serialized as `{rows, cols, data}` with `data` flat.  Model files written
+/// before the flat layout (`data` as a list of rows) still deserialize.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct Matrix{
     pub rows: usize,
     pub cols: usize,
-    pub data: Vec<Vec<f64>>
+    pub data: Vec<f64>
 }
 
 impl Matrix{
@@ -15,19 +24,20 @@ impl Matrix{
         Matrix{
             rows,
             cols,
-            data: vec![vec![0.0; cols]; rows]
+            data: vec![0.0; rows * cols]
         }
     }
 
     pub fn random(rows: usize, cols: usize) -> Matrix {
-        let mut rng = rand::thread_rng();
-        let mut res = Matrix::zeros(rows, cols);
+        Matrix::random_with(rows, cols, &mut rand::thread_rng())
+    }
 
-        for i in 0..rows {
-            for j in 0..cols {
-                res.data[i][j] = rng.gen::<f64>() * 2.0 - 1.0;
-            }
+    /// `random` drawing from the given generator (e.g. a seeded `StdRng`).
+    pub fn random_with<R: Rng + ?Sized>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
+        let mut res = Matrix::zeros(rows, cols);
 
+        for v in res.data.iter_mut() {
+            *v = rng.gen::<f64>() * 2.0 - 1.0;
         }
 
         res
@@ -35,7 +45,7 @@ impl Matrix{
 
     /// Samples a single value from N(0, 1) using the Box-Muller transform.
     /// Both u1 and u2 must be uniform on (0, 1].
-    fn sample_standard_normal(rng: &mut ThreadRng) -> f64 {
+    pub(crate) fn sample_standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
         // Draw two independent uniform samples in (0, 1] to avoid log(0).
         let u1: f64 = 1.0 - rng.gen::<f64>();
         let u2: f64 = 1.0 - rng.gen::<f64>();
@@ -49,13 +59,15 @@ impl Matrix{
     ///
     /// Shape: (rows, cols). `cols` is the fan-in (number of input connections).
     pub fn he(rows: usize, cols: usize) -> Matrix {
-        let mut rng = rand::thread_rng();
+        Matrix::he_with(rows, cols, &mut rand::thread_rng())
+    }
+
+    /// `he` drawing from the given generator (e.g. a seeded `StdRng`).
+    pub fn he_with<R: Rng + ?Sized>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
         let std_dev = (2.0 / cols as f64).sqrt();
         let mut res = Matrix::zeros(rows, cols);
-        for i in 0..rows {
-            for j in 0..cols {
-                res.data[i][j] = Matrix::sample_standard_normal(&mut rng) * std_dev;
-            }
+        for v in res.data.iter_mut() {
+            *v = Matrix::sample_standard_normal(rng) * std_dev;
         }
         res
     }
@@ -67,13 +79,15 @@ impl Matrix{
     ///
     /// Shape: (rows, cols). `cols` is the fan-in (number of input connections).
     pub fn xavier(rows: usize, cols: usize) -> Matrix {
-        let mut rng = rand::thread_rng();
+        Matrix::xavier_with(rows, cols, &mut rand::thread_rng())
+    }
+
+    /// `xavier` drawing from the given generator (e.g. a seeded `StdRng`).
+    pub fn xavier_with<R: Rng + ?Sized>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
         let std_dev = (1.0 / cols as f64).sqrt();
         let mut res = Matrix::zeros(rows, cols);
-        for i in 0..rows {
-            for j in 0..cols {
-                res.data[i][j] = Matrix::sample_standard_normal(&mut rng) * std_dev;
-            }
+        for v in res.data.iter_mut() {
+            *v = Matrix::sample_standard_normal(rng) * std_dev;
         }
         res
     }
@@ -83,7 +97,7 @@ impl Matrix{
 
         for i in 0..res.rows {
             for j in 0..res.cols {
-                res.data[i][j] = self.data[j][i];
+                res[(i, j)] = self[(j, i)];
             }
         }
 
@@ -94,92 +108,454 @@ impl Matrix{
     where
         F: Fn(f64) -> f64,
     {
-        Matrix::from_data(
-            (self.data)
-                .clone()
-                .into_iter()
-                .map(|row| row.into_iter().map(|x| functor(x)).collect())
-                .collect()
-        )
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&v| functor(v)).collect(),
+        }
     }
 
+    /// Builds a matrix from a list of equally long rows.
+    ///
+    /// # Panics
+    /// Panics if the rows differ in length.
     pub fn from_data(data: Vec<Vec<f64>>) -> Matrix {
-        Matrix {
-            rows: data.len(),
-            cols: data[0].len(),
-            data
+        let rows = data.len();
+        // No rows means an empty 0×0 matrix (parameter-free layers).
+        let cols = data.first().map_or(0, Vec::len);
+        assert!(data.iter().all(|row| row.len() == cols), "Matrix rows must all have the same length");
+        Matrix { rows, cols, data: data.concat() }
+    }
+
+    /// Wraps a row-major buffer of `rows · cols` values.
+    ///
+    /// # Panics
+    /// Panics if `data.len() != rows * cols`.
+    pub fn from_flat(rows: usize, cols: usize, data: Vec<f64>) -> Matrix {
+        assert_eq!(data.len(), rows * cols, "a {}×{} matrix needs {} values", rows, cols, rows * cols);
+        Matrix { rows, cols, data }
+    }
+
+    /// Row `i` as a slice.
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [f64] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// The rows, in order.
+    pub fn row_iter(&self) -> impl Iterator<Item = &[f64]> + '_ {
+        (0..self.rows).map(move |i| self.row(i))
+    }
+
+    pub fn row_iter_mut(&mut self) -> impl Iterator<Item = &mut [f64]> + '_ {
+        // `max(1)`: a matrix without columns has an empty buffer and no rows
+        // to hand out.
+        self.data.chunks_mut(self.cols.max(1))
+    }
+
+    /// Rows `range` as a new `range.len() × cols` matrix.
+    ///
+    /// # Panics
+    /// Panics if the range reaches past the last row.
+    pub fn rows(&self, range: Range<usize>) -> Matrix {
+        self.view_rows(range).to_matrix()
+    }
+
+    /// The rows at `indices`, in that order (repeats allowed) — e.g. a
+    /// shuffled mini-batch gathered from a dataset matrix.
+    ///
+    /// # Panics
+    /// Panics if an index is out of bounds.
+    pub fn select_rows(&self, indices: &[usize]) -> Matrix {
+        let mut data = Vec::with_capacity(indices.len() * self.cols);
+        for &i in indices {
+            assert!(i < self.rows, "row {} out of bounds for {} rows", i, self.rows);
+            data.extend_from_slice(self.row(i));
         }
+        Matrix::from_flat(indices.len(), self.cols, data)
     }
-}
 
-impl Default for Matrix {
-    fn default() -> Self {
-        Matrix { rows: 0, cols: 0, data: vec![] }
+    /// Stacks `parts` on top of each other.
+    ///
+    /// # Panics
+    /// Panics if `parts` is empty or the column counts differ.
+    pub fn vstack(parts: &[&Matrix]) -> Matrix {
+        let cols = parts.first().expect("vstack needs at least one matrix").cols;
+        assert!(parts.iter().all(|m| m.cols == cols), "vstack needs matrices with the same number of columns");
+        let data = parts.iter().flat_map(|m| m.data.iter().copied()).collect();
+        Matrix::from_flat(parts.iter().map(|m| m.rows).sum(), cols, data)
     }
-}
 
-impl Add for Matrix {
-    type Output = Matrix;
+    /// Places `parts` side by side.
+    ///
+    /// # Panics
+    /// Panics if `parts` is empty or the row counts differ.
+    pub fn hstack(parts: &[&Matrix]) -> Matrix {
+        let rows = parts.first().expect("hstack needs at least one matrix").rows;
+        assert!(parts.iter().all(|m| m.rows == rows), "hstack needs matrices with the same number of rows");
+        let cols = parts.iter().map(|m| m.cols).sum();
+        let mut data = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for m in parts {
+                data.extend_from_slice(m.row(i));
+            }
+        }
+        Matrix::from_flat(rows, cols, data)
+    }
 
-    fn add(self, rhs: Self) -> Self::Output {
-        if self.rows != rhs.rows || self.cols != rhs.cols {
-            panic!("Matrices are of incorrect sizes")
+    /// `self += rhs` element-wise, without allocating.  `rhs` is either the
+    /// same shape or a `1 × cols` row added to every row (e.g. a bias row
+    /// against a batch of pre-activations).
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn add_assign_mat(&mut self, rhs: &Matrix) {
+        self.try_add_assign_mat(rhs).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// `add_assign_mat` that reports a shape mismatch instead of panicking;
+    /// `self` is untouched on error.
+    pub fn try_add_assign_mat(&mut self, rhs: &Matrix) -> Result<(), ShapeError> {
+        self.zip_broadcast("add", rhs, |a, b| *a += b)
+    }
+
+    /// `self += rhs` element-wise for matrices of exactly the same shape:
+    /// unlike `try_add_assign_mat` a `1 × cols` row is a mismatch, not
+    /// broadcast.  Used to accumulate gradients, where a row-shaped gradient
+    /// for a full weight matrix is a bug.  `self` is untouched on error.
+    pub fn try_add_assign_exact(&mut self, rhs: &Matrix) -> Result<(), ShapeError> {
+        if (self.rows, self.cols) != (rhs.rows, rhs.cols) {
+            return Err(ShapeError::new("add", self, rhs));
         }
+        self.data.iter_mut().zip(&rhs.data).for_each(|(a, &b)| *a += b);
+        Ok(())
+    }
+
+    /// `self -= rhs` element-wise, broadcasting a `1 × cols` row like
+    /// `add_assign_mat`.
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn sub_assign_mat(&mut self, rhs: &Matrix) {
+        self.zip_broadcast("subtract", rhs, |a, b| *a -= b).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// `self -= factor · rhs` element-wise, without allocating; broadcasts
+    /// a `1 × cols` row like `add_assign_mat`.
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn sub_scaled_assign_mat(&mut self, rhs: &Matrix, factor: f64) {
+        self.zip_broadcast("subtract", rhs, |a, b| *a -= factor * b).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// `self ⊙= rhs` (element-wise product), broadcasting a `1 × cols` row
+    /// like `add_assign_mat`.
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn hadamard_assign(&mut self, rhs: &Matrix) {
+        self.zip_broadcast("multiply element-wise", rhs, |a, b| *a *= b).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// Element-wise product `self ⊙ rhs` (see `hadamard_assign`).
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn hadamard(&self, rhs: &Matrix) -> Matrix {
+        let mut out = self.clone();
+        out.hadamard_assign(rhs);
+        out
+    }
 
-        let mut res = Matrix::zeros(self.rows, self.cols);
+    /// Multiplies every element by `factor` in place.
+    pub fn scale_mut(&mut self, factor: f64) {
+        self.data.iter_mut().for_each(|v| *v *= factor);
+    }
 
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                res.data[i][j] = self.data[i][j] + rhs.data[i][j];
+    /// In-place `map`.
+    pub fn map_mut<F>(&mut self, functor: F)
+    where
+        F: Fn(f64) -> f64,
+    {
+        self.data.iter_mut().for_each(|v| *v = functor(*v));
+    }
+
+    /// Applies `f(self_ij, rhs_ij)` to every element, reading row 0 of
+    /// `rhs` for every row when `rhs` is a single row.
+    fn zip_broadcast(&mut self, op: &'static str, rhs: &Matrix, f: impl Fn(&mut f64, f64)) -> Result<(), ShapeError> {
+        if self.cols != rhs.cols || (rhs.rows != self.rows && rhs.rows != 1) {
+            return Err(ShapeError::new(op, self, rhs));
+        }
+        if rhs.rows == self.rows {
+            self.data.iter_mut().zip(&rhs.data).for_each(|(a, &b)| f(a, b));
+        } else {
+            for row in self.row_iter_mut() {
+                row.iter_mut().zip(&rhs.data).for_each(|(a, &b)| f(a, b));
             }
         }
+        Ok(())
+    }
 
+    /// `self + rhs` (broadcasting like `add_assign_mat`), or the mismatch.
+    pub fn try_add(&self, rhs: &Matrix) -> Result<Matrix, ShapeError> {
+        let mut out = self.clone();
+        out.zip_broadcast("add", rhs, |a, b| *a += b)?;
+        Ok(out)
+    }
+
+    /// `self − rhs` (broadcasting like `add_assign_mat`), or the mismatch.
+    pub fn try_sub(&self, rhs: &Matrix) -> Result<Matrix, ShapeError> {
+        let mut out = self.clone();
+        out.zip_broadcast("subtract", rhs, |a, b| *a -= b)?;
+        Ok(out)
+    }
+
+    /// Matrix product `self · rhs`, or the mismatch when `self.cols` differs
+    /// from `rhs.rows`.
+    pub fn try_matmul(&self, rhs: &Matrix) -> Result<Matrix, ShapeError> {
+        self.view().try_matmul(rhs.view())
+    }
+
+    /// `selfᵀ · rhs` without materializing the transpose — e.g. the weight
+    /// gradient `Xᵀ·δ` of a dense layer over a batch.
+    ///
+    /// # Panics
+    /// Panics if `self.rows != rhs.rows`.
+    pub fn matmul_tn(&self, rhs: &Matrix) -> Matrix {
+        if self.rows != rhs.rows {
+            panic!("{}", ShapeError::new("multiply the transpose of", self, rhs));
+        }
+        let mut res = Matrix::zeros(self.cols, rhs.cols);
+        // k-i-j order: row k of `self` is column k of `selfᵀ`, and row k of
+        // `rhs` is added into every output row.  Each element is still summed
+        // over k in order, so the result matches `self.transpose() * rhs`.
+        for (a_row, b_row) in self.row_iter().zip(rhs.row_iter()) {
+            for (&a, out) in a_row.iter().zip(res.row_iter_mut()) {
+                out.iter_mut().zip(b_row).for_each(|(o, &b)| *o += a * b);
+            }
+        }
         res
     }
+
+    /// `self · rhsᵀ` without materializing the transpose — e.g. the input
+    /// delta `δ·Wᵀ` of a dense layer over a batch.  Element `(i, j)` is the
+    /// dot product of row `i` of `self` and row `j` of `rhs`.
+    ///
+    /// # Panics
+    /// Panics if `self.cols != rhs.cols`.
+    pub fn matmul_nt(&self, rhs: &Matrix) -> Matrix {
+        if self.cols != rhs.cols {
+            panic!("{}", ShapeError::new("multiply by the transpose of", self, rhs));
+        }
+        let data = self.row_iter()
+            .flat_map(|a| rhs.row_iter().map(move |b| dot(a, b)))
+            .collect();
+        Matrix::from_flat(self.rows, rhs.rows, data)
+    }
+
+    /// The whole matrix as a borrowed view.
+    pub fn view(&self) -> MatrixView<'_> {
+        MatrixView { rows: self.rows, cols: self.cols, data: &self.data }
+    }
+
+    /// The whole matrix as a writable view.
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_> {
+        MatrixViewMut { rows: self.rows, cols: self.cols, data: &mut self.data }
+    }
+
+    /// Rows `range` as a borrowed view; unlike `rows` nothing is copied.
+    ///
+    /// # Panics
+    /// Panics if the range reaches past the last row.
+    pub fn view_rows(&self, range: Range<usize>) -> MatrixView<'_> {
+        self.view().rows(range)
+    }
+
+    /// Rows `range` as a writable view.
+    ///
+    /// # Panics
+    /// Panics if the range reaches past the last row.
+    pub fn view_rows_mut(&mut self, range: Range<usize>) -> MatrixViewMut<'_> {
+        assert!(
+            range.start <= range.end && range.end <= self.rows,
+            "row range {:?} out of bounds for {} rows",
+            range,
+            self.rows
+        );
+        let cols = self.cols;
+        MatrixViewMut::new(range.len(), cols, &mut self.data[range.start * cols..range.end * cols])
+    }
+
+    /// Copies the matrix out as a list of rows.
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        self.row_iter().map(<[f64]>::to_vec).collect()
+    }
 }
 
-impl Sub for Matrix {
-    type Output = Matrix;
+/// Operand shapes that an operation cannot combine, returned by the `try_*`
+/// methods of `Matrix` (the operators panic with the same message).
+///
+/// # Fields
+/// - `op`    — what was attempted (`"add"`, `"subtract"`, `"multiply"`, …)
+/// - `left`  — `(rows, cols)` of the left operand
+/// - `right` — `(rows, cols)` of the right operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapeError {
+    pub op: &'static str,
+    pub left: (usize, usize),
+    pub right: (usize, usize),
+}
+
+impl ShapeError {
+    fn new(op: &'static str, left: &Matrix, right: &Matrix) -> ShapeError {
+        ShapeError { op, left: (left.rows, left.cols), right: (right.rows, right.cols) }
+    }
+}
+
+impl std::fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cannot {} a {}×{} matrix and a {}×{} matrix",
+            self.op, self.left.0, self.left.1, self.right.0, self.right.1
+        )
+    }
+}
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        if self.rows != rhs.rows || self.cols != rhs.cols {
-            panic!("Matrices are of incorrect sizes")
+impl std::error::Error for ShapeError {}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        debug_assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}×{}", i, j, self.rows, self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        debug_assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}×{}", i, j, self.rows, self.cols);
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+/// Accepts both the flat `data` layout and the nested list of rows written
+/// by older versions.
+impl<'de> Deserialize<'de> for Matrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Data {
+            Flat(Vec<f64>),
+            Nested(Vec<Vec<f64>>),
         }
 
-        let mut res = Matrix::zeros(self.rows, self.cols);
+        #[derive(Deserialize)]
+        struct Raw {
+            rows: usize,
+            cols: usize,
+            data: Data,
+        }
 
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                res.data[i][j] = self.data[i][j] - rhs.data[i][j];
+        let raw = Raw::deserialize(deserializer)?;
+        let data = match raw.data {
+            Data::Flat(data) => data,
+            Data::Nested(rows) => {
+                if rows.len() != raw.rows || rows.iter().any(|row| row.len() != raw.cols) {
+                    return Err(D::Error::custom(format!("matrix data does not match its {}×{} shape", raw.rows, raw.cols)));
+                }
+                rows.concat()
             }
+        };
+        if data.len() != raw.rows * raw.cols {
+            return Err(D::Error::custom(format!(
+                "matrix has {} values but its {}×{} shape needs {}",
+                data.len(), raw.rows, raw.cols, raw.rows * raw.cols
+            )));
         }
+        Ok(Matrix { rows: raw.rows, cols: raw.cols, data })
+    }
+}
 
-        res
+/// Element-wise sum; `rhs` may be a `1 × cols` row (see `add_assign_mat`).
+impl Add for Matrix {
+    type Output = Matrix;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.add_assign_mat(&rhs);
+        self
     }
 }
 
-impl Mul for Matrix {
+/// Element-wise difference; `rhs` may be a `1 × cols` row.
+impl Sub for Matrix {
     type Output = Matrix;
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        if self.cols != rhs.rows {
-            panic!("Matrices are of incorrect sizes")
-        }
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self.sub_assign_mat(&rhs);
+        self
+    }
+}
 
-        let mut res =  Matrix::zeros(self.rows, rhs.cols);
+impl Mul for Matrix {
+    type Output = Matrix;
 
-        for i in 0..res.rows {
-            for j in 0..res.cols {
-                let mut sum = 0.0;
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
 
-                for k in 0..self.cols {
-                    sum += self.data[i][k] * rhs.data[k][j];
+// ---------------------------------------------------------------------------
+// Matrix product kernel
+// ---------------------------------------------------------------------------
+
+/// Depth of a `k` block: a `GEMM_BLOCK_K × GEMM_BLOCK_J` panel of the right
+/// operand (128 × 256 × 8 B = 256 KiB) stays in L2 while every row of the
+/// left operand streams over it.
+const GEMM_BLOCK_K: usize = 128;
+
+/// Width of a `j` block: one row slice of the panel and of the result
+/// (2 KiB each) stay in L1 through the inner loop.
+const GEMM_BLOCK_J: usize = 256;
+
+/// `out += a · b` with the loops blocked over `k` and `j`.
+///
+/// Inside a block the order is i-k-j, so the inner loop walks contiguous
+/// slices of a `b` row and an `out` row.  `k` blocks are visited in order,
+/// so every element is summed over `k` in the same order as the plain
+/// triple loop and the result is bit-identical to it.  Operands that fit in
+/// one block take the same path with a single block.
+pub(crate) fn gemm_tiled(a: MatrixView<'_>, b: MatrixView<'_>, out: &mut Matrix) {
+    let n = b.cols;
+    for j0 in (0..n).step_by(GEMM_BLOCK_J) {
+        let j1 = (j0 + GEMM_BLOCK_J).min(n);
+        for k0 in (0..a.cols).step_by(GEMM_BLOCK_K) {
+            let k1 = (k0 + GEMM_BLOCK_K).min(a.cols);
+            for i in 0..a.rows {
+                let out_row = &mut out.data[i * n + j0..i * n + j1];
+                for (k, &x) in a.row(i)[k0..k1].iter().enumerate() {
+                    let b_row = &b.data[(k0 + k) * n + j0..(k0 + k) * n + j1];
+                    for (o, &y) in out_row.iter_mut().zip(b_row) {
+                        *o += x * y;
+                    }
                 }
-
-                res.data[i][j] = sum;
             }
         }
+    }
+}
 
-        res
+/// Matrix product of borrowed operands, so neither side has to be cloned.
+impl Mul<&Matrix> for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: &Matrix) -> Matrix {
+        self.try_matmul(rhs).unwrap_or_else(|e| panic!("{}", e))
     }
 }
@@ -1,3 +1,9 @@
+pub mod init;
 pub mod matrix;
+pub mod vector;
+pub mod view;
 
-pub use matrix::Matrix;
+pub use init::WeightInit;
+pub use matrix::{Matrix, ShapeError};
+pub use vector::{dot, mat_vec, outer, vec_mat};
+pub use view::{MatrixView, MatrixViewMut};
@@ -0,0 +1,65 @@
+//! Single-sample vector helpers.  Backprop through one sample works on
+//! plain slices; these avoid wrapping them in `1 × n` matrices just to
+//! multiply them.
+
+use crate::math::matrix::Matrix;
+
+/// `Σ aᵢ·bᵢ`.
+///
+/// # Panics
+/// Panics if the lengths differ.
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    assert_eq!(a.len(), b.len(), "dot product of vectors of length {} and {}", a.len(), b.len());
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Outer product `a · bᵀ`: the `a.len() × b.len()` matrix with
+/// `(i, j) = aᵢ·bⱼ` — e.g. a dense layer's weight gradient xᵀ·δ.
+pub fn outer(a: &[f64], b: &[f64]) -> Matrix {
+    let mut data = Vec::with_capacity(a.len() * b.len());
+    for x in a {
+        data.extend(b.iter().map(|y| x * y));
+    }
+    Matrix::from_flat(a.len(), b.len(), data)
+}
+
+/// `m += a · bᵀ` in place: adds the outer product without materializing
+/// it, e.g. to accumulate a dense layer's weight gradient.
+///
+/// # Panics
+/// Panics if `m` is not `a.len() × b.len()`.
+pub fn add_outer(m: &mut Matrix, a: &[f64], b: &[f64]) {
+    assert!(
+        m.rows == a.len() && m.cols == b.len(),
+        "cannot add a {}×{} outer product to a {}×{} matrix",
+        a.len(), b.len(), m.rows, m.cols
+    );
+    for (x, row) in a.iter().zip(m.row_iter_mut()) {
+        row.iter_mut().zip(b).for_each(|(o, y)| *o += x * y);
+    }
+}
+
+/// Row vector times matrix, `x · M` (length `m.cols`) — a dense layer's
+/// `x·W` without the bias.
+///
+/// # Panics
+/// Panics if `x.len() != m.rows`.
+pub fn vec_mat(x: &[f64], m: &Matrix) -> Vec<f64> {
+    assert_eq!(x.len(), m.rows, "cannot multiply a vector of length {} by a {}×{} matrix", x.len(), m.rows, m.cols);
+    let mut out = vec![0.0; m.cols];
+    for (xv, row) in x.iter().zip(m.row_iter()) {
+        out.iter_mut().zip(row).for_each(|(o, w)| *o += xv * w);
+    }
+    out
+}
+
+/// Matrix times column vector, `M · x` (length `m.rows`): one dot product
+/// per row.  With `M = W` this is `δ · Wᵀ`, the input delta of a dense
+/// layer, without transposing `W`.
+///
+/// # Panics
+/// Panics if `x.len() != m.cols`.
+pub fn mat_vec(m: &Matrix, x: &[f64]) -> Vec<f64> {
+    assert_eq!(x.len(), m.cols, "cannot multiply a {}×{} matrix by a vector of length {}", m.rows, m.cols, x.len());
+    m.row_iter().map(|row| dot(row, x)).collect()
+}
@@ -0,0 +1,171 @@
+use std::ops::{Index, IndexMut, Mul, Range};
+
+use crate::math::matrix::{gemm_tiled, Matrix, ShapeError};
+
+/// Borrowed, read-only `rows × cols` window over a row-major buffer — a
+/// whole `Matrix`, a range of its rows, or a plain slice such as one
+/// sample's activations.  Views are `Copy` and never allocate; multiplying
+/// two views produces an owned `Matrix`.
+///
+/// # Fields
+/// - `rows`, `cols` — the shape
+/// - `data`         — exactly `rows · cols` values, row-major
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatrixView<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: &'a [f64],
+}
+
+impl<'a> MatrixView<'a> {
+    /// Views `data` as a `rows × cols` matrix.  A slice of length `n` can be
+    /// seen as a `1 × n` row or an `n × 1` column without copying.
+    ///
+    /// # Panics
+    /// Panics if `data.len() != rows * cols`.
+    pub fn new(rows: usize, cols: usize, data: &'a [f64]) -> MatrixView<'a> {
+        assert_eq!(data.len(), rows * cols, "a {}×{} view needs {} values", rows, cols, rows * cols);
+        MatrixView { rows, cols, data }
+    }
+
+    /// Row `i` as a slice.
+    pub fn row(&self, i: usize) -> &'a [f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// The rows, in order.
+    pub fn row_iter(&self) -> impl Iterator<Item = &'a [f64]> + 'a {
+        let view = *self;
+        (0..view.rows).map(move |i| view.row(i))
+    }
+
+    /// Rows `range` as a narrower view (e.g. one mini-batch of a dataset).
+    ///
+    /// # Panics
+    /// Panics if the range reaches past the last row.
+    pub fn rows(&self, range: Range<usize>) -> MatrixView<'a> {
+        assert!(
+            range.start <= range.end && range.end <= self.rows,
+            "row range {:?} out of bounds for {} rows",
+            range,
+            self.rows
+        );
+        MatrixView::new(range.len(), self.cols, &self.data[range.start * self.cols..range.end * self.cols])
+    }
+
+    /// Copies the viewed values into an owned `Matrix`.
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::from_flat(self.rows, self.cols, self.data.to_vec())
+    }
+
+    /// Matrix product `self · rhs`, or the mismatch when `self.cols` differs
+    /// from `rhs.rows`.
+    pub fn try_matmul(&self, rhs: MatrixView<'_>) -> Result<Matrix, ShapeError> {
+        if self.cols != rhs.rows {
+            return Err(ShapeError { op: "multiply", left: (self.rows, self.cols), right: (rhs.rows, rhs.cols) });
+        }
+        let mut res = Matrix::zeros(self.rows, rhs.cols);
+        gemm_tiled(*self, rhs, &mut res);
+        Ok(res)
+    }
+}
+
+impl Index<(usize, usize)> for MatrixView<'_> {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        debug_assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}×{}", i, j, self.rows, self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+/// Matrix product of two views.
+impl Mul<MatrixView<'_>> for MatrixView<'_> {
+    type Output = Matrix;
+
+    fn mul(self, rhs: MatrixView<'_>) -> Matrix {
+        self.try_matmul(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mutable view
+// ---------------------------------------------------------------------------
+
+/// Borrowed, writable `rows × cols` window — e.g. the rows of a gradient
+/// accumulator that belong to one slice of a batch.
+///
+/// # Fields
+/// - `rows`, `cols` — the shape
+/// - `data`         — exactly `rows · cols` values, row-major
+#[derive(Debug, PartialEq)]
+pub struct MatrixViewMut<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: &'a mut [f64],
+}
+
+impl<'a> MatrixViewMut<'a> {
+    /// Views `data` as a writable `rows × cols` matrix.
+    ///
+    /// # Panics
+    /// Panics if `data.len() != rows * cols`.
+    pub fn new(rows: usize, cols: usize, data: &'a mut [f64]) -> MatrixViewMut<'a> {
+        assert_eq!(data.len(), rows * cols, "a {}×{} view needs {} values", rows, cols, rows * cols);
+        MatrixViewMut { rows, cols, data }
+    }
+
+    /// A read-only view of the same window.
+    pub fn as_view(&self) -> MatrixView<'_> {
+        MatrixView { rows: self.rows, cols: self.cols, data: self.data }
+    }
+
+    /// Row `i` as a slice.
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [f64] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// `self += rhs` element-wise.  Like `Matrix::add_assign_mat`, a
+    /// `1 × cols` `rhs` is added to every row.
+    ///
+    /// # Panics
+    /// Panics if the shapes neither match nor broadcast.
+    pub fn add_assign(&mut self, rhs: MatrixView<'_>) {
+        if self.cols != rhs.cols || (rhs.rows != self.rows && rhs.rows != 1) {
+            panic!("{}", ShapeError { op: "add", left: (self.rows, self.cols), right: (rhs.rows, rhs.cols) });
+        }
+        if rhs.rows == self.rows {
+            self.data.iter_mut().zip(rhs.data).for_each(|(a, b)| *a += b);
+        } else {
+            // `max(1)`: a view without columns has no rows to hand out.
+            for row in self.data.chunks_mut(self.cols.max(1)) {
+                row.iter_mut().zip(rhs.data).for_each(|(a, b)| *a += b);
+            }
+        }
+    }
+
+    /// Multiplies every element by `factor` in place.
+    pub fn scale_mut(&mut self, factor: f64) {
+        self.data.iter_mut().for_each(|v| *v *= factor);
+    }
+}
+
+impl Index<(usize, usize)> for MatrixViewMut<'_> {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        debug_assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}×{}", i, j, self.rows, self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for MatrixViewMut<'_> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        debug_assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}×{}", i, j, self.rows, self.cols);
+        &mut self.data[i * self.cols + j]
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Serialize, Deserialize};
+
+use crate::train::loop_fn::argmax;
+
+/// Precision / recall / F1 for a single class, computed one-vs-rest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    /// 0-based class index.
+    pub class: usize,
+    /// TP / (TP + FP); 0 when the class was never predicted.
+    pub precision: f64,
+    /// TP / (TP + FN); 0 when the class never occurs.
+    pub recall: f64,
+    /// Harmonic mean of precision and recall; 0 when both are 0.
+    pub f1: f64,
+    /// Number of samples whose true class is `class`.
+    pub support: usize,
+}
+
+/// How per-class scores are combined into one number.
+///
+/// - `Macro` — unweighted mean over classes; every class counts equally, so
+///   rare classes the model ignores pull the score down
+/// - `Micro` — computed from the TP / FP / FN totals over all classes; every
+///   sample counts equally.  For single-label data micro precision, recall
+///   and F1 all equal the accuracy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Average {
+    Macro,
+    Micro,
+}
+
+/// Precision / recall / F1 averaged over all classes (see `Average`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AveragedMetrics {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+/// Everything `classification_report` computes from one set of predictions.
+///
+/// # Fields
+/// - `per_class` — one entry per class, in class order
+/// - `macro_avg` — `Average::Macro` over `per_class`
+/// - `micro_avg` — `Average::Micro` over the confusion matrix
+/// - `confusion` — `confusion[truth][predicted]` sample counts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationReport {
+    pub per_class: Vec<ClassMetrics>,
+    pub macro_avg: AveragedMetrics,
+    pub micro_avg: AveragedMetrics,
+    pub confusion: Vec<Vec<usize>>,
+}
+
+/// Counts `confusion[truth][predicted]` over paired predictions and labels.
+///
+/// Labels at least two wide are one-hot (or soft) class vectors and both
+/// sides are reduced with argmax.  Single-column labels are binary: classes
+/// 0/1 at a 0.5 threshold, giving a 2×2 matrix.
+///
+/// # Panics
+/// Panics if `predictions` and `labels` differ in length.
+pub fn confusion_matrix(predictions: &[Vec<f64>], labels: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    assert_eq!(predictions.len(), labels.len(), "predictions and labels must have equal length");
+    let label_width = labels.first().map(|l| l.len()).unwrap_or(0);
+    let n_classes = match label_width {
+        0 => 0,
+        1 => 2,
+        w => w,
+    };
+
+    let mut confusion = vec![vec![0usize; n_classes]; n_classes];
+    for (output, label) in predictions.iter().zip(labels.iter()) {
+        let (truth, predicted) = if label_width == 1 {
+            ((label[0] >= 0.5) as usize, (output[0] >= 0.5) as usize)
+        } else {
+            (argmax(label), argmax(output))
+        };
+        if truth < n_classes && predicted < n_classes {
+            confusion[truth][predicted] += 1;
+        }
+    }
+    confusion
+}
+
+/// One-vs-rest precision / recall / F1 for every row of a confusion matrix.
+pub fn class_metrics(confusion: &[Vec<usize>]) -> Vec<ClassMetrics> {
+    (0..confusion.len())
+        .map(|c| {
+            let tp = confusion[c][c];
+            let support: usize = confusion[c].iter().sum();
+            let predicted: usize = confusion.iter().map(|row| row[c]).sum();
+            let precision = ratio(tp, predicted);
+            let recall    = ratio(tp, support);
+            ClassMetrics { class: c, precision, recall, f1: f1(precision, recall), support }
+        })
+        .collect()
+}
+
+/// Precision / recall / F1 averaged over all classes of a confusion matrix.
+/// All zero for an empty matrix.
+pub fn averaged_metrics(confusion: &[Vec<usize>], average: Average) -> AveragedMetrics {
+    let n_classes = confusion.len();
+    if n_classes == 0 {
+        return AveragedMetrics { precision: 0.0, recall: 0.0, f1: 0.0 };
+    }
+    match average {
+        Average::Macro => {
+            let per_class = class_metrics(confusion);
+            let mean = |f: fn(&ClassMetrics) -> f64| {
+                per_class.iter().map(f).sum::<f64>() / n_classes as f64
+            };
+            AveragedMetrics {
+                precision: mean(|c| c.precision),
+                recall:    mean(|c| c.recall),
+                f1:        mean(|c| c.f1),
+            }
+        }
+        Average::Micro => {
+            // Every off-diagonal count is one FP (for the predicted class)
+            // and one FN (for the true class), so both totals are N − TP.
+            let tp: usize = (0..n_classes).map(|c| confusion[c][c]).sum();
+            let total: usize = confusion.iter().flatten().sum();
+            let precision = ratio(tp, total);
+            let recall    = ratio(tp, total);
+            AveragedMetrics { precision, recall, f1: f1(precision, recall) }
+        }
+    }
+}
+
+/// Per-class and averaged precision / recall / F1 for `predictions` against
+/// `labels` (network outputs and one-hot labels; see `confusion_matrix` for
+/// how classes are read from them).
+///
+/// # Panics
+/// Panics if `predictions` and `labels` differ in length.
+pub fn classification_report(predictions: &[Vec<f64>], labels: &[Vec<f64>]) -> ClassificationReport {
+    let confusion = confusion_matrix(predictions, labels);
+    ClassificationReport {
+        per_class: class_metrics(&confusion),
+        macro_avg: averaged_metrics(&confusion, Average::Macro),
+        micro_avg: averaged_metrics(&confusion, Average::Micro),
+        confusion,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+fn ratio(num: usize, den: usize) -> f64 {
+    if den == 0 { 0.0 } else { num as f64 / den as f64 }
+}
+
+fn f1(precision: f64, recall: f64) -> f64 {
+    if precision + recall > 0.0 {
+        2.0 * precision * recall / (precision + recall)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,6 @@
+pub mod classification;
+
+pub use classification::{
+    averaged_metrics, class_metrics, classification_report, confusion_matrix, Average,
+    AveragedMetrics, ClassMetrics, ClassificationReport,
+};
@@ -0,0 +1,169 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Serialize, Deserialize};
+
+use crate::activation::activation::ActivationFunction;
+use crate::layers::dense::Layer;
+use crate::layers::ops::LayerOps;
+use crate::loss::loss_type::LossType;
+use crate::network::network::Network;
+
+/// Symmetric encoder/decoder network trained to reconstruct its input,
+/// used for dimensionality reduction and feature learning.
+///
+/// The encoder narrows `input_size` through the given sizes down to the
+/// code; the decoder mirrors it back.  With `tied` weights each decoder
+/// layer uses the transpose of its mirrored encoder layer's weights (biases
+/// stay separate), halving the parameters; `train::autoencoder` keeps the
+/// pairs tied.  After training, `encode` maps inputs to codes and `decode`
+/// maps codes back.
+///
+/// # Fields
+/// - `network`      — encoder layers followed by decoder layers
+/// - `encoder_layers` — how many of `network.layers` form the encoder
+/// - `tied`         — whether decoder weights are the encoder's transposes
+/// - `loss`         — reconstruction loss used by `train_autoencoder`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Autoencoder {
+    pub network: Network,
+    pub encoder_layers: usize,
+    pub tied: bool,
+    pub loss: LossType,
+}
+
+impl Autoencoder {
+    /// Builds an autoencoder `input_size → sizes[0] → … → code → … →
+    /// input_size`, where the code is `sizes.last()`.  Hidden and code layers
+    /// use `activation`, the reconstruction `output_activation` (e.g.
+    /// Sigmoid for inputs in [0, 1], Identity for standardized ones).  The
+    /// loss is MSE; set `loss` to change it.
+    ///
+    /// # Panics
+    /// Panics if `sizes` is empty.
+    pub fn new(
+        input_size: usize,
+        sizes: &[usize],
+        activation: ActivationFunction,
+        output_activation: ActivationFunction,
+        tied: bool,
+    ) -> Autoencoder {
+        Autoencoder::new_with_rng(input_size, sizes, activation, output_activation, tied, &mut rand::thread_rng())
+    }
+
+    /// `new` with weights initialized from `seed`.
+    pub fn new_seeded(
+        input_size: usize,
+        sizes: &[usize],
+        activation: ActivationFunction,
+        output_activation: ActivationFunction,
+        tied: bool,
+        seed: u64,
+    ) -> Autoencoder {
+        Autoencoder::new_with_rng(input_size, sizes, activation, output_activation, tied, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn new_with_rng<R: Rng + ?Sized>(
+        input_size: usize,
+        sizes: &[usize],
+        activation: ActivationFunction,
+        output_activation: ActivationFunction,
+        tied: bool,
+        rng: &mut R,
+    ) -> Autoencoder {
+        assert!(!sizes.is_empty(), "an autoencoder needs at least one code layer size");
+        let widths: Vec<usize> = std::iter::once(input_size).chain(sizes.iter().copied()).collect();
+
+        let mut layers: Vec<Box<dyn LayerOps>> = Vec::with_capacity(2 * sizes.len());
+        for pair in widths.windows(2) {
+            layers.push(Box::new(Layer::new_with_rng(pair[1], pair[0], activation.clone(), rng)));
+        }
+        for (k, pair) in widths.windows(2).enumerate().rev() {
+            let act = if k == 0 { output_activation.clone() } else { activation.clone() };
+            layers.push(Box::new(Layer::new_with_rng(pair[0], pair[1], act, rng)));
+        }
+
+        let mut autoencoder = Autoencoder {
+            network: Network { layers, metadata: None },
+            encoder_layers: sizes.len(),
+            tied,
+            loss: LossType::Mse,
+        };
+        if tied {
+            autoencoder.tie_weights();
+        }
+        autoencoder
+    }
+
+    /// Width of the code produced by `encode`.
+    pub fn code_size(&self) -> usize {
+        self.network.layers[self.encoder_layers - 1].output_size()
+    }
+
+    /// Maps `input` to its code (the encoder's output).
+    pub fn encode(&self, input: &[f64]) -> Vec<f64> {
+        run(&self.network.layers[..self.encoder_layers], input)
+    }
+
+    /// Maps a code back to input space (the decoder's output).
+    pub fn decode(&self, code: &[f64]) -> Vec<f64> {
+        run(&self.network.layers[self.encoder_layers..], code)
+    }
+
+    /// `decode(encode(input))`.
+    pub fn reconstruct(&self, input: &[f64]) -> Vec<f64> {
+        run(&self.network.layers, input)
+    }
+
+    /// The encoder as a standalone `Network`, e.g. to feed codes into a
+    /// downstream model or to save it on its own.
+    pub fn encoder(&self) -> Network {
+        Network { layers: self.network.layers[..self.encoder_layers].to_vec(), metadata: None }
+    }
+
+    /// The decoder as a standalone `Network`.
+    pub fn decoder(&self) -> Network {
+        Network { layers: self.network.layers[self.encoder_layers..].to_vec(), metadata: None }
+    }
+
+    /// Index pairs `(encoder, decoder)` of layers whose weights are tied.
+    pub(crate) fn tied_pairs(&self) -> impl Iterator<Item = (usize, usize)> {
+        let last = self.network.layers.len() - 1;
+        (0..self.encoder_layers).map(move |i| (i, last - i))
+    }
+
+    /// Sets every decoder layer's weights to the transpose of its mirrored
+    /// encoder layer's weights.
+    pub(crate) fn tie_weights(&mut self) {
+        for (enc, dec) in self.tied_pairs() {
+            let transposed = self.network.layers[enc].params().0.transpose();
+            *self.network.layers[dec].params_mut().0 = transposed;
+        }
+    }
+
+    /// Serializes the autoencoder to a pretty-printed JSON file.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Deserializes an autoencoder from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<Autoencoder> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(std::io::Error::other)
+    }
+}
+
+/// Inference through a run of layers (no temperature).
+fn run(layers: &[Box<dyn LayerOps>], input: &[f64]) -> Vec<f64> {
+    let mut current = input.to_vec();
+    let mut next = Vec::new();
+    for layer in layers {
+        layer.predict_into(&current, &mut next, 1.0);
+        std::mem::swap(&mut current, &mut next);
+    }
+    current
+}
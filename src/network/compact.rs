@@ -0,0 +1,82 @@
+//! Single-precision JSON model files.
+//!
+//! `save_json_f32` writes the same document as `save_json`, but every value
+//! inside a weight matrix is rounded to `f32` and printed with at most nine
+//! significant digits, and the JSON is not pretty-printed.  For a large
+//! dense model that is well under half the size of the `f64` file.  The
+//! result is an ordinary model file: `load_json` reads it as-is, while
+//! `load_json_f32` snaps every weight back to the exact stored `f32` value.
+//! Rounding changes the weights, so these files carry no fingerprint.
+
+use std::io::{BufReader, BufWriter, Error, Result};
+
+use serde_json::{Number, Value};
+
+use crate::network::network::Network;
+
+impl Network {
+    /// Serializes the network to a compact JSON file with weights stored at
+    /// `f32` precision.  Metadata and layer settings are written unchanged.
+    pub fn save_json_f32(&self, path: &str) -> Result<()> {
+        let mut value = serde_json::to_value(self).map_err(Error::other)?;
+        drop_fingerprint(&mut value);
+        map_matrix_values(&mut value, &|v| {
+            format!("{:?}", v as f32).parse::<Number>().map(Value::Number).unwrap_or(Value::Null)
+        });
+        let writer = BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(writer, &value).map_err(Error::other)
+    }
+
+    /// Deserializes a network from a JSON file, rounding every weight to the
+    /// nearest `f32`.  For a file written by `save_json_f32` this recovers
+    /// exactly the stored single-precision weights, so inference matches the
+    /// `.npz` written by `export_npz_f32` bit for bit.
+    pub fn load_json_f32(path: &str) -> Result<Network> {
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        let mut value: Value = serde_json::from_reader(reader).map_err(Error::other)?;
+        drop_fingerprint(&mut value);
+        map_matrix_values(&mut value, &|v| Value::from(v as f32 as f64));
+        serde_json::from_value(value).map_err(Error::other)
+    }
+}
+
+/// Removes `metadata.fingerprint`, which no longer matches once the weights
+/// are rounded.
+fn drop_fingerprint(network: &mut Value) {
+    if let Some(Value::Object(metadata)) = network.get_mut("metadata") {
+        metadata.remove("fingerprint");
+    }
+}
+
+/// Replaces every number in the `data` of each serialized `Matrix` (an
+/// object with `rows`, `cols` and `data`, flat or nested) under `layers`.
+fn map_matrix_values(network: &mut Value, f: &dyn Fn(f64) -> Value) {
+    fn visit(value: &mut Value, f: &dyn Fn(f64) -> Value) {
+        match value {
+            Value::Object(fields) if fields.contains_key("rows") && fields.contains_key("cols") => {
+                if let Some(data) = fields.get_mut("data") {
+                    map_numbers(data, f);
+                }
+            }
+            Value::Object(fields) => fields.values_mut().for_each(|v| visit(v, f)),
+            Value::Array(items) => items.iter_mut().for_each(|v| visit(v, f)),
+            _ => {}
+        }
+    }
+
+    fn map_numbers(value: &mut Value, f: &dyn Fn(f64) -> Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|v| map_numbers(v, f)),
+            Value::Number(n) => {
+                if let Some(v) = n.as_f64() {
+                    *value = f(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(layers) = network.get_mut("layers") {
+        visit(layers, f);
+    }
+}
@@ -0,0 +1,140 @@
+//! Compute and memory estimates for an architecture.
+//!
+//! `Network::cost_estimate` and `NetworkSpec::cost_estimate` count, per
+//! layer and in total, the multiply-accumulate operations (MACs) of one
+//! inference pass over a single sample and the memory taken by the
+//! parameters, so the size of a model can be judged before training it.
+//! Parameters and activations are `f64`, 8 bytes each.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::network::network::Network;
+use crate::network::spec::NetworkSpec;
+
+const BYTES_PER_VALUE: usize = std::mem::size_of::<f64>();
+
+/// Cost of one layer for a single sample.
+///
+/// # Fields
+/// - `name`        — the layer's name, if any
+/// - `kind`        — layer type tag, e.g. `"dense"`
+/// - `macs`        — multiply-accumulate operations of one forward pass
+/// - `params`      — trainable parameters (weights and biases)
+/// - `activations` — values the layer outputs (and training caches)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LayerCost {
+    pub name: Option<String>,
+    pub kind: String,
+    pub macs: usize,
+    pub params: usize,
+    pub activations: usize,
+}
+
+/// Per-layer and total cost of a network; see the module docs.
+///
+/// # Fields
+/// - `layers`           — one entry per layer, in forward order
+/// - `total_macs`       — MACs of one inference pass over a single sample
+/// - `total_params`     — trainable parameters
+/// - `param_bytes`      — memory taken by the parameters
+/// - `activation_bytes` — memory for one sample's layer outputs, all of
+///   which training keeps for backpropagation (multiply by the batch size)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CostEstimate {
+    pub layers: Vec<LayerCost>,
+    pub total_macs: usize,
+    pub total_params: usize,
+    pub param_bytes: usize,
+    pub activation_bytes: usize,
+}
+
+impl CostEstimate {
+    fn from_layers(layers: Vec<LayerCost>) -> CostEstimate {
+        let total_params = layers.iter().map(|l| l.params).sum::<usize>();
+        CostEstimate {
+            total_macs: layers.iter().map(|l| l.macs).sum(),
+            total_params,
+            param_bytes: total_params * BYTES_PER_VALUE,
+            activation_bytes: layers.iter().map(|l| l.activations).sum::<usize>() * BYTES_PER_VALUE,
+            layers,
+        }
+    }
+}
+
+impl Network {
+    /// Per-layer and total MACs, parameter count and memory (see
+    /// `CostEstimate`).
+    pub fn cost_estimate(&self) -> CostEstimate {
+        CostEstimate::from_layers(self.layers.iter().map(|layer| LayerCost {
+            name: layer.name().map(str::to_owned),
+            kind: layer.kind().to_owned(),
+            macs: layer.macs(),
+            params: layer.param_count(),
+            activations: layer.output_size(),
+        }).collect())
+    }
+}
+
+impl NetworkSpec {
+    /// `Network::cost_estimate` of the network this spec builds, computed
+    /// from the layer sizes without allocating any weights.
+    pub fn cost_estimate(&self) -> CostEstimate {
+        CostEstimate::from_layers(self.layers.iter().map(|layer| LayerCost {
+            name: layer.name.clone(),
+            kind: "dense".to_owned(),
+            macs: layer.input_size * layer.size,
+            params: (layer.input_size + 1) * layer.size,
+            activations: layer.size,
+        }).collect())
+    }
+}
+
+/// Plain-text table: one row per layer, then the totals.
+impl fmt::Display for CostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<4}  {:<16}  {:<10}  {:>10}  {:>10}", "#", "Name", "Kind", "MACs", "Params")?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<4}  {:<16}  {:<10}  {:>10}  {:>10}",
+                i,
+                layer.name.as_deref().unwrap_or("—"),
+                layer.kind,
+                format_count(layer.macs),
+                format_count(layer.params),
+            )?;
+        }
+        writeln!(f, "Total MACs per sample: {}", format_count(self.total_macs))?;
+        writeln!(f, "Parameters: {} ({})", format_count(self.total_params), format_bytes(self.param_bytes))?;
+        writeln!(f, "Activations per sample: {}", format_bytes(self.activation_bytes))
+    }
+}
+
+/// `1234567` → `"1.23M"`; counts below 1000 are printed as is.
+pub fn format_count(n: usize) -> String {
+    let (value, unit) = scaled(n, 1000.0, &["", "K", "M", "G", "T"]);
+    format!("{}{}", value, unit)
+}
+
+/// `1536` → `"1.50 KiB"`.
+pub fn format_bytes(n: usize) -> String {
+    let (value, unit) = scaled(n, 1024.0, &["", "Ki", "Mi", "Gi", "Ti"]);
+    format!("{} {}B", value, unit)
+}
+
+/// `n` divided by `step` until it drops below it, with the matching unit.
+fn scaled(n: usize, step: f64, units: &[&'static str]) -> (String, &'static str) {
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= step && unit + 1 < units.len() {
+        value /= step;
+        unit += 1;
+    }
+    if unit == 0 {
+        (n.to_string(), units[0])
+    } else {
+        (format!("{:.2}", value), units[unit])
+    }
+}
@@ -0,0 +1,176 @@
+//! Architecture diagrams: Graphviz DOT and a self-contained SVG.
+//!
+//! Both render the same chain of boxes — the input, one box per layer
+//! (name, kind, shape, activation, parameter count) and, for a
+//! `NetworkSpec`, the loss — with each edge labelled by the width of the
+//! vector it carries.  `to_dot` output is meant for `dot -Tsvg`; `to_svg`
+//! needs no external tools (the studio's Architect tab embeds it).
+
+use crate::network::network::Network;
+use crate::network::spec::NetworkSpec;
+
+/// One box of the diagram.
+struct Node {
+    title: String,
+    lines: Vec<String>,
+    /// Width of the vector leaving this box, used as the next edge's label.
+    output: Option<usize>,
+}
+
+impl Network {
+    /// Graphviz description of the layer graph, e.g. for
+    /// `dot -Tpng model.dot -o model.png`.
+    pub fn to_dot(&self) -> String {
+        dot("network", &self.nodes())
+    }
+
+    /// The layer graph as a standalone SVG document.
+    pub fn to_svg(&self) -> String {
+        svg(&self.nodes())
+    }
+
+    fn nodes(&self) -> Vec<Node> {
+        let input = self.layers.first().map(|l| l.input_size());
+        let mut nodes = vec![input_node(input)];
+        nodes.extend(self.layers.iter().enumerate().map(|(i, layer)| {
+            let mut lines = vec![format!("{} {} → {}", layer.kind(), layer.input_size(), layer.output_size())];
+            if let Some(activation) = layer.activation() {
+                lines.push(format!("{:?}", activation));
+            }
+            lines.push(format!("{} params", layer.param_count()));
+            Node { title: layer.name().map(str::to_owned).unwrap_or_else(|| format!("layer {}", i)), lines, output: Some(layer.output_size()) }
+        }));
+        nodes
+    }
+}
+
+impl NetworkSpec {
+    /// Graphviz description of the architecture (see `Network::to_dot`),
+    /// ending in the loss.
+    pub fn to_dot(&self) -> String {
+        dot(&self.name, &self.nodes())
+    }
+
+    /// The architecture as a standalone SVG document.
+    pub fn to_svg(&self) -> String {
+        svg(&self.nodes())
+    }
+
+    fn nodes(&self) -> Vec<Node> {
+        let mut nodes = vec![input_node(self.layers.first().map(|l| l.input_size))];
+        nodes.extend(self.layers.iter().enumerate().map(|(i, layer)| Node {
+            title: layer.name.clone().unwrap_or_else(|| format!("layer {}", i)),
+            lines: vec![
+                format!("dense {} → {}", layer.input_size, layer.size),
+                format!("{:?}", layer.activation),
+                format!("{} params", layer.size * layer.input_size + layer.size),
+            ],
+            output: Some(layer.size),
+        }));
+        nodes.push(Node { title: "loss".into(), lines: vec![format!("{:?}", self.loss)], output: None });
+        nodes
+    }
+}
+
+fn input_node(width: Option<usize>) -> Node {
+    Node {
+        title: "input".into(),
+        lines: width.map(|w| vec![format!("{} values", w)]).unwrap_or_default(),
+        output: width,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DOT
+// ---------------------------------------------------------------------------
+
+fn dot(name: &str, nodes: &[Node]) -> String {
+    let mut out = format!("digraph \"{}\" {{\n", dot_escape(name));
+    out.push_str("    rankdir=TB;\n");
+    out.push_str("    node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+    out.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n");
+    for (i, node) in nodes.iter().enumerate() {
+        let label: Vec<String> = std::iter::once(&node.title).chain(&node.lines).map(|s| dot_escape(s)).collect();
+        let shape = if i == 0 { ", shape=ellipse" } else { "" };
+        out.push_str(&format!("    n{} [label=\"{}\"{}];\n", i, label.join("\\n"), shape));
+    }
+    for (i, pair) in nodes.windows(2).enumerate() {
+        let label = pair[0].output.map(|w| format!(" [label=\"{}\"]", w)).unwrap_or_default();
+        out.push_str(&format!("    n{} -> n{}{};\n", i, i + 1, label));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// ---------------------------------------------------------------------------
+// SVG
+// ---------------------------------------------------------------------------
+
+const BOX_WIDTH: usize = 220;
+const LINE_HEIGHT: usize = 16;
+const PADDING: usize = 10;
+const GAP: usize = 34;
+const MARGIN: usize = 12;
+
+fn svg(nodes: &[Node]) -> String {
+    let heights: Vec<usize> = nodes.iter().map(|n| (1 + n.lines.len()) * LINE_HEIGHT + 2 * PADDING).collect();
+    let width = BOX_WIDTH + 2 * MARGIN;
+    let height = heights.iter().sum::<usize>() + GAP * nodes.len().saturating_sub(1) + 2 * MARGIN;
+    let center = width / 2;
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"Helvetica, Arial, sans-serif\" font-size=\"12\">\n",
+        w = width, h = height
+    );
+    out.push_str(
+        "  <defs><marker id=\"arrow\" markerWidth=\"8\" markerHeight=\"8\" refX=\"7\" refY=\"4\" orient=\"auto\">\
+         <path d=\"M0,0 L8,4 L0,8 z\" fill=\"#555\"/></marker></defs>\n",
+    );
+
+    let mut y = MARGIN;
+    for (i, (node, &h)) in nodes.iter().zip(&heights).enumerate() {
+        let radius = if i == 0 { h / 2 } else { 8 };
+        out.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"#f5f7fa\" stroke=\"#555\"/>\n",
+            MARGIN, y, BOX_WIDTH, h, radius
+        ));
+        let mut text_y = y + PADDING + LINE_HEIGHT - 4;
+        out.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-weight=\"bold\">{}</text>\n",
+            center, text_y, xml_escape(&node.title)
+        ));
+        for line in &node.lines {
+            text_y += LINE_HEIGHT;
+            out.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#333\">{}</text>\n",
+                center, text_y, xml_escape(line)
+            ));
+        }
+        y += h;
+
+        if i + 1 < nodes.len() {
+            out.push_str(&format!(
+                "  <line x1=\"{c}\" y1=\"{}\" x2=\"{c}\" y2=\"{}\" stroke=\"#555\" marker-end=\"url(#arrow)\"/>\n",
+                y, y + GAP - 2, c = center
+            ));
+            if let Some(w) = node.output {
+                out.push_str(&format!(
+                    "  <text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"#777\">{}</text>\n",
+                    center + 6, y + GAP / 2 + 4, w
+                ));
+            }
+            y += GAP;
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,74 @@
+use serde::{Serialize, Deserialize};
+use crate::network::network::Network;
+
+/// A collection of networks whose predictions are averaged.
+///
+/// All members must share the same input and output sizes.  Produced by
+/// `train_snapshot_ensemble`, but can also be assembled by hand from
+/// independently trained models.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Ensemble {
+    pub members: Vec<Network>,
+}
+
+impl Ensemble {
+    pub fn new(members: Vec<Network>) -> Ensemble {
+        Ensemble { members }
+    }
+
+    /// Adds a network to the ensemble.
+    pub fn push(&mut self, network: Network) {
+        self.members.push(network);
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Runs every member on `input` and returns the element-wise mean of
+    /// their outputs.  For Softmax members this is the averaged class
+    /// distribution.
+    ///
+    /// `input` is raw: each member goes through `Network::predict`, so its
+    /// own `polynomial_features`, `input_scaling` and calibrated temperature
+    /// apply.
+    ///
+    /// # Panics
+    /// Panics if the ensemble is empty.
+    pub fn forward(&mut self, input: Vec<f64>) -> Vec<f64> {
+        assert!(!self.members.is_empty(), "cannot run an empty ensemble");
+        let n = self.members.len() as f64;
+        let mut sum: Vec<f64> = Vec::new();
+        for member in &mut self.members {
+            let output = member.predict(&input);
+            if sum.is_empty() {
+                sum = output;
+            } else {
+                for (s, o) in sum.iter_mut().zip(output.iter()) {
+                    *s += o;
+                }
+            }
+        }
+        sum.iter().map(|s| s / n).collect()
+    }
+
+    /// Serializes all members to a pretty-printed JSON file.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Deserializes an ensemble from a JSON file previously written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<Ensemble> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(std::io::Error::other)
+    }
+}
@@ -0,0 +1,65 @@
+//! Content hash of a network's weights.
+//!
+//! Every serialized `Network` with metadata records `metadata.fingerprint`,
+//! and loading verifies it, so a model file whose weights were corrupted or
+//! edited by hand is rejected instead of silently predicting garbage.  A
+//! network saved without metadata carries no fingerprint and is loaded
+//! unverified.  Comparing
+//! fingerprints tells whether two saved models hold the same weights
+//! without loading either.
+
+use crate::network::network::Network;
+
+/// FNV-1a, 64-bit: stable across platforms and Rust versions, unlike
+/// `std`'s `DefaultHasher`.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write(&(n as u64).to_le_bytes());
+    }
+}
+
+impl Network {
+    /// Fingerprint of the current weights: a 16-digit hex hash of every
+    /// layer's kind, activation, parameter shapes and exact parameter
+    /// values.  Layer names and metadata do not contribute, so renaming or
+    /// relabelling a model keeps its fingerprint.
+    ///
+    /// `metadata.fingerprint` holds the value recorded when the model was
+    /// last saved; this recomputes it from the weights in memory.
+    pub fn fingerprint(&self) -> String {
+        let mut hash = Fnv1a::new();
+        hash.write_usize(self.layers.len());
+        for layer in &self.layers {
+            hash.write(layer.kind().as_bytes());
+            hash.write(layer.activation().map(|a| format!("{:?}", a)).unwrap_or_default().as_bytes());
+            let (weights, biases) = layer.params();
+            for m in [weights, biases] {
+                hash.write_usize(m.rows);
+                hash.write_usize(m.cols);
+                for v in &m.data {
+                    hash.write(&v.to_bits().to_le_bytes());
+                }
+            }
+        }
+        format!("{:016x}", hash.0)
+    }
+
+    /// `true` when both networks have the same architecture and bit-identical
+    /// weights (equal fingerprints).
+    pub fn has_same_weights(&self, other: &Network) -> bool {
+        self.fingerprint() == other.fingerprint()
+    }
+}
@@ -0,0 +1,175 @@
+//! Model file format versioning.
+//!
+//! Every serialized `Network` carries a `format_version`.  Reading a file
+//! first upgrades it, one version at a time, to `FORMAT_VERSION` and only
+//! then builds the layers, so schema changes are handled here instead of
+//! surfacing as serde errors deep inside a layer.
+//!
+//! History:
+//! - 1 — everything written before versioning (no `format_version` field):
+//!   layers may lack a `type` tag (dense), matrices may store `data` as a
+//!   list of rows, and `metadata` may be missing.
+//! - 2 — `format_version` written; every layer tagged, matrices flat,
+//!   `metadata` always present (possibly `null`).
+//!
+//! To change the schema: bump `FORMAT_VERSION` and append a migration from
+//! the previous version to `MIGRATIONS`.
+
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::activation::activation::ActivationFunction;
+use crate::activation::registry::is_activation_registered;
+use crate::layers::layer_from_json;
+use crate::network::metadata::ModelMetadata;
+use crate::network::network::Network;
+
+/// Format version written by `save_json` and every other `Network`
+/// serialization.  Serializing a network that has metadata also records the
+/// weights' fingerprint in it (see `network::fingerprint`); `metadata: None`
+/// is written as `null` and reloads as `None`.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Version assumed for files without a `format_version` field.
+const UNVERSIONED: u32 = 1;
+
+/// Upgrades one version of a serialized network's top-level object in place.
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` document to version `i + 2`.
+const MIGRATIONS: &[Migration] = &[v1_to_v2];
+
+/// Upgrades a serialized network in place to `FORMAT_VERSION`.
+///
+/// # Errors
+/// A readable message if `value` is not a model document, its version is
+/// newer than this build understands, or a migration step fails.
+pub fn migrate(value: &mut Value) -> Result<(), String> {
+    let doc = value.as_object_mut()
+        .ok_or("a model file must be a JSON object")?;
+    if !doc.contains_key("layers") {
+        return Err("not a model file: missing \"layers\"".into());
+    }
+    let version = match doc.get("format_version") {
+        None => UNVERSIONED,
+        Some(v) => v.as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v >= UNVERSIONED)
+            .ok_or_else(|| format!("invalid format_version {}", v))?,
+    };
+    if version > FORMAT_VERSION {
+        return Err(format!(
+            "model file format version {} is newer than this version of ferrite-nn supports ({})",
+            version, FORMAT_VERSION
+        ));
+    }
+    for (from, step) in (version..FORMAT_VERSION).zip(&MIGRATIONS[(version - UNVERSIONED) as usize..]) {
+        step(doc).map_err(|e| format!("upgrading model file from format version {}: {}", from, e))?;
+    }
+    doc.insert("format_version".into(), Value::from(FORMAT_VERSION));
+    Ok(())
+}
+
+fn v1_to_v2(doc: &mut Map<String, Value>) -> Result<(), String> {
+    let layers = doc.get_mut("layers")
+        .and_then(Value::as_array_mut)
+        .ok_or("\"layers\" must be a list")?;
+    for (i, layer) in layers.iter_mut().enumerate() {
+        let fields = layer.as_object_mut().ok_or_else(|| format!("layer {} is not an object", i))?;
+        fields.entry("type").or_insert_with(|| Value::from("dense"));
+        for value in fields.values_mut() {
+            flatten_matrix(value).map_err(|e| format!("layer {}: {}", i, e))?;
+        }
+    }
+    doc.entry("metadata").or_insert(Value::Null);
+    Ok(())
+}
+
+/// Rewrites a matrix stored as a list of rows (`{rows, cols, data: [[..]]}`)
+/// to the flat layout, recursing into nested objects.
+fn flatten_matrix(value: &mut Value) -> Result<(), String> {
+    let Value::Object(fields) = value else { return Ok(()) };
+    if !(fields.contains_key("rows") && fields.contains_key("cols")) {
+        return fields.values_mut().try_for_each(flatten_matrix);
+    }
+    if let Some(Value::Array(rows)) = fields.get_mut("data") {
+        if rows.iter().all(Value::is_array) && !rows.is_empty() {
+            let flat: Vec<Value> = rows.drain(..)
+                .flat_map(|row| match row {
+                    Value::Array(values) => values,
+                    _ => unreachable!("checked above"),
+                })
+                .collect();
+            *rows = flat;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Serde
+// ---------------------------------------------------------------------------
+
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Network", 3)?;
+        state.serialize_field("format_version", &FORMAT_VERSION)?;
+        state.serialize_field("layers", &self.layers)?;
+        let metadata = self.metadata.clone().map(|mut metadata| {
+            metadata.fingerprint = Some(self.fingerprint());
+            metadata
+        });
+        state.serialize_field("metadata", &metadata)?;
+        state.end()
+    }
+}
+
+/// Migrates the document to `FORMAT_VERSION`, then builds each layer;
+/// errors name the offending layer.  Custom activations must already be
+/// registered, and a recorded `metadata.fingerprint` must match the loaded
+/// weights.
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        migrate(&mut value).map_err(D::Error::custom)?;
+
+        let Value::Object(mut doc) = value else { unreachable!("migrate checks for an object") };
+        let layers = match doc.remove("layers") {
+            Some(Value::Array(layers)) => layers,
+            _ => return Err(D::Error::custom("\"layers\" must be a list")),
+        };
+        let layers = layers.into_iter()
+            .enumerate()
+            .map(|(i, layer)| {
+                let layer = layer_from_json(layer).map_err(|e| D::Error::custom(format!("layer {}: {}", i, e)))?;
+                if let Some(ActivationFunction::Custom(name)) = layer.activation() {
+                    if !is_activation_registered(name) {
+                        return Err(D::Error::custom(format!(
+                            "layer {}: custom activation '{}' is not registered; call register_activation before loading",
+                            i, name
+                        )));
+                    }
+                }
+                Ok(layer)
+            })
+            .collect::<Result<_, _>>()?;
+        let metadata = doc.remove("metadata")
+            .map(serde_json::from_value::<Option<ModelMetadata>>)
+            .transpose()
+            .map_err(|e| D::Error::custom(format!("metadata: {}", e)))?
+            .flatten();
+        let network = Network { layers, metadata };
+        if let Some(recorded) = network.metadata.as_ref().and_then(|m| m.fingerprint.as_deref()) {
+            let actual = network.fingerprint();
+            if recorded != actual {
+                return Err(D::Error::custom(format!(
+                    "weights do not match the recorded fingerprint {} (found {}): the file is corrupted or was edited",
+                    recorded, actual
+                )));
+            }
+        }
+        Ok(network)
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Serialize, Deserialize};
+
+use crate::loss::loss_type::LossType;
+use crate::network::network::Network;
+
+/// One output of a `Graph`: a network reading the trunk's output, the loss
+/// it is trained with, and that loss's weight in the total.
+///
+/// # Fields
+/// - `name`    — label used in reports (e.g. `"price"`, `"category"`)
+/// - `network` — the head's layers
+/// - `loss`    — loss applied to this head's output during `train_graph`
+/// - `weight`  — multiplier on this head's loss gradient (1.0 = equal share)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GraphHead {
+    pub name: String,
+    pub network: Network,
+    pub loss: LossType,
+    pub weight: f64,
+}
+
+impl GraphHead {
+    /// A head with weight 1.0.
+    pub fn new(name: impl Into<String>, network: Network, loss: LossType) -> GraphHead {
+        GraphHead { name: name.into(), network, loss, weight: 1.0 }
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> GraphHead {
+        self.weight = weight;
+        self
+    }
+}
+
+/// A multi-input, multi-output network for mixed inputs (e.g. tabular
+/// features plus an image) and multi-task training.
+///
+/// Each input goes through its own branch; the branch outputs are
+/// concatenated in order and fed to the shared trunk, whose output feeds
+/// every head.  A branch or trunk without layers passes its input through
+/// unchanged, so raw features can join the concatenation directly.  Train
+/// with `train::graph::train_graph`.
+///
+/// # Fields
+/// - `branches` — one network per input, in input order
+/// - `trunk`    — shared layers over the concatenated branch outputs
+/// - `heads`    — one network per output, each with its own loss
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Graph {
+    pub branches: Vec<Network>,
+    pub trunk: Network,
+    pub heads: Vec<GraphHead>,
+}
+
+impl Graph {
+    /// Assembles a graph.
+    ///
+    /// # Panics
+    /// Panics if there are no branches or no heads, or if the widths do
+    /// not line up: the branch outputs must add up to the trunk's input
+    /// and every head must read the trunk's output.  Widths of branches
+    /// without layers are only known at run time and are not checked.
+    pub fn new(branches: Vec<Network>, trunk: Network, heads: Vec<GraphHead>) -> Graph {
+        assert!(!branches.is_empty(), "a graph needs at least one input branch");
+        assert!(!heads.is_empty(), "a graph needs at least one head");
+        let graph = Graph { branches, trunk, heads };
+        if let Err(msg) = graph.check_widths() {
+            panic!("{}", msg);
+        }
+        graph
+    }
+
+    /// Every network in optimizer order: branches, trunk, then heads.
+    pub fn networks(&self) -> impl Iterator<Item = &Network> {
+        self.branches.iter().chain(std::iter::once(&self.trunk)).chain(self.heads.iter().map(|h| &h.network))
+    }
+
+    pub fn networks_mut(&mut self) -> impl Iterator<Item = &mut Network> {
+        self.branches.iter_mut()
+            .chain(std::iter::once(&mut self.trunk))
+            .chain(self.heads.iter_mut().map(|h| &mut h.network))
+    }
+
+    /// Training forward pass; caches activations in every layer for
+    /// backprop.  Returns one output per head.
+    ///
+    /// # Panics
+    /// Panics if `inputs.len()` differs from the number of branches.
+    pub fn forward(&mut self, inputs: &[&[f64]]) -> Vec<Vec<f64>> {
+        self.check_input_count(inputs.len());
+        let joined: Vec<f64> = self.branches.iter_mut()
+            .zip(inputs)
+            .flat_map(|(branch, input)| branch.forward(input.to_vec()))
+            .collect();
+        let shared = self.trunk.forward(joined);
+        self.heads.iter_mut().map(|head| head.network.forward(shared.clone())).collect()
+    }
+
+    /// Inference-only forward pass through `&self` (see `Network::predict`).
+    /// Returns one output per head.
+    ///
+    /// # Panics
+    /// Panics if `inputs.len()` differs from the number of branches.
+    pub fn predict(&self, inputs: &[&[f64]]) -> Vec<Vec<f64>> {
+        self.check_input_count(inputs.len());
+        let joined: Vec<f64> = self.branches.iter()
+            .zip(inputs)
+            .flat_map(|(branch, input)| branch.predict(input))
+            .collect();
+        let shared = self.trunk.predict(&joined);
+        self.heads.iter().map(|head| head.network.predict(&shared)).collect()
+    }
+
+    /// Serializes the graph to a pretty-printed JSON file.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Deserializes a graph from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<Graph> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(std::io::Error::other)
+    }
+
+    fn check_input_count(&self, n: usize) {
+        assert_eq!(n, self.branches.len(), "graph has {} input branches, got {} inputs", self.branches.len(), n);
+    }
+
+    /// Width of the concatenated branch outputs, if every branch has layers.
+    fn joined_width(&self) -> Option<usize> {
+        self.branches.iter().map(|b| b.layers.last().map(|l| l.output_size())).sum()
+    }
+
+    fn check_widths(&self) -> Result<(), String> {
+        let joined = self.joined_width();
+        let shared = match (self.trunk.layers.first(), self.trunk.layers.last()) {
+            (Some(first), Some(last)) => {
+                if let Some(width) = joined.filter(|&w| w != first.input_size()) {
+                    return Err(format!("branch outputs add up to {} values but the trunk expects {}", width, first.input_size()));
+                }
+                Some(last.output_size())
+            }
+            _ => joined,
+        };
+        for head in &self.heads {
+            let expected = head.network.layers.first().map(|l| l.input_size());
+            if let (Some(width), Some(expected)) = (shared, expected) {
+                if width != expected {
+                    return Err(format!("head '{}' expects {} inputs but the trunk produces {}", head.name, expected, width));
+                }
+            }
+        }
+        Ok(())
+    }
+}
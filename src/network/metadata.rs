@@ -1,5 +1,12 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::data::categorical::CategoricalEncoder;
+use crate::data::scaling::Scaling;
+use crate::preprocess::polynomial::PolynomialFeatures;
+use crate::preprocess::text::TextVectorizer;
+
 /// Describes how to interpret the input fed to a Network.
 /// Stored in model JSON; GUI reads this to render the right input widget.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +18,12 @@ pub enum InputType {
     ImageGrayscale { width: u32, height: u32 },
     /// RGB image resized to width×height, normalized to [0, 1], flattened as R,G,B,...
     ImageRgb { width: u32, height: u32 },
+    /// Raw text, encoded as a bag of words over the vectorizer's vocabulary
+    /// (one input per word).
+    Text { vectorizer: TextVectorizer },
+    /// A raw row of mixed numeric and categorical cells, encoded by the
+    /// encoder (see `data::categorical`).
+    Tabular { encoder: CategoricalEncoder },
 }
 
 /// Optional annotations attached to a saved Network.
@@ -19,6 +32,32 @@ pub enum InputType {
 pub struct ModelMetadata {
     pub description: Option<String>,
     pub input_type: Option<InputType>,
+    /// Names of the input features in order (e.g. the CSV header of the
+    /// training data), used to label the inputs in the studio Test tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature_names: Option<Vec<String>>,
     /// Human-readable class labels for the output layer (e.g. ["0","1",...,"9"]).
     pub output_labels: Option<Vec<String>>,
+    /// Final evaluation metrics recorded when the model was saved
+    /// (e.g. `"val_accuracy"`, `"train_loss"`), keyed by metric name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<BTreeMap<String, f64>>,
+    /// Softmax temperature fitted by `eval::calibrate_temperature`; inference
+    /// divides the output logits by it.  `None` means uncalibrated (1.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// `Network::fingerprint` of the weights, recorded whenever a network
+    /// with metadata is serialized and verified when it is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Per-feature statistics fitted on the training inputs when training
+    /// with `TrainConfig::input_scaling`; the inference paths apply them to
+    /// raw inputs (see `Network::input_scaling`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_scaling: Option<Scaling>,
+    /// Degree-2 feature expansion the network was trained with
+    /// (`TrainConfig::polynomial_features`); the inference paths apply it to
+    /// raw inputs before `input_scaling` (see `Network::polynomial_features`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polynomial_features: Option<PolynomialFeatures>,
 }
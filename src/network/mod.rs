@@ -1,6 +1,25 @@
+pub mod autoencoder;
+pub mod cost;
+pub mod ensemble;
+pub mod graph;
 pub mod metadata;
+#[allow(clippy::module_inception)]
 pub mod network;
 pub mod spec;
+pub mod format;
+pub mod session;
+pub mod registry;
+mod compact;
+mod fingerprint;
+mod diagram;
+mod npz;
+mod surgery;
 
 pub use network::Network;
-pub use spec::{NetworkSpec, LayerSpec};
+pub use autoencoder::Autoencoder;
+pub use cost::{CostEstimate, LayerCost};
+pub use ensemble::Ensemble;
+pub use graph::{Graph, GraphHead};
+pub use session::InferenceSession;
+pub use registry::{ModelRegistry, ModelEntry};
+pub use spec::{NetworkSpec, LayerSpec, SpecError};
@@ -1,20 +1,45 @@
 use crate::{activation::activation::ActivationFunction, layers::dense::Layer};
+use crate::data::scaling::Scaling;
+use crate::layers::dense::{check_mask, masked_softmax_in_place};
+use crate::layers::ops::LayerOps;
+use crate::math::init::WeightInit;
+use crate::math::matrix::Matrix;
 use crate::network::metadata::ModelMetadata;
-use crate::network::spec::NetworkSpec;
-use serde::{Serialize, Deserialize};
+use crate::network::spec::{NetworkSpec, SpecError};
+use crate::preprocess::polynomial::PolynomialFeatures;
+use crate::train::loop_fn::argmax;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-#[derive(Clone, Serialize, Deserialize)]
+/// Serialized with a `format_version`; see `network::format` for the
+/// schema history and how older files are upgraded on load.
+#[derive(Clone)]
 pub struct Network {
-    pub layers: Vec<Layer>,
-    #[serde(default)]
+    /// The layers in forward order; dense `Layer`s unless built by hand.
+    pub layers: Vec<Box<dyn LayerOps>>,
     pub metadata: Option<ModelMetadata>,
 }
 
 impl Network {
     /// Builds a network from (size, input_size, activation) tuples.
     pub fn new(layer_specs: Vec<(usize, usize, ActivationFunction)>) -> Network {
+        Network::new_with_rng(layer_specs, &mut rand::thread_rng())
+    }
+
+    /// `new` with weights initialized from `seed`: the same seed and layer
+    /// specs always produce the same network.
+    pub fn new_seeded(layer_specs: Vec<(usize, usize, ActivationFunction)>, seed: u64) -> Network {
+        Network::new_with_rng(layer_specs, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn new_with_rng<R: Rng + ?Sized>(
+        layer_specs: Vec<(usize, usize, ActivationFunction)>,
+        rng: &mut R,
+    ) -> Network {
         let layers = layer_specs.into_iter()
-            .map(|(size, input_size, activation)| Layer::new(size, input_size, activation))
+            .map(|(size, input_size, activation)| {
+                Box::new(Layer::new_with_rng(size, input_size, activation, rng)) as Box<dyn LayerOps>
+            })
             .collect();
         Network { layers, metadata: None }
     }
@@ -23,37 +48,483 @@ impl Network {
     pub fn forward(&mut self, input: Vec<f64>) -> Vec<f64> {
         let mut current = input;
         for layer in &mut self.layers {
-            current = layer.feed_from(current);
+            current = layer.forward(&current);
+        }
+        current
+    }
+
+    /// Inference-only forward pass through `&self`; unlike `forward` it does
+    /// not cache activations, so a shared (e.g. `Arc`) network can be used
+    /// from several threads.  See `InferenceSession` to reuse buffers across
+    /// calls.  Raw inputs are expanded by `polynomial_features` and scaled
+    /// by `input_scaling`, and a Softmax output is scaled by the calibrated
+    /// `temperature`.
+    pub fn predict(&self, input: &[f64]) -> Vec<f64> {
+        let mut current = input.to_vec();
+        self.prepare_input(&mut current);
+        let mut next = Vec::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            layer.predict_into(&current, &mut next, self.layer_temperature(i));
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+
+    /// Inference-only forward pass over a batch of inputs (see `predict`);
+    /// returns one output per input, in order.  Faster than calling
+    /// `predict` per sample once the batch has more than a few entries.
+    pub fn forward_batch(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let mut current = inputs.to_vec();
+        current.iter_mut().for_each(|x| self.prepare_input(x));
+        for (i, layer) in self.layers.iter().enumerate() {
+            current = layer.predict_batch(&current, self.layer_temperature(i));
+        }
+        current
+    }
+
+    /// Class probabilities for `input`: the output of a Softmax network as
+    /// is, and `[1 − p, p]` for a network ending in a single Sigmoid unit.
+    /// Other outputs are returned unchanged.
+    pub fn predict_proba(&self, input: &[f64]) -> Vec<f64> {
+        let output = self.predict(input);
+        let sigmoid = self.layers.last().and_then(|l| l.activation()) == Some(&ActivationFunction::Sigmoid);
+        match output[..] {
+            [p] if sigmoid => vec![1.0 - p, p],
+            _ => output,
+        }
+    }
+
+    /// Most likely class for `input`: the argmax of `predict_proba`, i.e.
+    /// 0/1 at a 0.5 threshold for a single Sigmoid output (as in
+    /// `confusion_matrix`).  Any other single output (Identity, Tanh, …) has
+    /// no class boundary and always yields 0.
+    pub fn predict_class(&self, input: &[f64]) -> usize {
+        argmax(&self.predict_proba(input))
+    }
+
+    /// `predict_class` mapped through `metadata.output_labels`; the class
+    /// index as a string when the network has no label for it.
+    pub fn predict_label(&self, input: &[f64]) -> String {
+        let class = self.predict_class(input);
+        self.metadata.as_ref()
+            .and_then(|m| m.output_labels.as_ref())
+            .and_then(|labels| labels.get(class))
+            .cloned()
+            .unwrap_or_else(|| class.to_string())
+    }
+
+    /// `forward_batch` over a `batch × input_size` matrix (one sample per
+    /// row), returning a `batch × output_size` matrix.  Each dense layer is
+    /// a single matrix product with the bias broadcast over the rows and
+    /// Softmax applied row-wise; results match `predict` exactly.
+    pub fn forward_batch_matrix(&self, inputs: &Matrix) -> Matrix {
+        let mut current = match self.polynomial_features() {
+            Some(expansion) => Matrix::from_data(inputs.row_iter().map(|row| expansion.transform(row)).collect()),
+            None => inputs.clone(),
+        };
+        if self.input_scaling().is_some() {
+            current.row_iter_mut().for_each(|row| self.scale_input(row));
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            current = layer.predict_matrix(&current, self.layer_temperature(i));
+        }
+        current
+    }
+
+    /// Pre-activation values of the output layer (the logits, for a Softmax
+    /// output) for `input`; used by temperature calibration.
+    pub fn logits(&self, input: &[f64]) -> Vec<f64> {
+        let mut current = input.to_vec();
+        self.prepare_input(&mut current);
+        let mut next = Vec::new();
+        let last = self.layers.len().saturating_sub(1);
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i < last {
+                layer.predict_into(&current, &mut next, 1.0);
+            } else {
+                layer.pre_activation_into(&current, &mut next);
+            }
+            std::mem::swap(&mut current, &mut next);
         }
         current
     }
 
+    /// Saliency of each input feature for `target_class`: the gradient of
+    /// that class's score with respect to `input`, backpropagated without
+    /// touching the weights.  The score is the logit for a Softmax output
+    /// (as in Simonyan et al.'s saliency maps), the output itself otherwise;
+    /// for a single-output network class 1 is `p` and class 0 is `1 − p`,
+    /// matching `predict_proba`.  Take absolute values for a heatmap.
+    ///
+    /// Like `predict`, takes a raw input: with `polynomial_features` and
+    /// `input_scaling` the gradient is with respect to the raw features.
+    /// Runs `forward`, so the layers' cached activations are overwritten.
+    ///
+    /// # Panics
+    /// Panics if `input` has the wrong length or `target_class` is not an
+    /// output of the network.
+    pub fn input_gradient(&mut self, input: &[f64], target_class: usize) -> Vec<f64> {
+        let raw = input;
+        let mut input = input.to_vec();
+        self.prepare_input(&mut input);
+        let output = self.forward(input.clone());
+        let mut delta = vec![0.0; output.len()];
+        match output.len() {
+            1 if target_class < 2 => delta[0] = if target_class == 1 { 1.0 } else { -1.0 },
+            n if target_class < n => delta[target_class] = 1.0,
+            n => panic!("target class {} out of range for {} outputs", target_class, n.max(2)),
+        }
+
+        for i in (0..self.layers.len()).rev() {
+            let layer_input = if i == 0 { &input } else { self.layers[i - 1].output() };
+            delta = self.layers[i].backward(layer_input, &delta, true)
+                .input_delta
+                .expect("input delta requested");
+        }
+        if let Some(scaling) = self.input_scaling() {
+            scaling.chain_gradient(&mut delta);
+        }
+        if let Some(expansion) = self.polynomial_features() {
+            delta = expansion.chain_gradient(raw, &delta);
+        }
+        delta
+    }
+
+    /// `predict` with the Softmax output restricted to the entries whose
+    /// `allowed` flag is `true` (the others get probability 0), e.g. the
+    /// valid positions of a shorter sequence or a subset of classes.  Applied
+    /// on top of the output layer's own `output_mask`, if any.
+    ///
+    /// # Panics
+    /// Panics if the output layer is not a dense Softmax layer, or if
+    /// `allowed` has the wrong length or allows no output.
+    pub fn predict_masked(&self, input: &[f64], allowed: &[bool]) -> Vec<f64> {
+        let output = self.layers.last()
+            .and_then(|layer| layer.as_dense())
+            .filter(|layer| layer.activator == ActivationFunction::Softmax)
+            .expect("predict_masked requires a dense Softmax output layer");
+        check_mask(allowed, output.size);
+        let allowed: Vec<bool> = match &output.output_mask {
+            Some(mask) => allowed.iter().zip(mask).map(|(&a, &m)| a && m).collect(),
+            None => allowed.to_vec(),
+        };
+        check_mask(&allowed, output.size);
+        let temperature = self.temperature();
+        let mut logits = self.logits(input);
+        logits.iter_mut().for_each(|v| *v /= temperature);
+        masked_softmax_in_place(&mut logits, &allowed);
+        logits
+    }
+
+    /// Softmax temperature applied by the inference paths (`predict`,
+    /// `forward_batch`, `InferenceSession`): the calibrated value stored in
+    /// the metadata, or 1.0.  Training (`forward`) never applies it.
+    pub fn temperature(&self) -> f64 {
+        self.metadata.as_ref().and_then(|m| m.temperature).unwrap_or(1.0)
+    }
+
+    /// Per-feature scaling applied to raw inputs by the inference paths
+    /// (`predict`, `forward_batch`, `logits`, `InferenceSession`), as fitted
+    /// during training and stored in the metadata.  `forward` is the training
+    /// pass and expects inputs that are already scaled.
+    pub fn input_scaling(&self) -> Option<&Scaling> {
+        self.metadata.as_ref().and_then(|m| m.input_scaling.as_ref())
+    }
+
+    /// Records `scaling` (e.g. a `StandardScaler` fitted by hand) in the
+    /// metadata, so the inference paths apply it to raw inputs; see
+    /// `input_scaling`.
+    pub fn set_input_scaling(&mut self, scaling: impl Into<Scaling>) {
+        self.metadata.get_or_insert_with(Default::default).input_scaling = Some(scaling.into());
+    }
+
+    /// Degree-2 feature expansion applied to raw inputs by the inference
+    /// paths before `input_scaling`, as recorded by
+    /// `TrainConfig::polynomial_features`.  `forward` expects inputs that
+    /// are already expanded.
+    pub fn polynomial_features(&self) -> Option<&PolynomialFeatures> {
+        self.metadata.as_ref().and_then(|m| m.polynomial_features.as_ref())
+    }
+
+    /// Records `expansion` in the metadata, so the inference paths apply it
+    /// to raw inputs; see `polynomial_features`.
+    pub fn set_polynomial_features(&mut self, expansion: PolynomialFeatures) {
+        self.metadata.get_or_insert_with(Default::default).polynomial_features = Some(expansion);
+    }
+
+    /// Number of raw values the inference paths take: the features before
+    /// `polynomial_features` expansion if the network has one, else the
+    /// first layer's input size (0 for a network without layers).
+    pub fn input_size(&self) -> usize {
+        match self.polynomial_features() {
+            Some(expansion) => expansion.n_features,
+            None => self.layers.first().map_or(0, |l| l.input_size()),
+        }
+    }
+
+    /// Applies `polynomial_features` and then `input_scaling`, if any, to a
+    /// raw `input` in place.
+    pub(crate) fn prepare_input(&self, input: &mut Vec<f64>) {
+        if let Some(expansion) = self.polynomial_features() {
+            *input = expansion.transform(input);
+        }
+        self.scale_input(input);
+    }
+
+    /// Applies `input_scaling`, if any, to `input` in place.
+    pub(crate) fn scale_input(&self, input: &mut [f64]) {
+        if let Some(scaling) = self.input_scaling() {
+            scaling.apply_in_place(input);
+        }
+    }
+
+    /// Temperature for layer `index` — only the output layer is scaled.
+    pub(crate) fn layer_temperature(&self, index: usize) -> f64 {
+        if index + 1 == self.layers.len() { self.temperature() } else { 1.0 }
+    }
+
     /// Serializes the network weights to a pretty-printed JSON file.
     pub fn save_json(&self, path: &str) -> std::io::Result<()> {
         let file = std::fs::File::create(path)?;
         let writer = std::io::BufWriter::new(file);
         serde_json::to_writer_pretty(writer, self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
     }
 
-    /// Deserializes a network from a JSON file previously written by `save_json`.
+    /// Deserializes a network from a JSON file previously written by
+    /// `save_json`.  Files from older versions are migrated to the current
+    /// format first; unreadable files yield an error naming the problem
+    /// (e.g. the layer that failed to parse).
     pub fn load_json(path: &str) -> std::io::Result<Network> {
         let file = std::fs::File::open(path)?;
         let reader = std::io::BufReader::new(file);
         serde_json::from_reader(reader)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
+    }
+
+    /// Copies the weights and biases of the layers at `layer_indices` from the
+    /// model saved at `path` into this network, leaving every other layer as
+    /// it is.  Used to warm-start a new architecture from a trained model,
+    /// e.g. reusing the hidden layers of a classifier under a new output layer.
+    ///
+    /// Nothing is modified unless every requested layer exists in both
+    /// networks with the same shape; otherwise an `InvalidInput` error
+    /// names the first offending layer.
+    pub fn load_weights_partial(&mut self, path: &str, layer_indices: &[usize]) -> std::io::Result<()> {
+        let source  = Network::load_json(path)?;
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+
+        for &i in layer_indices {
+            let (Some(dst), Some(src)) = (self.layers.get(i), source.layers.get(i)) else {
+                return Err(invalid(format!(
+                    "layer {} does not exist in both networks ({} vs {} layers)",
+                    i, self.layers.len(), source.layers.len()
+                )));
+            };
+            let (dst_w, src_w) = (dst.params().0, src.params().0);
+            if dst.kind() != src.kind() || (dst_w.rows, dst_w.cols) != (src_w.rows, src_w.cols) {
+                return Err(invalid(format!(
+                    "{} shape mismatch: {} {}×{} here, {} {}×{} in '{}'",
+                    dst.label(i), dst.kind(), dst_w.rows, dst_w.cols,
+                    src.kind(), src_w.rows, src_w.cols, path
+                )));
+            }
+        }
+
+        for &i in layer_indices {
+            let (weights, biases) = source.layers[i].params();
+            let (dst_weights, dst_biases) = self.layers[i].params_mut();
+            *dst_weights = weights.clone();
+            *dst_biases  = biases.clone();
+        }
+        Ok(())
+    }
+
+    /// Like `load_weights_partial`, selecting layers by name.  Names are
+    /// resolved in this network; the source layer at the same position is
+    /// copied.  Unknown names yield an `InvalidInput` error.
+    pub fn load_weights_partial_by_name(&mut self, path: &str, names: &[&str]) -> std::io::Result<()> {
+        let indices = names.iter()
+            .map(|name| self.layer_index(name).ok_or_else(|| std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("no layer named '{}'", name),
+            )))
+            .collect::<std::io::Result<Vec<usize>>>()?;
+        self.load_weights_partial(path, &indices)
+    }
+
+    /// Warm-starts a modified architecture from the model saved at `path`:
+    /// every layer whose kind and parameter shapes match the source layer
+    /// at the same position gets its weights and biases; every other dense
+    /// layer is re-initialized (`WeightInit::for_activation`, zero biases).
+    /// Unmatched layers of other kinds (normalization, recurrent, …) are not
+    /// re-initialized: they keep their current parameters.  Returns the
+    /// indices of the copied layers.
+    ///
+    /// With `strict`, every layer must match (and the layer counts agree);
+    /// otherwise an `InvalidInput` error names the first mismatch and
+    /// nothing is modified.
+    pub fn load_weights_from(&mut self, path: &str, strict: bool) -> std::io::Result<Vec<usize>> {
+        self.load_weights_from_with_rng(path, strict, &mut rand::thread_rng())
+    }
+
+    /// `load_weights_from` with the re-initialized layers drawn from `seed`;
+    /// pass the run's `TrainConfig::seed` to make a warm-started training
+    /// run reproducible.
+    pub fn load_weights_from_seeded(&mut self, path: &str, strict: bool, seed: u64) -> std::io::Result<Vec<usize>> {
+        self.load_weights_from_with_rng(path, strict, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn load_weights_from_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        path: &str,
+        strict: bool,
+        rng: &mut R,
+    ) -> std::io::Result<Vec<usize>> {
+        let source  = Network::load_json(path)?;
+        let matches = |i: usize| {
+            source.layers.get(i).is_some_and(|src| {
+                let (dst, (src_w, src_b)) = (&self.layers[i], src.params());
+                let (dst_w, dst_b) = dst.params();
+                dst.kind() == src.kind()
+                    && (dst_w.rows, dst_w.cols, dst_b.rows, dst_b.cols) == (src_w.rows, src_w.cols, src_b.rows, src_b.cols)
+            })
+        };
+        let matched: Vec<usize> = (0..self.layers.len()).filter(|&i| matches(i)).collect();
+
+        if strict && (matched.len() != self.layers.len() || source.layers.len() != self.layers.len()) {
+            let msg = match (0..self.layers.len()).find(|&i| !matches(i)) {
+                Some(i) => format!("{} has no layer of the same kind and shape at its position in '{}'", self.layers[i].label(i), path),
+                None => format!("'{}' has {} layers, this network {}", path, source.layers.len(), self.layers.len()),
+            };
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, msg));
+        }
+
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            if matched.contains(&i) {
+                let (weights, biases) = source.layers[i].params();
+                let (dst_weights, dst_biases) = layer.params_mut();
+                *dst_weights = weights.clone();
+                *dst_biases  = biases.clone();
+            } else if let Some(dense) = layer.as_dense_mut() {
+                let init = WeightInit::for_activation(&dense.activator);
+                dense.weights = init.weights(dense.weights.rows, dense.size, rng);
+                dense.biases = Matrix::zeros(1, dense.size);
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Position of the layer called `name`, if any.
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|l| l.name() == Some(name))
+    }
+
+    /// Transfer learning: swaps the output layer for a freshly initialized
+    /// dense layer of `new_size` units, keeping every hidden layer's weights.
+    /// The new head reads the old head's input and inherits its name and L2
+    /// penalty; its weights follow `WeightInit::for_activation`.
+    ///
+    /// The metadata that described the old outputs — `output_labels`, the
+    /// calibrated `temperature` and recorded `metrics` — is cleared; set new
+    /// `output_labels` before saving a classifier.
+    ///
+    /// # Panics
+    /// Panics if the network has no layers.
+    pub fn replace_head(&mut self, new_size: usize, activation: ActivationFunction) {
+        self.replace_head_with_rng(new_size, activation, &mut rand::thread_rng());
+    }
+
+    /// `replace_head` with the new layer initialized from `seed`.
+    pub fn replace_head_seeded(&mut self, new_size: usize, activation: ActivationFunction, seed: u64) {
+        self.replace_head_with_rng(new_size, activation, &mut StdRng::seed_from_u64(seed));
+    }
+
+    fn replace_head_with_rng<R: Rng + ?Sized>(&mut self, new_size: usize, activation: ActivationFunction, rng: &mut R) {
+        let old = self.layers.pop().expect("cannot replace the head of an empty network");
+        let mut head = Layer::new_with_rng(new_size, old.input_size(), activation, rng);
+        head.name = old.name().map(str::to_owned);
+        head.l2 = old.l2();
+        self.layers.push(Box::new(head));
+        self.clear_output_metadata();
+    }
+
+    /// Drops the metadata tied to the current outputs (labels, calibrated
+    /// temperature, metrics) after the output layer changes.
+    pub(crate) fn clear_output_metadata(&mut self) {
+        if let Some(meta) = self.metadata.as_mut() {
+            meta.output_labels = None;
+            meta.temperature = None;
+            meta.metrics = None;
+        }
+    }
+
+    /// Plain-text table of the layers: name, shape, activation and parameter
+    /// count, followed by the total parameter count.
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{:<4}  {:<16}  {:>12}  {:<18}  {:>10}\n",
+            "#", "Name", "Shape", "Activation", "Params"
+        );
+        let mut total = 0usize;
+        for (i, layer) in self.layers.iter().enumerate() {
+            let params = layer.param_count();
+            total += params;
+            out.push_str(&format!(
+                "{:<4}  {:<16}  {:>12}  {:<18}  {:>10}\n",
+                i,
+                layer.name().unwrap_or("—"),
+                format!("{} → {}", layer.input_size(), layer.output_size()),
+                layer.activation().map(|a| format!("{:?}", a)).unwrap_or_else(|| layer.kind().to_owned()),
+                params,
+            ));
+        }
+        out.push_str(&format!("Total parameters: {}\n", total));
+        out
     }
 
     /// Builds a fresh (randomly initialized) `Network` from a `NetworkSpec`.
     ///
-    /// Weight initialization follows `Layer::new` conventions:
+    /// Each layer's weights use its spec's `init` scheme, or `Layer::new`
+    /// conventions when unset:
     /// - ReLU activations → He init
     /// - everything else  → Xavier init
     ///
     /// Metadata is copied from the spec if present.
+    ///
+    /// # Panics
+    /// Panics if `spec.validate()` fails; use `try_from_spec` to get the
+    /// errors instead.
     pub fn from_spec(spec: &NetworkSpec) -> Network {
+        expect_valid(spec);
+        Network::from_spec_with_rng(spec, &mut rand::thread_rng())
+    }
+
+    /// `from_spec` that returns the spec's validation errors instead of
+    /// panicking.
+    pub fn try_from_spec(spec: &NetworkSpec) -> Result<Network, Vec<SpecError>> {
+        spec.validate()?;
+        Ok(Network::from_spec_with_rng(spec, &mut rand::thread_rng()))
+    }
+
+    /// `from_spec` with weights initialized from `seed` (see `new_seeded`).
+    ///
+    /// # Panics
+    /// Panics if `spec.validate()` fails.
+    pub fn from_spec_seeded(spec: &NetworkSpec, seed: u64) -> Network {
+        expect_valid(spec);
+        Network::from_spec_with_rng(spec, &mut StdRng::seed_from_u64(seed))
+    }
+
+    fn from_spec_with_rng<R: Rng + ?Sized>(spec: &NetworkSpec, rng: &mut R) -> Network {
         let layers = spec.layers.iter()
-            .map(|ls| Layer::new(ls.size, ls.input_size, ls.activation.clone()))
+            .map(|ls| {
+                let init = ls.init.unwrap_or_else(|| WeightInit::for_activation(&ls.activation));
+                let mut layer = Layer::new_with_init(ls.size, ls.input_size, ls.activation.clone(), init, rng);
+                layer.name = ls.name.clone();
+                layer.l2 = ls.l2;
+                Box::new(layer) as Box<dyn LayerOps>
+            })
             .collect();
         Network {
             layers,
@@ -61,3 +532,11 @@ impl Network {
         }
     }
 }
+
+/// Panics with every validation message if `spec` is invalid.
+fn expect_valid(spec: &NetworkSpec) {
+    if let Err(errors) = spec.validate() {
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        panic!("invalid network spec: {}", messages.join("; "));
+    }
+}
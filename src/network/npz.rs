@@ -0,0 +1,367 @@
+//! NumPy `.npz` interchange for network parameters.
+//!
+//! An `.npz` file is a zip archive of `.npy` arrays.  `export_npz` writes
+//! one `float64` array per parameter matrix, named `{key}.weights` and
+//! `{key}.biases`, where `key` is the layer's name or `layer{index}` for
+//! unnamed layers.  Shapes are the `Matrix` shapes: weights are
+//! `fan_in × fan_out` (transpose them for a PyTorch `nn.Linear`) and biases
+//! `1 × n`.  Layers without parameters (pooling) are skipped.
+//!
+//! `export_npz_f32` stores the same arrays as `float32`, halving the file
+//! for inference-only deployment; `import_npz` reads either.
+//!
+//! Only uncompressed archives are read (`np.savez`, not
+//! `np.savez_compressed`).
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+
+impl Network {
+    /// Writes every layer's weights and biases to `path` as an `.npz`
+    /// archive readable with `np.load`.
+    pub fn export_npz(&self, path: &str) -> Result<()> {
+        self.write_npz(path, false)
+    }
+
+    /// Like `export_npz`, with every array stored as `float32` — half the
+    /// size, at single precision.  Weights read back with `import_npz` are
+    /// the `f32` values widened to `f64`.
+    pub fn export_npz_f32(&self, path: &str) -> Result<()> {
+        self.write_npz(path, true)
+    }
+
+    fn write_npz(&self, path: &str, single: bool) -> Result<()> {
+        let mut zip = ZipWriter::default();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let (weights, biases) = layer.params();
+            if weights.data.is_empty() && biases.data.is_empty() {
+                continue;
+            }
+            let key = array_key(layer.name(), i);
+            zip.add(&format!("{}.weights.npy", key), &encode_npy(weights, single));
+            zip.add(&format!("{}.biases.npy", key), &encode_npy(biases, single));
+        }
+        std::fs::write(path, zip.finish()?)
+    }
+
+    /// Replaces every layer's weights and biases with the arrays of the same
+    /// names in the `.npz` archive at `path` (see `export_npz` for the
+    /// naming).  Arrays may be `float64` or `float32`, in C or Fortran order;
+    /// a 1-D array is accepted for a single-row matrix.  Extra arrays are
+    /// ignored.
+    ///
+    /// Nothing is modified unless every layer's arrays are present with the
+    /// right shapes; otherwise an `InvalidData` error names the first
+    /// missing or mismatched array.
+    pub fn import_npz(&mut self, path: &str) -> Result<()> {
+        let arrays = read_zip(&std::fs::read(path)?)?;
+        let mut params = Vec::with_capacity(self.layers.len());
+        for (i, layer) in self.layers.iter().enumerate() {
+            let (weights, biases) = layer.params();
+            if weights.data.is_empty() && biases.data.is_empty() {
+                params.push(None);
+                continue;
+            }
+            let key = array_key(layer.name(), i);
+            let load = |suffix: &str, like: &Matrix| -> Result<Matrix> {
+                let name = format!("{}.{}", key, suffix);
+                let bytes = arrays.get(&format!("{}.npy", name))
+                    .or_else(|| arrays.get(&name))
+                    .ok_or_else(|| invalid(format!("array '{}' is missing", name)))?;
+                let (shape, data) = decode_npy(bytes).map_err(|e| invalid(format!("array '{}': {}", name, e)))?;
+                let fits = match shape[..] {
+                    [rows, cols] => (rows, cols) == (like.rows, like.cols),
+                    [n] => like.rows == 1 && n == like.cols,
+                    _ => false,
+                };
+                if !fits {
+                    return Err(invalid(format!(
+                        "array '{}' has shape {:?} but {} expects {}×{}",
+                        name, shape, layer.label(i), like.rows, like.cols
+                    )));
+                }
+                Ok(Matrix::from_flat(like.rows, like.cols, data))
+            };
+            params.push(Some((load("weights", weights)?, load("biases", biases)?)));
+        }
+
+        for (layer, loaded) in self.layers.iter_mut().zip(params) {
+            if let Some((weights, biases)) = loaded {
+                let (dst_weights, dst_biases) = layer.params_mut();
+                *dst_weights = weights;
+                *dst_biases = biases;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn array_key(name: Option<&str>, index: usize) -> String {
+    name.map(str::to_owned).unwrap_or_else(|| format!("layer{}", index))
+}
+
+fn invalid(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+// ---------------------------------------------------------------------------
+// .npy
+// ---------------------------------------------------------------------------
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Encodes `m` as a version 1.0 `.npy` array of little-endian `float64`, or
+/// `float32` when `single` is set.
+fn encode_npy(m: &Matrix, single: bool) -> Vec<u8> {
+    let descr = if single { "<f4" } else { "<f8" };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}), }}", descr, m.rows, m.cols);
+    // Pad with spaces so the data starts on a 64-byte boundary, as NumPy does.
+    let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let width = if single { 4 } else { 8 };
+    let mut out = Vec::with_capacity(NPY_MAGIC.len() + 4 + header.len() + m.data.len() * width);
+    out.extend_from_slice(NPY_MAGIC);
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    for &v in &m.data {
+        if single {
+            out.extend_from_slice(&(v as f32).to_le_bytes());
+        } else {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    out
+}
+
+/// Decodes a `.npy` array of `float64` or `float32` into its shape and
+/// row-major values.
+fn decode_npy(bytes: &[u8]) -> std::result::Result<(Vec<usize>, Vec<f64>), String> {
+    if !bytes.starts_with(NPY_MAGIC) || bytes.len() < 10 {
+        return Err("not a .npy array".into());
+    }
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+        v => return Err(format!("unsupported .npy version {}", v)),
+    };
+    let header = bytes.get(header_start..header_start + header_len)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or("truncated .npy header")?;
+    let data = &bytes[header_start + header_len..];
+
+    let descr = header_value(header, "descr").ok_or("missing 'descr'")?;
+    let width = match descr.trim_matches(|c| c == '\'' || c == '"') {
+        "<f8" => 8,
+        "<f4" => 4,
+        other => return Err(format!("unsupported dtype {} (expected float64 or float32)", other)),
+    };
+    let fortran = header_value(header, "fortran_order").ok_or("missing 'fortran_order'")?.starts_with("True");
+    let shape_text = header_value(header, "shape").ok_or("missing 'shape'")?;
+    let shape = shape_text
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>().map_err(|_| format!("bad shape {}", shape_text)))
+        .collect::<std::result::Result<Vec<usize>, String>>()?;
+
+    let count: usize = shape.iter().product();
+    if data.len() < count * width {
+        return Err(format!("expected {} values, found {} bytes", count, data.len()));
+    }
+    let mut values: Vec<f64> = data[..count * width]
+        .chunks_exact(width)
+        .map(|c| match width {
+            8 => f64::from_le_bytes(c.try_into().expect("8-byte chunk")),
+            _ => f32::from_le_bytes(c.try_into().expect("4-byte chunk")) as f64,
+        })
+        .collect();
+    if fortran && shape.len() == 2 {
+        let (rows, cols) = (shape[0], shape[1]);
+        values = (0..rows * cols).map(|k| values[(k % cols) * rows + k / cols]).collect();
+    }
+    Ok((shape, values))
+}
+
+/// The raw text of `key`'s value in a `.npy` header dict, e.g. `(3, 4)` for
+/// `'shape'`.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if rest.starts_with('(') { rest.find(')')? + 1 } else { rest.find(',')? };
+    Some(rest[..end].trim())
+}
+
+// ---------------------------------------------------------------------------
+// Zip (stored entries only)
+// ---------------------------------------------------------------------------
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR: u32 = 0x0605_4b50;
+/// 1980-01-01, the earliest date a zip entry can carry.
+const DOS_DATE: u16 = 0x0021;
+
+#[derive(Default)]
+struct ZipWriter {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: usize,
+}
+
+impl ZipWriter {
+    fn add(&mut self, name: &str, data: &[u8]) {
+        let offset = self.out.len() as u32;
+        let crc = crc32(data);
+        let fields = |buf: &mut Vec<u8>| {
+            buf.extend_from_slice(&20u16.to_le_bytes()); // version needed: 2.0
+            buf.extend_from_slice(&0u16.to_le_bytes()); // flags
+            buf.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+            buf.extend_from_slice(&0u16.to_le_bytes()); // time
+            buf.extend_from_slice(&DOS_DATE.to_le_bytes());
+            buf.extend_from_slice(&crc.to_le_bytes());
+            buf.extend_from_slice(&(data.len() as u32).to_le_bytes()); // compressed
+            buf.extend_from_slice(&(data.len() as u32).to_le_bytes()); // uncompressed
+            buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            buf.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        };
+
+        self.out.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        fields(&mut self.out);
+        self.out.extend_from_slice(name.as_bytes());
+        self.out.extend_from_slice(data);
+
+        self.central.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        fields(&mut self.central);
+        self.central.extend_from_slice(&[0; 6]); // comment length, disk, internal attrs
+        self.central.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>> {
+        if self.out.len() + self.central.len() > u32::MAX as usize || self.entries > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "network too large for a non-zip64 .npz archive"));
+        }
+        let central_offset = self.out.len() as u32;
+        let central_len = self.central.len() as u32;
+        self.out.append(&mut self.central);
+        self.out.extend_from_slice(&END_OF_CENTRAL_DIR.to_le_bytes());
+        self.out.extend_from_slice(&[0; 4]); // disk numbers
+        self.out.extend_from_slice(&(self.entries as u16).to_le_bytes());
+        self.out.extend_from_slice(&(self.entries as u16).to_le_bytes());
+        self.out.extend_from_slice(&central_len.to_le_bytes());
+        self.out.extend_from_slice(&central_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        Ok(self.out)
+    }
+}
+
+fn u16_at(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u64_at(b: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// Reads every entry of a zip archive of stored (uncompressed) files,
+/// following zip64 extra fields where sizes or offsets overflow 32 bits.
+fn read_zip(bytes: &[u8]) -> Result<HashMap<String, Vec<u8>>> {
+    let corrupt = || invalid("not a valid .npz (zip) archive".into());
+    // The end-of-central-directory record is the last 22 bytes plus an
+    // optional comment of up to 64 KiB.
+    let eocd = (0..=bytes.len().saturating_sub(22))
+        .rev()
+        .take(22 + u16::MAX as usize)
+        .find(|&at| u32_at(bytes, at) == Some(END_OF_CENTRAL_DIR))
+        .ok_or_else(corrupt)?;
+    let entries = u16_at(bytes, eocd + 10).ok_or_else(corrupt)? as usize;
+    let mut at = u32_at(bytes, eocd + 16).ok_or_else(corrupt)? as usize;
+
+    let mut files = HashMap::with_capacity(entries);
+    for _ in 0..entries {
+        if u32_at(bytes, at) != Some(CENTRAL_HEADER) {
+            return Err(corrupt());
+        }
+        let method = u16_at(bytes, at + 10).ok_or_else(corrupt)?;
+        let crc = u32_at(bytes, at + 16).ok_or_else(corrupt)?;
+        let mut size = u32_at(bytes, at + 24).ok_or_else(corrupt)? as u64;
+        let name_len = u16_at(bytes, at + 28).ok_or_else(corrupt)? as usize;
+        let extra_len = u16_at(bytes, at + 30).ok_or_else(corrupt)? as usize;
+        let comment_len = u16_at(bytes, at + 32).ok_or_else(corrupt)? as usize;
+        let mut offset = u32_at(bytes, at + 42).ok_or_else(corrupt)? as u64;
+        let name = bytes.get(at + 46..at + 46 + name_len)
+            .map(|n| String::from_utf8_lossy(n).into_owned())
+            .ok_or_else(corrupt)?;
+
+        // Zip64 extra field (id 1): 8-byte values for each 32-bit field that
+        // is saturated, in the order uncompressed size, compressed size,
+        // offset.
+        let extra = bytes.get(at + 46 + name_len..at + 46 + name_len + extra_len).ok_or_else(corrupt)?;
+        let mut e = 0;
+        while e + 4 <= extra.len() {
+            let (id, len) = (u16_at(extra, e).unwrap_or(0), u16_at(extra, e + 2).unwrap_or(0) as usize);
+            if id == 1 {
+                let mut field = e + 4;
+                if size == u32::MAX as u64 {
+                    size = u64_at(extra, field).ok_or_else(corrupt)?;
+                    field += 8;
+                }
+                if u32_at(bytes, at + 20) == Some(u32::MAX) {
+                    // Compressed size: the same as `size` for stored entries.
+                    field += 8;
+                }
+                if offset == u32::MAX as u64 {
+                    offset = u64_at(extra, field).ok_or_else(corrupt)?;
+                }
+            }
+            e += 4 + len;
+        }
+
+        if method != 0 {
+            return Err(invalid(format!(
+                "entry '{}' is compressed; only uncompressed archives (np.savez) are supported",
+                name
+            )));
+        }
+        let local = offset as usize;
+        if u32_at(bytes, local) != Some(LOCAL_HEADER) {
+            return Err(corrupt());
+        }
+        let data_start = local + 30
+            + u16_at(bytes, local + 26).ok_or_else(corrupt)? as usize
+            + u16_at(bytes, local + 28).ok_or_else(corrupt)? as usize;
+        let data = bytes.get(data_start..data_start + size as usize).ok_or_else(corrupt)?;
+        if crc32(data) != crc {
+            return Err(invalid(format!("entry '{}' fails its CRC check", name)));
+        }
+        files.insert(name, data.to_vec());
+        at += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(files)
+}
+
+/// CRC-32 (IEEE 802.3), as used by zip.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
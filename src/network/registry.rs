@@ -0,0 +1,177 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::network::metadata::ModelMetadata;
+use crate::network::network::Network;
+
+/// Directory the Studio, examples and CLI save models to by default.
+pub const DEFAULT_MODEL_DIR: &str = "trained_models";
+
+/// One saved model found by `ModelRegistry`.
+#[derive(Debug, Clone)]
+pub struct ModelEntry {
+    /// File stem, e.g. `"mnist"` for `trained_models/mnist.json`.
+    pub name: String,
+    pub path: PathBuf,
+    /// File size in bytes.
+    pub size_bytes: u64,
+    /// Creation time, or the last modification time on filesystems that do
+    /// not record creation.
+    pub created: Option<SystemTime>,
+    /// Metadata stored in the model file (includes recorded metrics), if any.
+    /// `None` as well when the file could not be parsed as a model.
+    pub metadata: Option<ModelMetadata>,
+}
+
+/// Index of the `*.json` models in a directory.
+///
+/// The directory is scanned once by `open` (and again by `refresh`); entries
+/// are sorted by name.  `rename` and `delete` act on the files and keep the
+/// index in sync.  A missing directory is treated as empty.
+#[derive(Debug, Clone)]
+pub struct ModelRegistry {
+    dir: PathBuf,
+    entries: Vec<ModelEntry>,
+}
+
+impl ModelRegistry {
+    /// Scans `dir` for saved models.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<ModelRegistry> {
+        let mut registry = ModelRegistry { dir: dir.as_ref().to_path_buf(), entries: Vec::new() };
+        registry.refresh()?;
+        Ok(registry)
+    }
+
+    /// Re-scans the directory, picking up models written since `open`.
+    pub fn refresh(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(rd) => rd,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_owned()) else {
+                continue;
+            };
+            self.entries.push(scan_entry(name, path));
+        }
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// All models, sorted by name.
+    pub fn entries(&self) -> &[ModelEntry] {
+        &self.entries
+    }
+
+    /// Model names, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.entries.iter().map(|e| e.name.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ModelEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Models whose recorded fingerprint is `fingerprint` (see
+    /// `Network::fingerprint`), e.g. to spot a duplicate before saving.
+    pub fn find_by_fingerprint<'a>(&'a self, fingerprint: &'a str) -> impl Iterator<Item = &'a ModelEntry> + 'a {
+        self.entries.iter().filter(move |e| {
+            e.metadata.as_ref().and_then(|m| m.fingerprint.as_deref()) == Some(fingerprint)
+        })
+    }
+
+    /// Path a model called `name` is (or would be) stored at.
+    ///
+    /// Returns an `InvalidInput` error for names that are empty or contain
+    /// path separators or `..`.
+    pub fn path_for(&self, name: &str) -> io::Result<PathBuf> {
+        validate_name(name)?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+
+    /// Loads the named model.
+    pub fn load(&self, name: &str) -> io::Result<Network> {
+        let entry = self.get(name).ok_or_else(|| not_found(name))?;
+        Network::load_json(&entry.path.to_string_lossy())
+    }
+
+    /// Renames a model file.  Fails with `AlreadyExists` if `to` is taken.
+    pub fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        let new_path = self.path_for(to)?;
+        if self.get(to).is_some() || new_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("a model named '{}' already exists", to),
+            ));
+        }
+        let index = self.position(from)?;
+        std::fs::rename(&self.entries[index].path, &new_path)?;
+
+        let entry = &mut self.entries[index];
+        entry.name = to.to_owned();
+        entry.path = new_path;
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    /// Deletes a model file.
+    pub fn delete(&mut self, name: &str) -> io::Result<()> {
+        let index = self.position(name)?;
+        std::fs::remove_file(&self.entries[index].path)?;
+        self.entries.remove(index);
+        Ok(())
+    }
+
+    fn position(&self, name: &str) -> io::Result<usize> {
+        self.entries.iter().position(|e| e.name == name).ok_or_else(|| not_found(name))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+/// Only the metadata is needed for the index; the weights are skipped by
+/// serde as an unknown field.
+#[derive(Deserialize)]
+struct MetadataOnly {
+    #[serde(default)]
+    metadata: Option<ModelMetadata>,
+}
+
+fn scan_entry(name: String, path: PathBuf) -> ModelEntry {
+    let fs_meta = std::fs::metadata(&path).ok();
+    let size_bytes = fs_meta.as_ref().map(|m| m.len()).unwrap_or(0);
+    let created = fs_meta.as_ref().and_then(|m| m.created().or_else(|_| m.modified()).ok());
+    let metadata = std::fs::File::open(&path).ok()
+        .and_then(|f| serde_json::from_reader::<_, MetadataOnly>(io::BufReader::new(f)).ok())
+        .and_then(|m| m.metadata);
+    ModelEntry { name, path, size_bytes, created, metadata }
+}
+
+pub(crate) fn validate_name(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid model name '{}'", name),
+        ));
+    }
+    Ok(())
+}
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("no model named '{}'", name))
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use crate::network::network::Network;
+
+/// A cheap per-thread handle for running inference on a shared model.
+///
+/// The weights live behind an `Arc<Network>` and are never mutated; each
+/// session only owns two scratch buffers that are reused between calls, so
+/// after the first call inference does not allocate.  To serve concurrent
+/// requests, load the model once and give every thread (or request) its own
+/// session — cloning a session copies the `Arc`, not the weights.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use ferrite_nn::{Network, InferenceSession};
+///
+/// let model = Arc::new(Network::load_json("trained_models/mnist.json").unwrap());
+/// let handles: Vec<_> = (0..4).map(|_| {
+///     let mut session = InferenceSession::new(model.clone());
+///     std::thread::spawn(move || session.run(&[0.0; 784]).to_vec())
+/// }).collect();
+/// ```
+#[derive(Clone)]
+pub struct InferenceSession {
+    network: Arc<Network>,
+    current: Vec<f64>,
+    next: Vec<f64>,
+}
+
+impl InferenceSession {
+    pub fn new(network: Arc<Network>) -> InferenceSession {
+        InferenceSession { network, current: Vec::new(), next: Vec::new() }
+    }
+
+    /// The shared model this session runs.
+    pub fn network(&self) -> &Arc<Network> {
+        &self.network
+    }
+
+    /// Runs a forward pass and returns the output layer's activations (with
+    /// the feature expansion, input scaling and calibrated temperature
+    /// applied, as in `Network::predict`).
+    /// The returned slice borrows the session's buffer and is overwritten by
+    /// the next call.
+    ///
+    /// # Panics
+    /// Panics if `input.len()` does not match the network's input size.
+    pub fn run(&mut self, input: &[f64]) -> &[f64] {
+        self.current.clear();
+        self.current.extend_from_slice(input);
+        self.network.prepare_input(&mut self.current);
+        for (i, layer) in self.network.layers.iter().enumerate() {
+            layer.predict_into(&self.current, &mut self.next, self.network.layer_temperature(i));
+            std::mem::swap(&mut self.current, &mut self.next);
+        }
+        &self.current
+    }
+}
+
+impl From<Network> for InferenceSession {
+    fn from(network: Network) -> Self {
+        InferenceSession::new(Arc::new(network))
+    }
+}
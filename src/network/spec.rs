@@ -1,21 +1,37 @@
 use serde::{Serialize, Deserialize};
 use crate::activation::activation::ActivationFunction;
+use crate::activation::registry::is_activation_registered;
 use crate::loss::loss_type::LossType;
+use crate::math::init::WeightInit;
 use crate::network::metadata::ModelMetadata;
+#[cfg(feature = "toml")]
+use crate::config::{parse_section, unknown_keys, ConfigError};
 
 /// Describes one layer in a network specification.
 ///
 /// Fields:
 /// - `size`       — number of neurons in this layer
 /// - `input_size` — number of neurons feeding into this layer (i.e. the output
-///                  size of the previous layer, or the raw input dimension for
-///                  the first layer)
+///   size of the previous layer, or the raw input dimension for
+///   the first layer)
 /// - `activation` — activation function applied after the linear transform
+/// - `name`       — optional human-readable name (e.g. `"hidden2"`), carried
+///   into the built `Layer` and used in error messages and summaries
+/// - `init`       — optional weight initialization scheme; when absent, He
+///   before ReLU and Xavier otherwise
+/// - `l2`         — optional L2 strength for this layer's weights, used by
+///   the training loop instead of `TrainConfig::l2` (0 exempts the layer)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerSpec {
     pub size: usize,
     pub input_size: usize,
     pub activation: ActivationFunction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init: Option<WeightInit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l2: Option<f64>,
 }
 
 /// A fully serializable description of a network architecture plus its
@@ -43,7 +59,7 @@ impl NetworkSpec {
         let file = std::fs::File::create(path)?;
         let writer = std::io::BufWriter::new(file);
         serde_json::to_writer_pretty(writer, self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
     }
 
     /// Deserializes a `NetworkSpec` from a JSON file.
@@ -51,6 +67,164 @@ impl NetworkSpec {
         let file = std::fs::File::open(path)?;
         let reader = std::io::BufReader::new(file);
         serde_json::from_reader(reader)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
+    }
+
+    /// Parses a spec from TOML — a standalone document or the `[network]`
+    /// table of an experiment file (see `config`) — and validates it.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(doc: &str) -> Result<NetworkSpec, ConfigError> {
+        let value = parse_section(doc, "network")?;
+        let mut unknown = unknown_keys(&value, &["name", "layers", "loss", "metadata"], "");
+        if let Some(toml::Value::Array(layers)) = value.get("layers") {
+            for (i, layer) in layers.iter().enumerate() {
+                let context = format!("layers[{}].", i);
+                unknown.extend(unknown_keys(layer, &["size", "input_size", "activation", "name", "init", "l2"], &context));
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(ConfigError::Invalid(unknown));
+        }
+        let spec: NetworkSpec = value.try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.to_string().trim_end().to_owned()))?;
+        spec.validate()
+            .map_err(|errors| ConfigError::Invalid(errors.iter().map(ToString::to_string).collect()))?;
+        Ok(spec)
+    }
+
+    /// Reads and parses a TOML file with `from_toml`.
+    #[cfg(feature = "toml")]
+    pub fn load_toml(path: &str) -> Result<NetworkSpec, ConfigError> {
+        NetworkSpec::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Cleans up cosmetic problems in place: trims the model name and
+    /// replaces every character other than ASCII letters, digits, `_` and
+    /// `-` with `_` (the name becomes a file stem), and trims layer names,
+    /// dropping empty ones.
+    pub fn normalize(&mut self) {
+        self.name = sanitize_name(self.name.trim());
+        for layer in &mut self.layers {
+            layer.name = layer.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_owned);
+        }
+    }
+
+    /// Checks that the spec describes a buildable, trainable network.
+    ///
+    /// Returns one error per problem: empty name or layer list, a name that
+    /// is not a safe file stem (see `normalize`), zero sizes, a layer whose
+    /// `input_size` differs from the previous layer's `size`, an
+    /// unregistered custom activation, an invalid init scheme or `l2`,
+    /// duplicate layer names, Softmax before the output layer, and an output
+    /// activation that does not fit the loss (`CrossEntropy` needs Softmax,
+    /// `BinaryCrossEntropy` needs Sigmoid).
+    pub fn validate(&self) -> Result<(), Vec<SpecError>> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push(SpecError::new(None, "name", "name must not be empty"));
+        } else if sanitize_name(&self.name) != self.name {
+            errors.push(SpecError::new(
+                None,
+                "name",
+                format!("name '{}' may only contain ASCII letters, digits, '_' and '-'", self.name),
+            ));
+        }
+        if self.layers.is_empty() {
+            errors.push(SpecError::new(None, "layers", "at least one layer is required"));
+        }
+
+        let mut seen_names: Vec<&str> = Vec::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let label = match &layer.name {
+                Some(name) => format!("layer '{}'", name),
+                None       => format!("layer {}", i),
+            };
+            let mut push = |field: &'static str, message: String| {
+                errors.push(SpecError::new(Some(i), field, format!("{}: {}", label, message)));
+            };
+            if layer.size == 0 {
+                push("size", "size must be at least 1".to_owned());
+            }
+            if layer.input_size == 0 {
+                push("input_size", "input_size must be at least 1".to_owned());
+            }
+            if i > 0 && layer.input_size != self.layers[i - 1].size {
+                push("input_size", format!(
+                    "input_size is {} but the previous layer has {} neurons",
+                    layer.input_size, self.layers[i - 1].size
+                ));
+            }
+            if layer.activation == ActivationFunction::Softmax && i + 1 != self.layers.len() {
+                push("activation", "Softmax is only supported on the output layer".to_owned());
+            }
+            if let ActivationFunction::Custom(name) = &layer.activation {
+                if !is_activation_registered(name) {
+                    push("activation", format!("custom activation '{}' is not registered", name));
+                }
+            }
+            if let Some(Err(e)) = layer.init.as_ref().map(WeightInit::validate) {
+                push("init", e);
+            }
+            if let Some(l2) = layer.l2.filter(|l2| !(l2.is_finite() && *l2 >= 0.0)) {
+                push("l2", format!("l2 must be a non-negative number, got {}", l2));
+            }
+            if let Some(name) = layer.name.as_deref() {
+                if seen_names.contains(&name) {
+                    push("name", "duplicate layer name".to_owned());
+                }
+                seen_names.push(name);
+            }
+        }
+
+        if let Some(output) = self.layers.last() {
+            let last = Some(self.layers.len() - 1);
+            match (self.loss, &output.activation) {
+                (LossType::CrossEntropy, a) if *a != ActivationFunction::Softmax => {
+                    errors.push(SpecError::new(last, "loss", "loss cross_entropy requires a Softmax output layer"));
+                }
+                (LossType::BinaryCrossEntropy, a) if *a != ActivationFunction::Sigmoid => {
+                    errors.push(SpecError::new(last, "loss", "loss binary_cross_entropy requires a Sigmoid output layer"));
+                }
+                _ => {}
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
     }
 }
+
+/// Replaces characters that are unsafe in a file stem with `_`.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// One problem found by `NetworkSpec::validate`.
+///
+/// # Fields
+/// - `layer`   — 0-based index of the offending layer, or `None` for
+///   spec-wide problems (name, empty layer list)
+/// - `field`   — the spec field at fault (`"name"`, `"size"`,
+///   `"input_size"`, `"activation"`, `"init"`, `"l2"`, `"layers"`, `"loss"`)
+/// - `message` — human-readable description, prefixed with the layer label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub layer: Option<usize>,
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl SpecError {
+    fn new(layer: Option<usize>, field: &'static str, message: impl Into<String>) -> SpecError {
+        SpecError { layer, field, message: message.into() }
+    }
+}
+
+impl std::fmt::Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for SpecError {}
@@ -0,0 +1,169 @@
+//! Model surgery: evolving a trained architecture in place.
+//!
+//! `insert_layer` and `remove_layer` re-wire the neighbouring dense layer
+//! when the widths no longer line up (its weights are re-initialized, its
+//! biases kept).  `widen_layer` is Net2Net's function-preserving widening:
+//! the network computes the same outputs before and after, so training can
+//! resume from where it left off.
+
+use rand::Rng;
+
+use crate::activation::activation::ActivationFunction;
+use crate::layers::dense::Layer;
+use crate::layers::ops::LayerOps;
+use crate::math::init::WeightInit;
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+
+impl Network {
+    /// Inserts a new dense layer of `size` units at position `index`
+    /// (`layers.len()` appends a new output layer).  It reads the output of
+    /// the layer before it — or the network input at `index == 0` — and is
+    /// initialized with `WeightInit::for_activation`.  When `size` differs
+    /// from what the following layer expects, that layer's weights are
+    /// re-initialized for the new width.
+    ///
+    /// # Panics
+    /// Panics if the network is empty, `index > layers.len()`, or the
+    /// following layer needs re-wiring but is not dense.
+    pub fn insert_layer(&mut self, index: usize, size: usize, activation: ActivationFunction) {
+        self.insert_layer_with_rng(index, size, activation, &mut rand::thread_rng());
+    }
+
+    /// `insert_layer` with the new weights drawn from `rng`.
+    pub fn insert_layer_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        index: usize,
+        size: usize,
+        activation: ActivationFunction,
+        rng: &mut R,
+    ) {
+        assert!(!self.layers.is_empty(), "cannot insert into an empty network: its input size is unknown");
+        assert!(index <= self.layers.len(), "insert index {} out of bounds for {} layers", index, self.layers.len());
+
+        let input_size = self.input_width(index);
+        self.layers.insert(index, Box::new(Layer::new_with_rng(size, input_size, activation, rng)));
+        if index + 1 < self.layers.len() {
+            self.rewire_input(index + 1, size, rng);
+        } else {
+            self.clear_output_metadata();
+        }
+    }
+
+    /// Removes and returns the layer at `index`.  The layer after it is
+    /// re-wired to the removed layer's input width if needed; removing the
+    /// output layer makes the previous layer the output.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds, or the following layer needs
+    /// re-wiring but is not dense.
+    pub fn remove_layer(&mut self, index: usize) -> Box<dyn LayerOps> {
+        self.remove_layer_with_rng(index, &mut rand::thread_rng())
+    }
+
+    /// `remove_layer` with any re-initialized weights drawn from `rng`.
+    pub fn remove_layer_with_rng<R: Rng + ?Sized>(&mut self, index: usize, rng: &mut R) -> Box<dyn LayerOps> {
+        assert!(index < self.layers.len(), "remove index {} out of bounds for {} layers", index, self.layers.len());
+
+        let removed = self.layers.remove(index);
+        if index < self.layers.len() {
+            self.rewire_input(index, removed.input_size(), rng);
+        } else {
+            self.clear_output_metadata();
+        }
+        removed
+    }
+
+    /// Widens the hidden dense layer at `index` to `new_size` units without
+    /// changing what the network computes (Net2Net).  Each new unit copies
+    /// the incoming weights and bias of a randomly chosen existing unit; the
+    /// next layer's outgoing weights of every copied unit are split among
+    /// its copies in random proportions, so the copies sum to the original
+    /// contribution but receive different gradients once training resumes.
+    ///
+    /// Exact for element-wise activations; a Softmax hidden layer is not
+    /// preserved.
+    ///
+    /// # Panics
+    /// Panics if the layer is the output layer, it or the next layer is not
+    /// dense, or `new_size` is smaller than the current size.
+    pub fn widen_layer(&mut self, index: usize, new_size: usize) {
+        self.widen_layer_with_rng(index, new_size, &mut rand::thread_rng());
+    }
+
+    /// `widen_layer` with the copied units and split proportions drawn from
+    /// `rng`.
+    pub fn widen_layer_with_rng<R: Rng + ?Sized>(&mut self, index: usize, new_size: usize, rng: &mut R) {
+        assert!(index + 1 < self.layers.len(), "only hidden layers can be widened (layer {} of {})", index, self.layers.len());
+        let (layer, next) = match (self.layers[index].as_dense(), self.layers[index + 1].as_dense()) {
+            (Some(layer), Some(next)) => (layer, next),
+            _ => panic!("widen_layer needs layer {} and the layer after it to be dense", index),
+        };
+        let size = layer.size;
+        assert!(new_size >= size, "cannot widen layer {} from {} to {} units", index, size, new_size);
+
+        // Unit `j` of the widened layer copies unit `source[j]`.
+        let source: Vec<usize> = (0..size).chain((size..new_size).map(|_| rng.gen_range(0..size))).collect();
+        let share: Vec<f64> = {
+            let raw: Vec<f64> = (0..new_size).map(|_| rng.gen_range(0.5..1.5)).collect();
+            let mut totals = vec![0.0; size];
+            source.iter().zip(&raw).for_each(|(&s, &r)| totals[s] += r);
+            source.iter().zip(&raw).map(|(&s, &r)| r / totals[s]).collect()
+        };
+
+        let fan_in = layer.weights.rows;
+        let mut weights = Matrix::zeros(fan_in, new_size);
+        for i in 0..fan_in {
+            for (j, &s) in source.iter().enumerate() {
+                weights[(i, j)] = layer.weights[(i, s)];
+            }
+        }
+        let biases = Matrix::from_flat(1, new_size, source.iter().map(|&s| layer.biases.data[s]).collect());
+
+        let mut next_weights = Matrix::zeros(new_size, next.size);
+        for (j, (&s, &f)) in source.iter().zip(&share).enumerate() {
+            next_weights.row_mut(j).iter_mut().zip(next.weights.row(s)).for_each(|(w, &v)| *w = v * f);
+        }
+
+        let widened = rebuilt(layer, weights, biases, rng);
+        let next = rebuilt(next, next_weights, next.biases.clone(), rng);
+        self.layers[index] = Box::new(widened);
+        self.layers[index + 1] = Box::new(next);
+    }
+
+    /// Width of the vector fed to the layer at `index`.
+    fn input_width(&self, index: usize) -> usize {
+        match index {
+            0 => self.layers[0].input_size(),
+            _ => self.layers[index - 1].output_size(),
+        }
+    }
+
+    /// Makes the layer at `index` accept `input_size` values, re-initializing
+    /// its weights if its current fan-in differs.
+    fn rewire_input<R: Rng + ?Sized>(&mut self, index: usize, input_size: usize, rng: &mut R) {
+        if self.layers[index].input_size() == input_size {
+            return;
+        }
+        let layer = self.layers[index].as_dense()
+            .unwrap_or_else(|| panic!(
+                "layer {} ({}) expects {} inputs and only dense layers can be re-wired to {}",
+                index, self.layers[index].kind(), self.layers[index].input_size(), input_size
+            ));
+        let weights = WeightInit::for_activation(&layer.activator).weights(input_size, layer.size, rng);
+        let rewired = rebuilt(layer, weights, layer.biases.clone(), rng);
+        self.layers[index] = Box::new(rewired);
+    }
+}
+
+/// A copy of `layer`'s settings (activation, name, L2, output mask) with
+/// new parameters.
+fn rebuilt<R: Rng + ?Sized>(layer: &Layer, weights: Matrix, biases: Matrix, rng: &mut R) -> Layer {
+    let mut out = Layer::new_with_init(weights.cols, weights.rows, layer.activator.clone(), WeightInit::Zeros, rng);
+    out.weights = weights;
+    out.biases = biases;
+    out.name = layer.name.clone();
+    out.l2 = layer.l2;
+    out.output_mask = layer.output_mask.clone().filter(|mask| mask.len() == out.size);
+    out
+}
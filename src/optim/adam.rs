@@ -0,0 +1,127 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{math::matrix::Matrix, layers::ops::LayerOps};
+use crate::optim::optimizer::Optimizer;
+use crate::optim::state::OptimizerState;
+
+/// Adam optimizer (Kingma & Ba, 2015).
+///
+/// Keeps exponential moving averages of each parameter's gradient (first
+/// moment) and squared gradient (second moment), corrects their bias towards
+/// zero in early steps, and scales every parameter's step by
+/// `m̂ / (√v̂ + epsilon)`.  This adapts the effective step size per parameter
+/// and typically converges far faster than plain SGD.
+///
+/// # Fields
+/// - `learning_rate` — step size α (0.001 is the usual default)
+/// - `beta1`         — decay rate of the first-moment average (default 0.9)
+/// - `beta2`         — decay rate of the second-moment average (default 0.999)
+/// - `epsilon`       — numerical stabilizer in the denominator (default 1e-8)
+///
+/// Moment state is created lazily per layer on the first `step` and keyed
+/// by layer index, so one `Adam` must only be used with one network.  The
+/// moments are serialized along with the hyperparameters, so a deserialized
+/// `Adam` continues exactly where it left off (see `Checkpoint`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Adam {
+    pub learning_rate: f64,
+    pub beta1: f64,
+    pub beta2: f64,
+    pub epsilon: f64,
+    state: Vec<Option<LayerState>>,
+}
+
+/// Moment estimates for one layer's weights and biases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LayerState {
+    m_weights: Matrix,
+    v_weights: Matrix,
+    m_biases: Matrix,
+    v_biases: Matrix,
+    /// Number of updates applied to this layer, for bias correction.
+    t: i32,
+}
+
+impl Adam {
+    /// Creates an Adam optimizer with the standard defaults for β₁, β₂ and ε.
+    pub fn new(learning_rate: f64) -> Adam {
+        Adam { learning_rate, beta1: 0.9, beta2: 0.999, epsilon: 1e-8, state: Vec::new() }
+    }
+
+    /// Applies one Adam update to the layer at `layer_index`.
+    pub fn step(&mut self, layer_index: usize, layer: &mut dyn LayerOps, weights_grad: &Matrix, biases_grad: &Matrix) {
+        if self.state.len() <= layer_index {
+            self.state.resize_with(layer_index + 1, || None);
+        }
+        let (weights, biases) = layer.params_mut();
+        let state = self.state[layer_index].get_or_insert_with(|| LayerState {
+            m_weights: Matrix::zeros(weights.rows, weights.cols),
+            v_weights: Matrix::zeros(weights.rows, weights.cols),
+            m_biases: Matrix::zeros(biases.rows, biases.cols),
+            v_biases: Matrix::zeros(biases.rows, biases.cols),
+            t: 0,
+        });
+        state.t += 1;
+
+        let hp = Hyperparams {
+            lr: self.learning_rate,
+            beta1: self.beta1,
+            beta2: self.beta2,
+            epsilon: self.epsilon,
+            correction1: 1.0 - self.beta1.powi(state.t),
+            correction2: 1.0 - self.beta2.powi(state.t),
+        };
+        hp.apply(weights, weights_grad, &mut state.m_weights, &mut state.v_weights);
+        hp.apply(biases, biases_grad, &mut state.m_biases, &mut state.v_biases);
+    }
+
+    /// Discards the moment estimates, e.g. before training a new network.
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+}
+
+impl Optimizer for Adam {
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn step(&mut self, layer_idx: usize, layer: &mut dyn LayerOps, weights_grad: &Matrix, biases_grad: &Matrix) {
+        Adam::step(self, layer_idx, layer, weights_grad, biases_grad);
+    }
+
+    fn state(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::Adam(self.clone()))
+    }
+}
+
+/// Per-step constants shared by the weight and bias updates.
+struct Hyperparams {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    epsilon: f64,
+    correction1: f64,
+    correction2: f64,
+}
+
+impl Hyperparams {
+    /// Updates the moments `m`, `v` with `grad` and steps `params` in place.
+    fn apply(&self, params: &mut Matrix, grad: &Matrix, m: &mut Matrix, v: &mut Matrix) {
+        for (i, p) in params.data.iter_mut().enumerate() {
+            let g = grad.data[i];
+            let m_i = self.beta1 * m.data[i] + (1.0 - self.beta1) * g;
+            let v_i = self.beta2 * v.data[i] + (1.0 - self.beta2) * g * g;
+            m.data[i] = m_i;
+            v.data[i] = v_i;
+
+            let m_hat = m_i / self.correction1;
+            let v_hat = v_i / self.correction2;
+            *p -= self.lr * m_hat / (v_hat.sqrt() + self.epsilon);
+        }
+    }
+}
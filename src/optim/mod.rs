@@ -1,3 +1,9 @@
+pub mod optimizer;
 pub mod sgd;
+pub mod adam;
+pub mod state;
 
+pub use optimizer::Optimizer;
 pub use sgd::Sgd;
+pub use adam::Adam;
+pub use state::OptimizerState;
@@ -0,0 +1,30 @@
+use crate::{math::matrix::Matrix, layers::ops::LayerOps};
+use crate::optim::state::OptimizerState;
+
+/// A weight-update rule usable by the training loops.
+///
+/// Every training entry point (`train_network`, `train_loop`,
+/// `train_loop_with_callbacks`, `distill`, `train_snapshot_ensemble`) takes a
+/// `&mut dyn Optimizer`, so a new update rule only has to implement this
+/// trait.  The loops call `step` once per layer and mini-batch with the
+/// batch-averaged gradients, borrowed from buffers they reuse for the next
+/// batch.  `layer_idx` identifies the layer so stateful
+/// optimizers (e.g. `Adam`) can keep per-parameter state; it is stable for
+/// the lifetime of a network.  The learning-rate accessors let
+/// `LrSchedule` rescale the rate per epoch.
+pub trait Optimizer {
+    fn learning_rate(&self) -> f64;
+
+    fn set_learning_rate(&mut self, learning_rate: f64);
+
+    /// Applies one update to `layer` (at position `layer_idx` in its
+    /// network) given the gradients of its `params`.
+    fn step(&mut self, layer_idx: usize, layer: &mut dyn LayerOps, weights_grad: &Matrix, biases_grad: &Matrix);
+
+    /// Snapshot of the optimizer (hyperparameters plus any accumulated
+    /// state) for checkpointing.  `None` — the default — means the optimizer
+    /// cannot be checkpointed.
+    fn state(&self) -> Option<OptimizerState> {
+        None
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{math::matrix::Matrix, layers::dense::Layer};
+use serde::{Serialize, Deserialize};
 
+use crate::{math::matrix::Matrix, layers::ops::LayerOps};
+use crate::optim::optimizer::Optimizer;
+use crate::optim::state::OptimizerState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sgd {
     pub learning_rate: f64,
 }
@@ -10,7 +15,28 @@ impl Sgd {
     }
 
     /// Applies one SGD weight update to a layer given its pre-computed gradients.
-    pub fn step(&self, layer: &mut Layer, weights_grad: Matrix, biases_grad: Matrix) {
-        layer.apply_gradients(weights_grad, biases_grad, self.learning_rate);
+    pub fn step(&self, layer: &mut dyn LayerOps, weights_grad: &Matrix, biases_grad: &Matrix) {
+        let lr = self.learning_rate;
+        let (weights, biases) = layer.params_mut();
+        weights.sub_scaled_assign_mat(weights_grad, lr);
+        biases.sub_scaled_assign_mat(biases_grad, lr);
+    }
+}
+
+impl Optimizer for Sgd {
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn step(&mut self, _layer_idx: usize, layer: &mut dyn LayerOps, weights_grad: &Matrix, biases_grad: &Matrix) {
+        Sgd::step(self, layer, weights_grad, biases_grad);
+    }
+
+    fn state(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::Sgd(self.clone()))
     }
 }
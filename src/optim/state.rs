@@ -0,0 +1,25 @@
+use serde::{Serialize, Deserialize};
+
+use crate::optim::adam::Adam;
+use crate::optim::optimizer::Optimizer;
+use crate::optim::sgd::Sgd;
+
+/// Serializable snapshot of one of the built-in optimizers, including its
+/// accumulated state (e.g. Adam's moment estimates).  Produced by
+/// `Optimizer::state` and stored in a `Checkpoint`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OptimizerState {
+    Sgd(Sgd),
+    Adam(Adam),
+}
+
+impl OptimizerState {
+    /// Rebuilds the optimizer, ready to continue training.
+    pub fn into_optimizer(self) -> Box<dyn Optimizer> {
+        match self {
+            OptimizerState::Sgd(sgd)   => Box::new(sgd),
+            OptimizerState::Adam(adam) => Box::new(adam),
+        }
+    }
+}
@@ -1,8 +1,27 @@
-/// Image preprocessing utilities for the ferrite-nn studio.
+//! Image preprocessing (requires the `image` feature).
+//!
+//! These functions decode image bytes (PNG/JPEG/BMP/GIF), resize them to the
+//! specified dimensions, and normalize pixel values to the [0, 1] range ready
+//! for network inference.  The Studio uses the same functions, so inputs
+//! prepared here match what its models were trained and tested with.
+
+use crate::network::metadata::InputType;
+
+/// Prepares image bytes for a model whose metadata declares `input_type`.
 ///
-/// These functions decode image bytes (PNG/JPEG/BMP/GIF), resize them to the
-/// specified dimensions, and normalize pixel values to the [0, 1] range ready
-/// for network inference.
+/// Returns an error if the bytes cannot be decoded or `input_type` is not an
+/// image type.
+pub fn image_bytes_to_input(bytes: &[u8], input_type: &InputType) -> Result<Vec<f64>, String> {
+    match *input_type {
+        InputType::ImageGrayscale { width, height } => {
+            image_bytes_to_grayscale_input(bytes, width, height)
+        }
+        InputType::ImageRgb { width, height } => {
+            image_bytes_to_rgb_input(bytes, width, height)
+        }
+        _ => Err("Model does not declare an image input type.".to_owned()),
+    }
+}
 
 /// Decodes image bytes, resizes to `width × height`, converts to grayscale,
 /// and normalizes pixels to [0, 1].
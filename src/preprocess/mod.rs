@@ -0,0 +1,4 @@
+#[cfg(feature = "image")]
+pub mod image;
+pub mod polynomial;
+pub mod text;
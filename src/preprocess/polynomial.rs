@@ -0,0 +1,123 @@
+//! Degree-2 polynomial and interaction feature expansion.
+//!
+//! A `PolynomialFeatures` appends the pairwise products `xᵢ·xⱼ` (and,
+//! unless `interaction_only`, the squares `xᵢ²`) to a tabular input, so a
+//! tiny MLP — or even a single layer — can fit curved decision boundaries.
+//! Stored in a model's metadata (`TrainConfig::polynomial_features`,
+//! `Network::set_polynomial_features`), it is applied to raw inputs by the
+//! inference paths before any input scaling.
+
+use serde::{Serialize, Deserialize};
+
+/// Degree-2 feature expansion of `n_features` raw inputs.
+///
+/// The expanded vector holds the raw features first, in order, then one
+/// product per pair `i ≤ j` (`i < j` with `interaction_only`), in row-major
+/// pair order: `x₀², x₀x₁, …, x₀xₙ₋₁, x₁², x₁x₂, …`.
+///
+/// # Fields
+/// - `n_features`       — number of raw input features
+/// - `interaction_only` — leave out the squares, keeping only products of
+///   distinct features (default `false`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolynomialFeatures {
+    pub n_features: usize,
+    #[serde(default)]
+    pub interaction_only: bool,
+}
+
+impl PolynomialFeatures {
+    /// Full degree-2 expansion (squares and interactions) of `n_features`
+    /// inputs.
+    pub fn new(n_features: usize) -> PolynomialFeatures {
+        PolynomialFeatures { n_features, interaction_only: false }
+    }
+
+    /// With `true`, leaves out the squares.
+    pub fn with_interaction_only(mut self, interaction_only: bool) -> PolynomialFeatures {
+        self.interaction_only = interaction_only;
+        self
+    }
+
+    /// Length of every vector produced by `transform`; the input size of the
+    /// network's first layer.
+    pub fn output_size(&self) -> usize {
+        let n = self.n_features;
+        if self.interaction_only { n + n * n.saturating_sub(1) / 2 } else { n + n * (n + 1) / 2 }
+    }
+
+    /// Expands one raw input.
+    ///
+    /// # Panics
+    /// Panics if `input.len() != n_features`.
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        self.check_len(input.len());
+        let mut values = Vec::with_capacity(self.output_size());
+        values.extend_from_slice(input);
+        values.extend(self.pairs().map(|(i, j)| input[i] * input[j]));
+        values
+    }
+
+    /// Expands every input, e.g. to build training inputs by hand.
+    pub fn transform_all(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().map(|input| self.transform(input)).collect()
+    }
+
+    /// Chain rule through the expansion: maps `gradient` with respect to
+    /// the expanded features of `input` to the gradient with respect to the
+    /// raw features.
+    ///
+    /// # Panics
+    /// Panics if `input` or `gradient` has the wrong length.
+    pub(crate) fn chain_gradient(&self, input: &[f64], gradient: &[f64]) -> Vec<f64> {
+        self.check_len(input.len());
+        assert_eq!(gradient.len(), self.output_size(), "gradient has the wrong length");
+        let mut raw = gradient[..self.n_features].to_vec();
+        for ((i, j), g) in self.pairs().zip(&gradient[self.n_features..]) {
+            raw[i] += g * input[j];
+            raw[j] += g * input[i];
+        }
+        raw
+    }
+
+    /// Names of the expanded features for raw feature `names`: the names
+    /// themselves, then `"a^2"` and `"a*b"` for the products.
+    ///
+    /// # Panics
+    /// Panics if `names.len() != n_features`.
+    pub fn feature_names<S: AsRef<str>>(&self, names: &[S]) -> Vec<String> {
+        self.check_len(names.len());
+        let mut expanded: Vec<String> = names.iter().map(|n| n.as_ref().to_owned()).collect();
+        expanded.extend(self.pairs().map(|(i, j)| {
+            let (a, b) = (names[i].as_ref(), names[j].as_ref());
+            if i == j { format!("{}^2", a) } else { format!("{}*{}", a, b) }
+        }));
+        expanded
+    }
+
+    /// Serializes the expansion to a pretty-printed JSON file at `path`.
+    pub fn save_json(&self, path: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Deserializes an expansion from a JSON file written by `save_json`.
+    pub fn load_json(path: &str) -> std::io::Result<PolynomialFeatures> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        serde_json::from_reader(reader)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Index pairs `(i, j)` of the products, in output order.
+    fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let offset = usize::from(self.interaction_only);
+        (0..self.n_features).flat_map(move |i| (i + offset..self.n_features).map(move |j| (i, j)))
+    }
+
+    fn check_len(&self, len: usize) {
+        assert_eq!(len, self.n_features, "expected {} raw features, got {}", self.n_features, len);
+    }
+}
@@ -0,0 +1,122 @@
+//! Bag-of-words text vectorization.
+//!
+//! A `TextVectorizer` turns a raw string into one value per vocabulary
+//! word.  Stored in a model's `InputType::Text`, it lets the Studio and
+//! `serve::parse_text_input` run text classifiers on raw strings, encoding
+//! them exactly as the training data was encoded.
+
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+/// What each vocabulary entry of a vectorized text holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TermWeighting {
+    /// Number of occurrences of the word.
+    #[default]
+    Count,
+    /// 1 if the word occurs, 0 otherwise.
+    Binary,
+    /// Occurrences divided by the number of tokens in the text.
+    Frequency,
+}
+
+/// Bag-of-words vectorizer configuration.
+///
+/// Text is split into tokens at every character that is not alphanumeric
+/// (so punctuation and whitespace separate words), optionally lowercased,
+/// and counted against `vocabulary`; words outside the vocabulary are
+/// ignored.
+///
+/// # Fields
+/// - `vocabulary` — one word per input of the network, in input order
+/// - `lowercase`  — lowercase tokens before lookup (default `true`)
+/// - `weighting`  — value stored per word (default `TermWeighting::Count`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextVectorizer {
+    pub vocabulary: Vec<String>,
+    #[serde(default = "default_lowercase")]
+    pub lowercase: bool,
+    #[serde(default)]
+    pub weighting: TermWeighting,
+}
+
+fn default_lowercase() -> bool {
+    true
+}
+
+impl TextVectorizer {
+    /// A lowercasing, counting vectorizer over `vocabulary`.
+    pub fn new(vocabulary: Vec<String>) -> TextVectorizer {
+        TextVectorizer { vocabulary, lowercase: true, weighting: TermWeighting::Count }
+    }
+
+    /// Builds the vocabulary from a corpus: the `max_features` words that
+    /// occur in the most texts (ties broken alphabetically), lowercased.
+    pub fn fit<S: AsRef<str>>(texts: &[S], max_features: usize) -> TextVectorizer {
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+        for text in texts {
+            let mut words: Vec<String> = tokens(text.as_ref(), true).collect();
+            words.sort_unstable();
+            words.dedup();
+            for word in words {
+                *document_frequency.entry(word).or_insert(0) += 1;
+            }
+        }
+        let mut ranked: Vec<(String, usize)> = document_frequency.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        TextVectorizer::new(ranked.into_iter().take(max_features).map(|(word, _)| word).collect())
+    }
+
+    /// Length of every vector produced by `transform`.
+    pub fn len(&self) -> usize {
+        self.vocabulary.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vocabulary.is_empty()
+    }
+
+    /// Vectorizes one text.
+    pub fn transform(&self, text: &str) -> Vec<f64> {
+        self.vectorize(text, &self.index())
+    }
+
+    /// Vectorizes every text of a corpus, e.g. to build training inputs.
+    pub fn transform_all<S: AsRef<str>>(&self, texts: &[S]) -> Vec<Vec<f64>> {
+        let index = self.index();
+        texts.iter().map(|text| self.vectorize(text.as_ref(), &index)).collect()
+    }
+
+    fn index(&self) -> HashMap<&str, usize> {
+        self.vocabulary.iter().enumerate().map(|(i, word)| (word.as_str(), i)).collect()
+    }
+
+    fn vectorize(&self, text: &str, index: &HashMap<&str, usize>) -> Vec<f64> {
+        let mut values = vec![0.0f64; self.vocabulary.len()];
+        let mut n_tokens = 0usize;
+        for token in tokens(text, self.lowercase) {
+            n_tokens += 1;
+            if let Some(&i) = index.get(token.as_str()) {
+                values[i] += 1.0;
+            }
+        }
+        match self.weighting {
+            TermWeighting::Count => {}
+            TermWeighting::Binary => values.iter_mut().for_each(|v| *v = v.min(1.0)),
+            TermWeighting::Frequency if n_tokens > 0 => {
+                values.iter_mut().for_each(|v| *v /= n_tokens as f64);
+            }
+            TermWeighting::Frequency => {}
+        }
+        values
+    }
+}
+
+/// Splits `text` at non-alphanumeric characters.
+fn tokens(text: &str, lowercase: bool) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| if lowercase { word.to_lowercase() } else { word.to_owned() })
+}
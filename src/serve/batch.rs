@@ -0,0 +1,116 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::network::network::Network;
+use crate::serve::error::ServeError;
+use crate::serve::input::check_input_len;
+
+/// How a `BatchQueue` groups requests.
+///
+/// # Fields
+/// - `max_batch_size` — a batch is run as soon as it holds this many inputs
+/// - `max_latency`    — how long the first request of a batch may wait for
+///   others to join before the batch is run anyway; this bounds the extra
+///   latency batching adds to any single request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchConfig {
+    pub max_batch_size: usize,
+    pub max_latency: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig { max_batch_size: 32, max_latency: Duration::from_millis(2) }
+    }
+}
+
+struct Job {
+    input: Vec<f64>,
+    reply: Sender<Vec<f64>>,
+}
+
+/// Groups single inference requests from many threads into
+/// `Network::forward_batch` calls.
+///
+/// A background worker collects submitted inputs until `max_batch_size` are
+/// waiting or the oldest has waited `max_latency`, runs them as one batch and
+/// hands every caller its own output.  Under concurrent load this streams the
+/// weights once per batch instead of once per request; with a single caller
+/// it only adds up to `max_latency` per request.
+///
+/// `BatchQueue` is `Sync`: share it behind an `Arc`.  Dropping the last
+/// handle stops the worker after it has answered the queued requests.
+pub struct BatchQueue {
+    network: Arc<Network>,
+    sender: Option<Sender<Job>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl BatchQueue {
+    /// Starts a worker thread serving `network`.
+    ///
+    /// # Panics
+    /// Panics if `config.max_batch_size` is 0.
+    pub fn new(network: Arc<Network>, config: BatchConfig) -> BatchQueue {
+        assert!(config.max_batch_size > 0, "max_batch_size must be at least 1");
+        let (sender, receiver) = mpsc::channel();
+        let worker_network = network.clone();
+        let worker = std::thread::Builder::new()
+            .name("ferrite-batch".into())
+            .spawn(move || run_worker(&worker_network, receiver, config))
+            .expect("failed to spawn batch worker thread");
+        BatchQueue { network, sender: Some(sender), worker: Some(worker) }
+    }
+
+    /// The model this queue runs.
+    pub fn network(&self) -> &Arc<Network> {
+        &self.network
+    }
+
+    /// Queues `input` and blocks until its output is ready.
+    ///
+    /// Returns `BadInput` if the input length does not match the network
+    /// (checked before queueing, so one bad request cannot fail a batch).
+    pub fn submit(&self, input: Vec<f64>) -> Result<Vec<f64>, ServeError> {
+        check_input_len(&self.network, &input)?;
+        let (reply, result) = mpsc::channel();
+        self.sender.as_ref()
+            .and_then(|s| s.send(Job { input, reply }).ok())
+            .ok_or_else(|| ServeError::ModelLoad("batch worker has stopped".into()))?;
+        result.recv().map_err(|_| ServeError::ModelLoad("batch worker has stopped".into()))
+    }
+}
+
+impl Drop for BatchQueue {
+    fn drop(&mut self) {
+        // Closing the channel ends the worker's receive loop.
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_worker(network: &Network, receiver: Receiver<Job>, config: BatchConfig) {
+    // Block for the first job of each batch; exit once every sender is gone.
+    while let Ok(first) = receiver.recv() {
+        let deadline = Instant::now() + config.max_latency;
+        let mut jobs = vec![first];
+        while jobs.len() < config.max_batch_size {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok(job) => jobs.push(job),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let inputs: Vec<Vec<f64>> = jobs.iter_mut().map(|j| std::mem::take(&mut j.input)).collect();
+        let outputs = network.forward_batch(&inputs);
+        for (job, output) in jobs.into_iter().zip(outputs) {
+            // The caller may have given up waiting; nothing to do then.
+            let _ = job.reply.send(output);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Why a serving request could not be answered.
+#[derive(Debug)]
+pub enum ServeError {
+    /// The model name is invalid or no such model file exists.
+    ModelNotFound(String),
+    /// The model file exists but could not be read or parsed.
+    ModelLoad(String),
+    /// The request or its input values are malformed.
+    BadInput(String),
+}
+
+impl ServeError {
+    /// HTTP status code matching the error kind.
+    pub fn status(&self) -> u16 {
+        match self {
+            ServeError::ModelNotFound(_) => 404,
+            ServeError::ModelLoad(_)     => 500,
+            ServeError::BadInput(_)      => 400,
+        }
+    }
+}
+
+impl fmt::Display for ServeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServeError::ModelNotFound(msg) | ServeError::ModelLoad(msg) | ServeError::BadInput(msg) => {
+                write!(f, "{}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServeError {}
@@ -0,0 +1,51 @@
+use serde::{Serialize, Deserialize};
+
+use crate::network::session::InferenceSession;
+use crate::serve::error::ServeError;
+use crate::serve::input::check_input_len;
+use crate::serve::prediction::Prediction;
+use crate::serve::store::ModelStore;
+
+/// Body of a JSON prediction request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictRequest {
+    pub model: String,
+    pub inputs: Vec<f64>,
+}
+
+/// Body of a successful JSON prediction response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictResponse {
+    pub model: String,
+    /// Raw output-layer activations.
+    pub output: Vec<f64>,
+    pub prediction: Prediction,
+}
+
+/// Answers a JSON prediction request, independent of any HTTP framework.
+///
+/// `body` must be `{"model": "<name>", "inputs": [<f64>, ...]}`.  Returns the
+/// HTTP status code and a JSON body: a `PredictResponse` on success, or
+/// `{"error": "<message>"}`.  Wire it to a `POST` route of any server.
+/// Requests are batched across threads when the store has batching enabled.
+pub fn handle_predict_json(store: &ModelStore, body: &[u8]) -> (u16, String) {
+    match predict(store, body) {
+        Ok(response) => (200, serde_json::to_string(&response).unwrap_or_default()),
+        Err(e) => (e.status(), serde_json::json!({ "error": e.to_string() }).to_string()),
+    }
+}
+
+fn predict(store: &ModelStore, body: &[u8]) -> Result<PredictResponse, ServeError> {
+    let request: PredictRequest = serde_json::from_slice(body)
+        .map_err(|e| ServeError::BadInput(format!("invalid request body: {}", e)))?;
+    let network = store.get(&request.model)?;
+    check_input_len(&network, &request.inputs)?;
+
+    let output = if store.batching().is_some() {
+        store.queue(&request.model)?.submit(request.inputs)?
+    } else {
+        InferenceSession::new(network.clone()).run(&request.inputs).to_vec()
+    };
+    let prediction = Prediction::from_output(&network, &output);
+    Ok(PredictResponse { model: request.model, output, prediction })
+}
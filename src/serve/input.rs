@@ -0,0 +1,76 @@
+use crate::network::network::Network;
+use crate::serve::error::ServeError;
+
+/// Parses comma-separated numbers (whitespace around values is ignored) and
+/// checks the count against the network's input size.
+pub fn parse_numeric_input(network: &Network, raw: &str) -> Result<Vec<f64>, ServeError> {
+    let values = raw.split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<f64>().map_err(|_| ServeError::BadInput(format!("'{}' is not a number", s))))
+        .collect::<Result<Vec<f64>, ServeError>>()?;
+    check_input_len(network, &values)?;
+    Ok(values)
+}
+
+/// Decodes an uploaded image according to the input type declared in the
+/// network's metadata (grayscale or RGB, resized and normalized).
+#[cfg(feature = "image")]
+pub fn parse_image_input(network: &Network, bytes: &[u8]) -> Result<Vec<f64>, ServeError> {
+    use crate::network::metadata::InputType;
+    use crate::preprocess::image::image_bytes_to_input;
+
+    match network.metadata.as_ref().and_then(|m| m.input_type.as_ref()) {
+        Some(t @ (InputType::ImageGrayscale { .. } | InputType::ImageRgb { .. })) => {
+            image_bytes_to_input(bytes, t)
+                .map_err(|e| ServeError::BadInput(format!("image decode error: {}", e)))
+        }
+        _ => Err(ServeError::BadInput("model does not declare an image input type".into())),
+    }
+}
+
+/// Vectorizes raw text with the bag-of-words vectorizer declared in the
+/// network's metadata (`InputType::Text`).
+pub fn parse_text_input(network: &Network, text: &str) -> Result<Vec<f64>, ServeError> {
+    use crate::network::metadata::InputType;
+
+    match network.metadata.as_ref().and_then(|m| m.input_type.as_ref()) {
+        Some(InputType::Text { vectorizer }) => {
+            let values = vectorizer.transform(text);
+            check_input_len(network, &values)?;
+            Ok(values)
+        }
+        _ => Err(ServeError::BadInput("model does not declare a text input type".into())),
+    }
+}
+
+/// Encodes a raw comma-separated row of cells (numbers and category names,
+/// e.g. `"5.1, red, large"`) with the encoder declared in the network's
+/// metadata (`InputType::Tabular`).
+pub fn parse_tabular_input(network: &Network, row: &str) -> Result<Vec<f64>, ServeError> {
+    use crate::network::metadata::InputType;
+
+    match network.metadata.as_ref().and_then(|m| m.input_type.as_ref()) {
+        Some(InputType::Tabular { encoder }) => {
+            let cells: Vec<&str> = row.split(',').collect();
+            let values = encoder.transform(&cells).map_err(ServeError::BadInput)?;
+            check_input_len(network, &values)?;
+            Ok(values)
+        }
+        _ => Err(ServeError::BadInput("model does not declare a tabular input type".into())),
+    }
+}
+
+/// Errors unless `input` has exactly as many values as the network takes
+/// (see `Network::input_size`).
+pub fn check_input_len(network: &Network, input: &[f64]) -> Result<(), ServeError> {
+    let expected = network.input_size();
+    if input.len() != expected {
+        return Err(ServeError::BadInput(format!(
+            "input length mismatch: model expects {} values, got {}",
+            expected,
+            input.len()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! Building blocks for embedding a model-testing endpoint in an application:
+//! a cached model store, input parsing, predictions as data, a request
+//! batching queue, and a framework-agnostic JSON request handler.  The Studio's Test tab is built
+//! on this module.
+
+pub mod error;
+pub mod store;
+pub mod input;
+pub mod prediction;
+pub mod handler;
+pub mod batch;
+
+pub use error::ServeError;
+pub use store::ModelStore;
+pub use input::{parse_numeric_input, parse_text_input, parse_tabular_input, check_input_len};
+#[cfg(feature = "image")]
+pub use input::parse_image_input;
+pub use prediction::{Prediction, ClassScore};
+pub use batch::{BatchQueue, BatchConfig};
+pub use handler::{handle_predict_json, PredictRequest, PredictResponse};
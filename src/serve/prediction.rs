@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+
+use crate::activation::activation::ActivationFunction;
+use crate::network::network::Network;
+
+/// One class and its probability in a `Prediction::Classes` ranking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassScore {
+    pub index: usize,
+    /// Output label from the model metadata, or the index as a string.
+    pub label: String,
+    pub probability: f64,
+}
+
+/// A network output interpreted according to the output activation.
+///
+/// - `Classes`     — Softmax output: the ranked class distribution
+/// - `Probability` — single Sigmoid output
+/// - `Raw`         — anything else (regression values)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Prediction {
+    Classes { ranked: Vec<ClassScore> },
+    Probability { value: f64 },
+    Raw { values: Vec<f64> },
+}
+
+impl Prediction {
+    /// Interprets `output`, which must come from `network`.
+    pub fn from_output(network: &Network, output: &[f64]) -> Prediction {
+        let activator = network.layers.last().and_then(|l| l.activation());
+        match activator {
+            Some(ActivationFunction::Softmax) => {
+                let labels = network.metadata.as_ref().and_then(|m| m.output_labels.as_deref());
+                let mut ranked: Vec<ClassScore> = output.iter().enumerate()
+                    .map(|(index, &probability)| ClassScore {
+                        index,
+                        label: labels.and_then(|l| l.get(index)).cloned()
+                            .unwrap_or_else(|| index.to_string()),
+                        probability,
+                    })
+                    .collect();
+                ranked.sort_by(|a, b| {
+                    b.probability.partial_cmp(&a.probability).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                Prediction::Classes { ranked }
+            }
+            Some(ActivationFunction::Sigmoid) if output.len() == 1 => {
+                Prediction::Probability { value: output[0] }
+            }
+            _ => Prediction::Raw { values: output.to_vec() },
+        }
+    }
+
+    /// The most likely class, for `Classes` predictions.
+    pub fn top_class(&self) -> Option<&ClassScore> {
+        match self {
+            Prediction::Classes { ranked } => ranked.first(),
+            _ => None,
+        }
+    }
+}
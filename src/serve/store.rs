@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::network::network::Network;
+use crate::network::registry::{validate_name, ModelRegistry};
+use crate::serve::batch::{BatchConfig, BatchQueue};
+use crate::serve::error::ServeError;
+
+/// Loads models from a directory on demand and keeps them in memory.
+///
+/// Models are shared as `Arc<Network>`, so concurrent requests never copy
+/// the weights (pair with `InferenceSession` to run them).  A cached model is
+/// reloaded when its file's modification time changes, so re-saving or
+/// re-importing a model takes effect without a restart.  `ModelStore` is
+/// `Sync`; share it between request threads behind an `Arc`.
+///
+/// With `with_batching`, `queue` additionally keeps one `BatchQueue` per
+/// model so concurrent requests are grouped into batched forward passes.
+pub struct ModelStore {
+    dir: PathBuf,
+    cache: Mutex<HashMap<String, (SystemTime, Arc<Network>)>>,
+    batching: Option<BatchConfig>,
+    queues: Mutex<HashMap<String, Arc<BatchQueue>>>,
+}
+
+impl ModelStore {
+    pub fn new(dir: impl AsRef<Path>) -> ModelStore {
+        ModelStore {
+            dir: dir.as_ref().to_path_buf(),
+            cache: Mutex::new(HashMap::new()),
+            batching: None,
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Enables request batching (see `queue`) with the given settings.
+    pub fn with_batching(mut self, config: BatchConfig) -> ModelStore {
+        self.batching = Some(config);
+        self
+    }
+
+    /// The batching settings, if batching is enabled.
+    pub fn batching(&self) -> Option<BatchConfig> {
+        self.batching
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Names of the models currently in the directory, sorted.
+    pub fn names(&self) -> Vec<String> {
+        ModelRegistry::open(&self.dir)
+            .map(|registry| registry.names())
+            .unwrap_or_default()
+    }
+
+    /// Returns the named model, loading it if it is not cached or its file
+    /// changed since it was loaded.
+    pub fn get(&self, name: &str) -> Result<Arc<Network>, ServeError> {
+        validate_name(name).map_err(|e| ServeError::ModelNotFound(e.to_string()))?;
+        let path = self.dir.join(format!("{}.json", name));
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map_err(|_| ServeError::ModelNotFound(format!("no model named '{}'", name)))?;
+
+        if let Some((cached_at, network)) = self.cache.lock().unwrap().get(name) {
+            if *cached_at == modified {
+                return Ok(network.clone());
+            }
+        }
+
+        // Load outside the lock — large models take a while to parse.
+        let network = Network::load_json(&path.to_string_lossy())
+            .map_err(|e| ServeError::ModelLoad(format!("could not load model '{}': {}", name, e)))?;
+        if network.layers.is_empty() {
+            return Err(ServeError::ModelLoad(format!("model '{}' has no layers", name)));
+        }
+        let network = Arc::new(network);
+        self.cache.lock().unwrap().insert(name.to_owned(), (modified, network.clone()));
+        Ok(network)
+    }
+
+    /// Returns the batch queue for the named model, starting one on first
+    /// use and replacing it when the model file changed.  Uses the
+    /// `with_batching` settings, or `BatchConfig::default()` if batching was
+    /// not enabled.
+    pub fn queue(&self, name: &str) -> Result<Arc<BatchQueue>, ServeError> {
+        let network = self.get(name)?;
+        let mut queues = self.queues.lock().unwrap();
+        if let Some(queue) = queues.get(name) {
+            if Arc::ptr_eq(queue.network(), &network) {
+                return Ok(queue.clone());
+            }
+        }
+        let queue = Arc::new(BatchQueue::new(network, self.batching.unwrap_or_default()));
+        queues.insert(name.to_owned(), queue.clone());
+        Ok(queue)
+    }
+}
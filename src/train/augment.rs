@@ -0,0 +1,280 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Serialize, Deserialize};
+
+use crate::math::matrix::Matrix;
+use crate::network::metadata::InputType;
+use crate::train::train_config::SampleTransform;
+
+/// Input-noise augmentation: adds independent `N(0, std_dev²)` noise to every
+/// input feature of each training sample; labels are left unchanged.
+///
+/// Use as `TrainConfig::augment`.  The noise is drawn from the thread-local
+/// generator, so it is not covered by `TrainConfig::seed`; for images,
+/// `ImageAugment::with_noise` is.
+///
+/// # Panics
+/// Panics if `std_dev` is negative or not finite.
+pub fn gaussian_noise(std_dev: f64) -> SampleTransform {
+    assert!(std_dev >= 0.0 && std_dev.is_finite(), "noise std_dev must be non-negative");
+    Box::new(move |input: &mut Vec<f64>, _label: &mut Vec<f64>| {
+        let mut rng = rand::thread_rng();
+        for x in input.iter_mut() {
+            *x += std_dev * Matrix::sample_standard_normal(&mut rng);
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Image augmentation
+// ---------------------------------------------------------------------------
+
+/// Random image transforms applied to every training sample on the fly, to
+/// combat overfitting on small image sets.  Labels are left unchanged.
+///
+/// Each sample draws its own transform, in this order:
+/// 1. horizontal flip with probability ½
+/// 2. rotation about the centre by a uniform angle in
+///    `[-max_rotation, max_rotation]` degrees and translation by uniform
+///    `[-max_translation, max_translation]` pixels along each axis, resampled
+///    bilinearly; pixels that come from outside the image are 0
+/// 3. a brightness shift, one uniform offset in `[-brightness, brightness]`
+///    added to every value
+/// 4. independent `N(0, noise_std²)` noise on every value
+///
+/// Every transform is off (0 / `false`) by default.  Values are not clamped,
+/// so the transforms also work on standardized inputs; when
+/// `TrainConfig::input_scaling` is set they see the scaled values.
+///
+/// Use as `TrainConfig::image_augment`, where the randomness comes from the
+/// epoch's generator and is covered by `TrainConfig::seed`, or call
+/// `apply_batch_with_rng` directly.
+///
+/// # Fields
+/// - `width`, `height`, `channels` — image geometry; samples must have
+///   `width · height · channels` values
+/// - `interleaved` — `true` for pixel-interleaved layout (`R,G,B,R,G,B,…`,
+///   as `preprocess::image_bytes_to_rgb_input` produces), `false` for
+///   channel-major planes (index `c·H·W + y·W + x`, as `MaxPool2D` expects)
+/// - `horizontal_flip` — mirror half of the samples left to right
+/// - `max_rotation`    — largest rotation, in degrees
+/// - `max_translation` — largest shift, in pixels
+/// - `brightness`      — largest brightness offset
+/// - `noise_std`       — standard deviation of the additive noise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImageAugment {
+    pub width: usize,
+    pub height: usize,
+    #[serde(default = "default_channels")]
+    pub channels: usize,
+    #[serde(default)]
+    pub interleaved: bool,
+    #[serde(default)]
+    pub horizontal_flip: bool,
+    #[serde(default)]
+    pub max_rotation: f64,
+    #[serde(default)]
+    pub max_translation: f64,
+    #[serde(default)]
+    pub brightness: f64,
+    #[serde(default)]
+    pub noise_std: f64,
+}
+
+fn default_channels() -> usize {
+    1
+}
+
+impl ImageAugment {
+    /// Channel-major `channels × height × width` images with every
+    /// transform off; enable them with the `with_*` methods.
+    pub fn new(width: usize, height: usize, channels: usize) -> ImageAugment {
+        ImageAugment {
+            width,
+            height,
+            channels,
+            interleaved: false,
+            horizontal_flip: false,
+            max_rotation: 0.0,
+            max_translation: 0.0,
+            brightness: 0.0,
+            noise_std: 0.0,
+        }
+    }
+
+    /// Images shaped as a model declaring `input_type` expects them (one
+    /// channel for grayscale, three interleaved for RGB).  `None` for
+    /// non-image input types.
+    pub fn for_input_type(input_type: &InputType) -> Option<ImageAugment> {
+        match *input_type {
+            InputType::ImageGrayscale { width, height } => {
+                Some(ImageAugment::new(width as usize, height as usize, 1))
+            }
+            InputType::ImageRgb { width, height } => {
+                Some(ImageAugment::new(width as usize, height as usize, 3).with_interleaved(true))
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the pixel layout (see `interleaved`).
+    pub fn with_interleaved(mut self, interleaved: bool) -> ImageAugment {
+        self.interleaved = interleaved;
+        self
+    }
+
+    /// Mirrors half of the samples left to right.
+    pub fn with_horizontal_flip(mut self) -> ImageAugment {
+        self.horizontal_flip = true;
+        self
+    }
+
+    /// Rotates by up to `degrees` either way.
+    pub fn with_rotation(mut self, degrees: f64) -> ImageAugment {
+        self.max_rotation = degrees;
+        self
+    }
+
+    /// Shifts by up to `pixels` along each axis.
+    pub fn with_translation(mut self, pixels: f64) -> ImageAugment {
+        self.max_translation = pixels;
+        self
+    }
+
+    /// Adds one offset of up to `delta` either way to every value.
+    pub fn with_brightness(mut self, delta: f64) -> ImageAugment {
+        self.brightness = delta;
+        self
+    }
+
+    /// Adds `N(0, std_dev²)` noise to every value.
+    pub fn with_noise(mut self, std_dev: f64) -> ImageAugment {
+        self.noise_std = std_dev;
+        self
+    }
+
+    /// Number of values in one image.
+    pub fn len(&self) -> usize {
+        self.width * self.height * self.channels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Problems with the settings (empty geometry, negative or non-finite
+    /// magnitudes).  Returns one message per problem.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.is_empty() {
+            errors.push("image_augment width, height and channels must be at least 1".to_owned());
+        }
+        for (name, value) in [
+            ("max_rotation", self.max_rotation),
+            ("max_translation", self.max_translation),
+            ("brightness", self.brightness),
+            ("noise_std", self.noise_std),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                errors.push(format!("image_augment.{} must be non-negative, got {}", name, value));
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Augments one image in place with fresh entropy.
+    ///
+    /// # Panics
+    /// Panics if `input.len() != self.len()`.
+    pub fn apply(&self, input: &mut [f64]) {
+        self.apply_with_rng(input, &mut rand::thread_rng());
+    }
+
+    /// Same as `apply`, but reproducible: the transform is drawn from `seed`.
+    pub fn apply_seeded(&self, input: &mut [f64], seed: u64) {
+        self.apply_with_rng(input, &mut StdRng::seed_from_u64(seed));
+    }
+
+    /// Same as `apply`, drawing the transform from `rng`.
+    pub fn apply_with_rng<R: Rng + ?Sized>(&self, input: &mut [f64], rng: &mut R) {
+        assert_eq!(input.len(), self.len(), "image has {} values, expected {}", input.len(), self.len());
+        let flip = self.horizontal_flip && rng.gen::<bool>();
+        let angle = symmetric(rng, self.max_rotation).to_radians();
+        let dx = symmetric(rng, self.max_translation);
+        let dy = symmetric(rng, self.max_translation);
+        if flip || angle != 0.0 || dx != 0.0 || dy != 0.0 {
+            self.warp(input, flip, angle, dx, dy);
+        }
+
+        let shift = symmetric(rng, self.brightness);
+        if shift != 0.0 {
+            input.iter_mut().for_each(|x| *x += shift);
+        }
+        if self.noise_std > 0.0 {
+            for x in input.iter_mut() {
+                *x += self.noise_std * Matrix::sample_standard_normal(rng);
+            }
+        }
+    }
+
+    /// Augments every image of a mini-batch, each with its own transform.
+    pub fn apply_batch_with_rng<R: Rng + ?Sized>(&self, inputs: &mut [Vec<f64>], rng: &mut R) {
+        for input in inputs {
+            self.apply_with_rng(input, rng);
+        }
+    }
+
+    /// Index of channel `c` of pixel `(x, y)`.
+    fn index(&self, x: usize, y: usize, c: usize) -> usize {
+        if self.interleaved {
+            (y * self.width + x) * self.channels + c
+        } else {
+            (c * self.height + y) * self.width + x
+        }
+    }
+
+    /// Resamples `input` so that output pixel `p` takes the value at
+    /// `R(−angle)·(p − centre − (dx, dy)) + centre` of the original,
+    /// mirrored when `flip` is set.
+    fn warp(&self, input: &mut [f64], flip: bool, angle: f64, dx: f64, dy: f64) {
+        let source = input.to_vec();
+        let (w, h) = (self.width as f64, self.height as f64);
+        let (cx, cy) = ((w - 1.0) / 2.0, (h - 1.0) / 2.0);
+        let (sin, cos) = angle.sin_cos();
+        // Value at integer pixel (x, y), 0 outside the image.
+        let at = |x: isize, y: isize, c: usize| -> f64 {
+            if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+                0.0
+            } else {
+                source[self.index(x as usize, y as usize, c)]
+            }
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let px = x as f64 - cx - dx;
+                let py = y as f64 - cy - dy;
+                let mut sx = cos * px + sin * py + cx;
+                let sy = -sin * px + cos * py + cy;
+                if flip {
+                    sx = w - 1.0 - sx;
+                }
+                let (x0, y0) = (sx.floor(), sy.floor());
+                let (fx, fy) = (sx - x0, sy - y0);
+                let (x0, y0) = (x0 as isize, y0 as isize);
+                for c in 0..self.channels {
+                    let top = (1.0 - fx) * at(x0, y0, c) + fx * at(x0 + 1, y0, c);
+                    let bottom = (1.0 - fx) * at(x0, y0 + 1, c) + fx * at(x0 + 1, y0 + 1, c);
+                    input[self.index(x, y, c)] = (1.0 - fy) * top + fy * bottom;
+                }
+            }
+        }
+    }
+}
+
+/// Uniform draw from `[-limit, limit]`; 0 without touching `rng` when
+/// `limit` is 0.
+fn symmetric<R: Rng + ?Sized>(rng: &mut R, limit: f64) -> f64 {
+    if limit > 0.0 { rng.gen_range(-limit..=limit) } else { 0.0 }
+}
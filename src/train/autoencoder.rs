@@ -0,0 +1,96 @@
+use rand::seq::SliceRandom;
+
+use crate::loss::bce::BceLoss;
+use crate::loss::loss::Loss;
+use crate::network::autoencoder::Autoencoder;
+use crate::optim::optimizer::Optimizer;
+use crate::train::error::TrainError;
+use crate::train::gradients::GradientBuffers;
+use crate::train::loop_fn::{
+    accumulate_gradients, accumulate_logit_gradients, check_split, fuses_sigmoid_bce,
+};
+
+/// Trains an `Autoencoder` for one epoch to reconstruct `inputs`, with
+/// mini-batch gradient descent on its `loss`.
+///
+/// With tied weights the gradient of each decoder weight matrix is
+/// transposed onto its encoder partner, the encoder is updated with the
+/// sum, and the decoder weights are re-derived from it, so the pair stays
+/// exactly tied.  Biases are updated separately.
+///
+/// # Returns
+/// Mean reconstruction loss over the epoch.
+///
+/// # Panics
+/// Panics with the `TrainError` message if `inputs` is empty, a sample has
+/// the wrong width, or `batch_size == 0`; `try_train_autoencoder` returns
+/// these instead.
+pub fn train_autoencoder(
+    autoencoder: &mut Autoencoder,
+    inputs: &[Vec<f64>],
+    optimizer: &mut dyn Optimizer,
+    batch_size: usize,
+) -> f64 {
+    try_train_autoencoder(autoencoder, inputs, optimizer, batch_size)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as `train_autoencoder`, but invalid arguments are returned as a
+/// `TrainError` (checked before the network is touched).
+pub fn try_train_autoencoder(
+    autoencoder: &mut Autoencoder,
+    inputs: &[Vec<f64>],
+    optimizer: &mut dyn Optimizer,
+    batch_size: usize,
+) -> Result<f64, TrainError> {
+    if inputs.is_empty() {
+        return Err(TrainError::EmptyInputs { split: "train" });
+    }
+    check_split(&autoencoder.network, "train", inputs, inputs)?;
+    if batch_size == 0 {
+        return Err(TrainError::InvalidConfig("batch_size must be at least 1".into()));
+    }
+
+    let loss = autoencoder.loss;
+    let fused = fuses_sigmoid_bce(&autoencoder.network, loss);
+    let mut total_loss = 0.0;
+    let mut indices: Vec<usize> = (0..inputs.len()).collect();
+    indices.shuffle(&mut rand::thread_rng());
+
+    let mut acc_grads = GradientBuffers::for_network(&autoencoder.network);
+    for batch in indices.chunks(batch_size) {
+        let network = &mut autoencoder.network;
+        acc_grads.zero();
+        for &idx in batch {
+            let input = &inputs[idx];
+            let output = network.forward(input.clone());
+            total_loss += loss.loss(&output, input);
+            if fused {
+                accumulate_logit_gradients(network, input, BceLoss::logit_derivative(&output, input), &mut acc_grads)?;
+            } else {
+                accumulate_gradients(network, input, loss.derivative(&output, input), &mut acc_grads)?;
+            }
+        }
+
+        if autoencoder.tied {
+            for (enc, dec) in autoencoder.tied_pairs() {
+                let dec_grad = acc_grads[dec].0.transpose();
+                acc_grads[enc].0.try_add_assign_exact(&dec_grad)
+                    .map_err(|error| TrainError::GradientShape { layer: enc, error })?;
+                acc_grads[dec].0.data.fill(0.0);
+            }
+        }
+
+        let inv_batch = 1.0 / batch.len() as f64;
+        for (i, (w_acc, b_acc)) in acc_grads.iter_mut().enumerate() {
+            w_acc.scale_mut(inv_batch);
+            b_acc.scale_mut(inv_batch);
+            optimizer.step(i, autoencoder.network.layers[i].as_mut(), w_acc, b_acc);
+        }
+        if autoencoder.tied {
+            autoencoder.tie_weights();
+        }
+    }
+
+    Ok(total_loss / inputs.len() as f64)
+}
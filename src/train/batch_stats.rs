@@ -0,0 +1,18 @@
+use serde::{Serialize, Deserialize};
+
+/// Lightweight per-batch progress emitted by `train_loop`.
+///
+/// When a `batch_tx` channel is configured in `TrainConfig`, one value is
+/// sent after every mini-batch update, so long epochs (e.g. full MNIST) can
+/// show progress before their `EpochStats` arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BatchStats {
+    /// 1-based epoch number.
+    pub epoch: usize,
+    /// 1-based mini-batch number within the epoch.
+    pub batch: usize,
+    /// Number of mini-batches in this epoch.
+    pub total_batches: usize,
+    /// Mean (weighted) training loss over the samples of this batch.
+    pub loss: f64,
+}
@@ -0,0 +1,38 @@
+use crate::network::network::Network;
+use crate::optim::optimizer::Optimizer;
+use crate::train::epoch_stats::EpochStats;
+
+/// Hooks invoked by `train_loop_with_callbacks` at fixed points of a run.
+///
+/// Every method has an empty default body, so implementors only override the
+/// events they care about.  A callback can end training early through
+/// `should_stop` (see `EarlyStopping`).  Callbacks run on the training thread and should
+/// be cheap — `on_batch_end` in particular fires once per mini-batch.
+pub trait TrainCallback {
+    /// Called once before the first epoch.
+    fn on_train_start(&mut self, _total_epochs: usize) {}
+
+    /// Called at the top of every epoch with the 1-based epoch number and the
+    /// number of mini-batches it will run.
+    fn on_epoch_start(&mut self, _epoch: usize, _total_batches: usize) {}
+
+    /// Called after each mini-batch update; `batch` is 1-based.
+    fn on_batch_end(&mut self, _batch: usize, _total_batches: usize) {}
+
+    /// Called after every completed epoch, once its statistics are known and
+    /// before they are sent on the progress channel.
+    fn on_epoch_end(&mut self, _network: &Network, _stats: &EpochStats) {}
+
+    /// Called right after `on_epoch_end` with the optimizer as well, for
+    /// callbacks that need its state (e.g. `CheckpointWriter`).
+    fn on_epoch_state(&mut self, _network: &Network, _optimizer: &dyn Optimizer, _stats: &EpochStats) {}
+
+    /// Polled after the epoch-end hooks of every epoch; returning `true` ends
+    /// training after that epoch, exactly like setting `TrainConfig::stop_flag`.
+    fn should_stop(&self) -> bool {
+        false
+    }
+
+    /// Called once after the last epoch, including when training stops early.
+    fn on_train_end(&mut self, _network: &Network) {}
+}
@@ -0,0 +1,103 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::network::network::Network;
+use crate::optim::optimizer::Optimizer;
+use crate::optim::state::OptimizerState;
+use crate::train::callback::TrainCallback;
+use crate::train::epoch_stats::EpochStats;
+
+/// Everything needed to resume a training run exactly: the network weights,
+/// the optimizer with its accumulated state, and the number of completed
+/// epochs.
+///
+/// To resume, rebuild the optimizer with `OptimizerState::into_optimizer`
+/// and set `TrainConfig::initial_epoch` to `epoch`:
+///
+/// ```no_run
+/// use ferrite_nn::{Checkpoint, LossType, TrainConfig, train_loop};
+///
+/// let checkpoint = Checkpoint::load_json("run.ckpt.json").unwrap();
+/// let mut network = checkpoint.network;
+/// let mut optimizer = checkpoint.optimizer.into_optimizer();
+/// let mut config = TrainConfig::new(100, 32, LossType::CrossEntropy);
+/// config.initial_epoch = checkpoint.epoch;
+/// # let (inputs, labels): (Vec<Vec<f64>>, Vec<Vec<f64>>) = (vec![], vec![]);
+/// train_loop(&mut network, &(&inputs, &labels), None, optimizer.as_mut(), &config);
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub network: Network,
+    pub optimizer: OptimizerState,
+    /// Number of completed epochs.
+    pub epoch: usize,
+}
+
+impl Checkpoint {
+    /// Captures the current training state.  Returns `None` if the optimizer
+    /// does not support checkpointing (`Optimizer::state`).
+    pub fn capture(network: &Network, optimizer: &dyn Optimizer, epoch: usize) -> Option<Checkpoint> {
+        Some(Checkpoint { network: network.clone(), optimizer: optimizer.state()?, epoch })
+    }
+
+    /// Writes the checkpoint as JSON.  The file is written next to `path`
+    /// first and then renamed, so an interrupted write never leaves a
+    /// truncated checkpoint behind.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        {
+            let file = std::fs::File::create(&tmp)?;
+            let writer = io::BufWriter::new(file);
+            serde_json::to_writer(writer, self).map_err(io::Error::other)?;
+        }
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Reads a checkpoint written by `save_json`.
+    pub fn load_json(path: impl AsRef<Path>) -> io::Result<Checkpoint> {
+        let file = std::fs::File::open(path)?;
+        let reader = io::BufReader::new(file);
+        serde_json::from_reader(reader).map_err(io::Error::other)
+    }
+}
+
+/// Training callback that saves a `Checkpoint` every `every_epochs` epochs
+/// and after the final epoch, overwriting the same file each time.
+///
+/// Write failures do not interrupt training; the most recent one is kept in
+/// `last_error`.  Optimizers without `Optimizer::state` support are reported
+/// there as well.
+pub struct CheckpointWriter {
+    pub path: PathBuf,
+    pub every_epochs: usize,
+    pub last_error: Option<io::Error>,
+}
+
+impl CheckpointWriter {
+    pub fn new(path: impl Into<PathBuf>, every_epochs: usize) -> CheckpointWriter {
+        CheckpointWriter { path: path.into(), every_epochs: every_epochs.max(1), last_error: None }
+    }
+}
+
+impl TrainCallback for CheckpointWriter {
+    fn on_epoch_state(&mut self, network: &Network, optimizer: &dyn Optimizer, stats: &EpochStats) {
+        if !stats.epoch.is_multiple_of(self.every_epochs) && stats.epoch != stats.total_epochs {
+            return;
+        }
+        let result = match Checkpoint::capture(network, optimizer, stats.epoch) {
+            Some(checkpoint) => checkpoint.save_json(&self.path),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "optimizer does not support checkpointing",
+            )),
+        };
+        if let Err(e) = result {
+            self.last_error = Some(e);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use rand::seq::SliceRandom;
+
+use crate::activation::activation::ActivationFunction;
+use crate::loss::cross_entropy::CrossEntropyLoss;
+use crate::network::network::Network;
+use crate::network::spec::NetworkSpec;
+use crate::optim::optimizer::Optimizer;
+use crate::train::epoch_stats::EpochStats;
+use crate::train::gradients::GradientBuffers;
+use crate::train::layer_stats::LayerStats;
+use crate::train::loop_fn::{
+    accumulate_gradients, add_weight_penalty, apply_mean_gradients, argmax, epoch_rng,
+};
+use crate::train::train_config::TrainConfig;
+
+/// Small epsilon added inside log() to prevent log(0) = -inf.
+const EPS: f64 = 1e-12;
+
+/// Knowledge distillation (Hinton et al., 2015): trains a fresh network built
+/// from `student_spec` to mimic `teacher`, returning the trained student.
+///
+/// Both networks must end in a Softmax layer.  Each sample's loss is
+///
+/// ```text
+/// L = alpha · CE(y, p_s) + (1 − alpha) · T² · KL(q_T ‖ p_s,T)
+/// ```
+///
+/// where `p_s` is the student's output, `q_T` / `p_s,T` are the teacher's and
+/// student's distributions softened with temperature `T`, and `y` is the hard
+/// label.  The `T²` factor keeps the soft-target gradient magnitude
+/// independent of the temperature.  The combined gradient with respect to the
+/// student logits is `alpha·(p_s − y) + (1 − alpha)·T·(p_s,T − q_T)`.
+///
+/// # Arguments
+/// - `student_spec` — architecture of the (smaller) student network; takes
+///   as many inputs as the teacher's first layer
+/// - `teacher`      — trained teacher; only used for forward passes
+/// - `inputs`       — training samples
+/// - `labels`       — one-hot hard labels, same length as `inputs`
+/// - `optimizer`    — update rule (`Sgd`, `Adam`, …); carries the learning rate
+/// - `config`       — epochs, batch size, optional progress channel / stop
+///   flag, weight decay and seed (which also fixes the student's initial
+///   weights); the loss type and sample weighting fields are ignored
+/// - `temperature`  — softening temperature `T` (typically 2–10)
+/// - `alpha`        — weight of the hard-label term in `[0, 1]`
+///
+/// The emitted `EpochStats` carry the distillation loss and the student's
+/// hard-label training accuracy.
+///
+/// `inputs` are raw: the teacher's soft targets come from
+/// `Network::predict`, and the student trains on the inputs after the
+/// teacher's `polynomial_features` and `input_scaling`, which it records so
+/// that its own `predict` takes the same raw inputs.
+///
+/// # Panics
+/// Panics if `inputs` is empty, lengths mismatch, `batch_size == 0`, either
+/// network lacks a Softmax output, `temperature <= 0`, or `alpha` is outside
+/// `[0, 1]`.
+#[allow(clippy::too_many_arguments)]
+pub fn distill(
+    student_spec: &NetworkSpec,
+    teacher: &mut Network,
+    inputs: &[Vec<f64>],
+    labels: &[Vec<f64>],
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+    temperature: f64,
+    alpha: f64,
+) -> Network {
+    assert!(!inputs.is_empty(), "inputs must not be empty");
+    assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+    assert!(config.batch_size > 0, "batch_size must be at least 1");
+    assert!(temperature > 0.0, "temperature must be positive");
+    assert!((0.0..=1.0).contains(&alpha), "alpha must lie in [0, 1]");
+    assert!(
+        teacher.layers.last().and_then(|l| l.activation()) == Some(&ActivationFunction::Softmax),
+        "teacher must have a Softmax output layer"
+    );
+    assert!(
+        student_spec.layers.last().map(|l| &l.activation) == Some(&ActivationFunction::Softmax),
+        "student must have a Softmax output layer"
+    );
+
+    // Teacher targets never change — soften them once up front.
+    let soft_targets: Vec<Vec<f64>> = inputs.iter()
+        .map(|input| soften(&teacher.predict(input), temperature))
+        .collect();
+    let inputs: Vec<Vec<f64>> = inputs.iter()
+        .map(|input| {
+            let mut input = input.clone();
+            teacher.prepare_input(&mut input);
+            input
+        })
+        .collect();
+
+    let mut student = match config.seed {
+        Some(seed) => Network::from_spec_seeded(student_spec, seed),
+        None => Network::from_spec(student_spec),
+    };
+    let n = inputs.len();
+
+    let mut acc_grads = GradientBuffers::for_network(&student);
+    for epoch in 1..=config.epochs {
+        if let Some(ref flag) = config.stop_flag {
+            if flag.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+
+        let t_start = Instant::now();
+        let mut indices: Vec<usize> = (0..n).collect();
+        indices.shuffle(&mut epoch_rng(config.seed, epoch));
+
+        let mut total_loss = 0.0;
+        let mut correct = 0usize;
+
+        for batch_start in (0..n).step_by(config.batch_size) {
+            let batch_end = (batch_start + config.batch_size).min(n);
+            acc_grads.zero();
+
+            for &idx in &indices[batch_start..batch_end] {
+                let output = student.forward(inputs[idx].clone());
+                let soft_output = soften(&output, temperature);
+                let hard = &labels[idx];
+                let soft = &soft_targets[idx];
+
+                if argmax(&output) == argmax(hard) {
+                    correct += 1;
+                }
+                total_loss += alpha * CrossEntropyLoss::loss(&output, hard)
+                    + (1.0 - alpha) * temperature * temperature * kl_divergence(soft, &soft_output);
+
+                let delta: Vec<f64> = (0..output.len())
+                    .map(|k| {
+                        alpha * (output[k] - hard[k])
+                            + (1.0 - alpha) * temperature * (soft_output[k] - soft[k])
+                    })
+                    .collect();
+                accumulate_gradients(&student, &inputs[idx], delta, &mut acc_grads)
+                    .unwrap_or_else(|e| panic!("{}", e));
+            }
+
+            let batch_size = (batch_end - batch_start) as f64;
+            add_weight_penalty(&student, &mut acc_grads, config.l1, config.l2, batch_size);
+            apply_mean_gradients(&mut student, &mut acc_grads, batch_size, optimizer);
+        }
+
+        let stats = EpochStats {
+            epoch,
+            total_epochs: config.epochs,
+            train_loss: total_loss / n as f64,
+            val_loss: None,
+            train_accuracy: Some(correct as f64 / n as f64),
+            val_accuracy: None,
+            elapsed_ms: t_start.elapsed().as_millis() as u64,
+            layer_stats: if config.record_layer_stats {
+                Some(LayerStats::for_network(&student))
+            } else {
+                None
+            },
+            layer_timings: None,
+            layer_norms: None,
+        };
+        if let Some(ref tx) = config.progress_tx {
+            if tx.send(stats).is_err() {
+                break;
+            }
+        }
+    }
+
+    if student.metadata.is_none() {
+        student.metadata = teacher.metadata.clone();
+    }
+    if let Some(expansion) = teacher.polynomial_features() {
+        student.set_polynomial_features(expansion.clone());
+    }
+    if let Some(scaling) = teacher.input_scaling() {
+        student.set_input_scaling(scaling.clone());
+    }
+    student
+}
+
+/// Re-normalizes a probability vector at temperature `t`:
+/// `softmax(log(p) / t)`, which equals `softmax(z / t)` for the logits `z`
+/// that produced `p`.
+fn soften(probs: &[f64], t: f64) -> Vec<f64> {
+    let scaled: Vec<f64> = probs.iter().map(|&p| (p + EPS).ln() / t).collect();
+    let max = scaled.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = scaled.iter().map(|&v| (v - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    exps.iter().map(|&e| e / sum).collect()
+}
+
+/// KL(p ‖ q) = Σ p·ln(p / q).
+fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
+    p.iter().zip(q.iter())
+        .map(|(&pi, &qi)| if pi > 0.0 { pi * ((pi + EPS) / (qi + EPS)).ln() } else { 0.0 })
+        .sum()
+}
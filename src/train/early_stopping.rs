@@ -0,0 +1,80 @@
+use crate::network::network::Network;
+use crate::train::callback::TrainCallback;
+use crate::train::epoch_stats::EpochStats;
+
+/// Callback that ends training once the monitored loss stops improving.
+///
+/// Watches the validation loss, or the training loss for runs without a
+/// validation set.  An epoch counts as an improvement when the loss drops
+/// below the best value so far by more than `min_delta`; after `patience`
+/// epochs without one, `should_stop` returns `true`.
+///
+/// With `restore_best` set, the network weights of the best epoch are kept
+/// and can be taken back with `take_best` once training returns.
+///
+/// # Fields
+/// - `patience`     — consecutive epochs without improvement after which
+///   training stops (0 is treated as 1)
+/// - `min_delta`    — minimum decrease that counts as an improvement
+/// - `restore_best` — keep a copy of the network from the best epoch
+pub struct EarlyStopping {
+    pub patience: usize,
+    pub min_delta: f64,
+    pub restore_best: bool,
+    best: Option<(usize, f64)>,
+    stale_epochs: usize,
+    best_network: Option<Network>,
+}
+
+impl EarlyStopping {
+    pub fn new(patience: usize) -> EarlyStopping {
+        EarlyStopping {
+            patience,
+            min_delta: 0.0,
+            restore_best: false,
+            best: None,
+            stale_epochs: 0,
+            best_network: None,
+        }
+    }
+
+    /// `(epoch, loss)` of the best epoch seen so far.
+    pub fn best(&self) -> Option<(usize, f64)> {
+        self.best
+    }
+
+    /// The network as it was after the best epoch (only recorded when
+    /// `restore_best` is set).  Leaves `None` behind.
+    pub fn take_best(&mut self) -> Option<Network> {
+        self.best_network.take()
+    }
+}
+
+impl TrainCallback for EarlyStopping {
+    fn on_train_start(&mut self, _total_epochs: usize) {
+        self.best = None;
+        self.stale_epochs = 0;
+        self.best_network = None;
+    }
+
+    fn on_epoch_end(&mut self, network: &Network, stats: &EpochStats) {
+        let loss = stats.val_loss.unwrap_or(stats.train_loss);
+        let improved = match self.best {
+            Some((_, best)) => loss < best - self.min_delta,
+            None => loss.is_finite(),
+        };
+        if improved {
+            self.best = Some((stats.epoch, loss));
+            self.stale_epochs = 0;
+            if self.restore_best {
+                self.best_network = Some(network.clone());
+            }
+        } else {
+            self.stale_epochs += 1;
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.stale_epochs >= self.patience.max(1)
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Serialize, Deserialize};
 
+use crate::train::layer_stats::LayerStats;
+use crate::train::layer_norms::LayerNorms;
+use crate::train::layer_timing::LayerTiming;
+
 /// Per-epoch training statistics emitted by `train_loop`.
 ///
 /// When a `progress_tx` channel is configured in `TrainConfig`, the training
@@ -16,11 +20,26 @@ pub struct EpochStats {
     pub train_loss: f64,
     /// Mean validation loss, if a validation set was provided.
     pub val_loss: Option<f64>,
-    /// Training accuracy as a fraction in [0, 1]; only set for CrossEntropy runs.
+    /// Training accuracy as a fraction in [0, 1]; set for `CrossEntropy`
+    /// runs (argmax of output vs. label) and `BinaryCrossEntropy` runs
+    /// (fraction of outputs on the right side of 0.5, so multi-label outputs
+    /// are scored per label).  `None` for the regression losses.
     pub train_accuracy: Option<f64>,
-    /// Validation accuracy as a fraction in [0, 1]; only set for CrossEntropy runs
-    /// when a validation set is available.
+    /// Validation accuracy, measured like `train_accuracy`; only set when a
+    /// validation set is available.
     pub val_accuracy: Option<f64>,
     /// Wall-clock duration of this single epoch in milliseconds.
     pub elapsed_ms: u64,
+    /// Per-layer weight/bias statistics; only set when
+    /// `TrainConfig::record_layer_stats` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_stats: Option<Vec<LayerStats>>,
+    /// Per-layer forward/backward time in the training passes; only set
+    /// when `TrainConfig::record_layer_timings` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_timings: Option<Vec<LayerTiming>>,
+    /// Per-layer weight norm, gradient norm and update ratio; only set when
+    /// `TrainConfig::record_layer_norms` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_norms: Option<Vec<LayerNorms>>,
 }
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::math::matrix::ShapeError;
+
+/// Why `try_train_loop` / `try_train_network` refused to train or stopped
+/// without finishing.
+///
+/// The argument variants name the offending split (`"train"` or
+/// `"validation"`) and are reported before any weight is touched, except
+/// that `try_train_loop` checks only the first sample of a `Dataset` up
+/// front: `InputShape` / `LabelShape` for a later sample is reported when
+/// the sample is first fetched, possibly after some batches were applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrainError {
+    /// The split has no samples.
+    EmptyInputs { split: &'static str },
+    /// Inputs and labels of a split differ in length.
+    LengthMismatch {
+        split: &'static str,
+        inputs: usize,
+        labels: usize,
+    },
+    /// A sample's width does not match the network's input size.
+    InputShape {
+        split: &'static str,
+        /// 0-based sample index.
+        sample: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A label's width does not match the network's output size.
+    LabelShape {
+        split: &'static str,
+        /// 0-based sample index.
+        sample: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A `TrainConfig` setting is unusable with this data (zero batch size,
+    /// `Reduction::None`, weight vectors of the wrong length, a schedule
+    /// index out of range, …).
+    InvalidConfig(String),
+    /// A NaN or infinite value appeared during training and
+    /// `TrainConfig::non_finite` is `NonFinitePolicy::Abort`.  The network
+    /// holds the weights from before the offending batch.
+    NonFinite {
+        /// 1-based epoch number.
+        epoch: usize,
+        /// 1-based mini-batch number within the epoch.
+        batch: usize,
+        source: NonFiniteSource,
+    },
+    /// Layer `layer` (0-based) returned a gradient whose shape does not match
+    /// its parameters — a bug in that layer's `LayerOps::backward`.  The
+    /// network holds the weights from before the offending batch.
+    GradientShape { layer: usize, error: ShapeError },
+}
+
+/// Where a non-finite value was detected.
+///
+/// - `Loss`     — the loss of a training sample
+/// - `Gradient` — the weight or bias gradient of layer `layer` (0-based)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFiniteSource {
+    Loss,
+    Gradient { layer: usize },
+}
+
+/// What `train_loop` does when a batch produces a NaN or infinite loss or
+/// gradient.
+///
+/// - `Abort`     — stop and report `TrainError::NonFinite` (default)
+/// - `SkipBatch` — discard that batch's update and loss, then carry on;
+///   useful for rare bad samples, but hides a diverging run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    #[default]
+    Abort,
+    SkipBatch,
+}
+
+impl fmt::Display for TrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrainError::EmptyInputs { split } => write!(f, "{} inputs must not be empty", split),
+            TrainError::LengthMismatch { split, inputs, labels } => write!(
+                f,
+                "{} inputs and labels must have equal length ({} inputs, {} labels)",
+                split, inputs, labels
+            ),
+            TrainError::InputShape { split, sample, expected, actual } => write!(
+                f,
+                "{} sample {} has {} features but the network expects {}",
+                split, sample, actual, expected
+            ),
+            TrainError::LabelShape { split, sample, expected, actual } => write!(
+                f,
+                "{} label {} has {} values but the network outputs {}",
+                split, sample, actual, expected
+            ),
+            TrainError::InvalidConfig(msg) => write!(f, "invalid training config: {}", msg),
+            TrainError::NonFinite { epoch, batch, source } => {
+                let what = match source {
+                    NonFiniteSource::Loss => "loss".to_owned(),
+                    NonFiniteSource::Gradient { layer } => format!("gradient of layer {}", layer),
+                };
+                write!(
+                    f,
+                    "non-finite {} at epoch {}, batch {}; training diverged — try a lower \
+                     learning rate, normalized inputs, or a loss that matches the output activation",
+                    what, epoch, batch
+                )
+            }
+            TrainError::GradientShape { layer, error } => {
+                write!(f, "gradient of layer {} does not fit its parameters: {}", layer, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TrainError {}
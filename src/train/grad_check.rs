@@ -0,0 +1,137 @@
+//! Numerical gradient checking.
+//!
+//! `grad_check` compares the gradients backpropagation computes for one
+//! sample against central-difference estimates, parameter by parameter.  Run
+//! it on a small network after writing a new layer type or loss: a
+//! backward pass that disagrees with the forward pass shows up as a large
+//! relative error in the offending layer.
+
+use serde::{Serialize, Deserialize};
+
+use crate::loss::loss::Loss;
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+use crate::train::error::TrainError;
+use crate::train::gradients::GradientBuffers;
+use crate::train::loop_fn::accumulate_gradients;
+
+/// Gradients smaller than this in magnitude are compared absolutely rather
+/// than relatively, so values that are zero up to rounding do not report
+/// huge relative errors.
+const RELATIVE_ERROR_FLOOR: f64 = 1e-8;
+
+/// Agreement of one layer's analytic and numerical gradients.
+///
+/// Each error is the largest `|analytic − numerical| / max(|analytic|,
+/// |numerical|)` over the layer's weights or biases (0 for a layer without
+/// parameters).  With `eps` around `1e-5`, a correct backward pass usually
+/// stays below `1e-6`; ReLU-like kinks crossed by the `±eps` step can
+/// produce isolated larger values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradCheck {
+    /// 0-based layer index.
+    pub layer: usize,
+    /// The layer's name, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Largest relative error over the weights.
+    pub weights_error: f64,
+    /// Largest relative error over the biases.
+    pub biases_error: f64,
+}
+
+impl GradCheck {
+    /// The larger of `weights_error` and `biases_error`.
+    pub fn max_error(&self) -> f64 {
+        self.weights_error.max(self.biases_error)
+    }
+}
+
+/// Checks backpropagation through `network` for one `(input, label)`
+/// sample under `loss`.
+///
+/// The analytic gradients come from the same backward pass `train_loop`
+/// uses.  Every weight and bias is then nudged by `±eps` in turn and the
+/// loss re-evaluated, giving the central difference
+/// `(L(w + eps) − L(w − eps)) / (2 · eps)`.  That is two forward passes per
+/// parameter, so keep the network small.  Every parameter is restored
+/// afterwards.
+///
+/// As in training, `input` is fed to the first layer as given (no
+/// polynomial expansion or input scaling), and a Softmax output expects a
+/// loss whose derivative is taken w.r.t. the logits (see `Loss::derivative`).
+/// `loss` and `derivative` must follow the same reduction: the built-in
+/// binary cross-entropy averages its loss over the outputs but sums its
+/// gradient, so with `k > 1` outputs every error comes out near `1 − 1/k`;
+/// check BCE networks with a single output.
+///
+/// Returns one `GradCheck` per layer, in order.
+///
+/// # Errors
+/// `TrainError::GradientShape` if a layer's backward pass returns gradients
+/// that do not match its parameters.
+///
+/// # Panics
+/// Panics if `eps` is not positive and finite, or if `input` does not fit
+/// the network.
+pub fn grad_check(
+    network: &mut Network,
+    input: &[f64],
+    label: &[f64],
+    loss: &dyn Loss,
+    eps: f64,
+) -> Result<Vec<GradCheck>, TrainError> {
+    assert!(eps > 0.0 && eps.is_finite(), "eps must be positive and finite, got {}", eps);
+
+    let output = network.forward(input.to_vec());
+    let mut grads = GradientBuffers::for_network(network);
+    accumulate_gradients(network, input, loss.derivative(&output, label), &mut grads)?;
+
+    let checks = grads.iter().enumerate()
+        .map(|(layer, (weights_grad, biases_grad))| GradCheck {
+            layer,
+            name: network.layers[layer].name().map(str::to_owned),
+            weights_error: max_relative_error(network, input, label, loss, eps, (layer, false), weights_grad),
+            biases_error: max_relative_error(network, input, label, loss, eps, (layer, true), biases_grad),
+        })
+        .collect();
+    Ok(checks)
+}
+
+// ---------------------------------------------------------------------------
+// Private helpers
+// ---------------------------------------------------------------------------
+
+/// Largest relative error between `analytic` and the central differences of
+/// the parameters it belongs to: the weights of layer `param.0`, or its
+/// biases when `param.1` is set.
+fn max_relative_error(
+    network: &mut Network,
+    input: &[f64],
+    label: &[f64],
+    loss: &dyn Loss,
+    eps: f64,
+    (layer, biases): (usize, bool),
+    analytic: &Matrix,
+) -> f64 {
+    let mut max_error = 0.0f64;
+    for (k, &analytic) in analytic.data.iter().enumerate() {
+        let original = *parameter(network, layer, biases, k);
+        *parameter(network, layer, biases, k) = original + eps;
+        let plus = loss.loss(&network.forward(input.to_vec()), label);
+        *parameter(network, layer, biases, k) = original - eps;
+        let minus = loss.loss(&network.forward(input.to_vec()), label);
+        *parameter(network, layer, biases, k) = original;
+
+        let numerical = (plus - minus) / (2.0 * eps);
+        let scale = analytic.abs().max(numerical.abs()).max(RELATIVE_ERROR_FLOOR);
+        max_error = max_error.max((analytic - numerical).abs() / scale);
+    }
+    max_error
+}
+
+/// The `k`-th weight (or bias) of layer `layer`.
+fn parameter(network: &mut Network, layer: usize, biases: bool, k: usize) -> &mut f64 {
+    let (weights, bias) = network.layers[layer].params_mut();
+    if biases { &mut bias.data[k] } else { &mut weights.data[k] }
+}
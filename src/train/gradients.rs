@@ -0,0 +1,66 @@
+//! Gradient accumulators reused across mini-batches.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+#[cfg(feature = "parallel")]
+use crate::train::error::TrainError;
+
+/// One `(weights_grad, biases_grad)` accumulator pair per layer, shaped like
+/// the network's parameters.
+///
+/// The training loops allocate one set per run and `zero` it in place
+/// before every mini-batch, rather than allocating fresh matrices per batch.
+/// Dereferences to the per-layer slice the backpropagation helpers
+/// (`accumulate_gradients` and friends) add into.
+pub(crate) struct GradientBuffers {
+    layers: Vec<(Matrix, Matrix)>,
+}
+
+impl GradientBuffers {
+    /// Zeroed accumulators for every layer of `network`.
+    pub(crate) fn for_network(network: &Network) -> GradientBuffers {
+        let layers = network.layers.iter()
+            .map(|layer| {
+                let (weights, biases) = layer.params();
+                (Matrix::zeros(weights.rows, weights.cols), Matrix::zeros(biases.rows, biases.cols))
+            })
+            .collect();
+        GradientBuffers { layers }
+    }
+
+    /// Resets every accumulator to 0 without reallocating.
+    pub(crate) fn zero(&mut self) {
+        for (weights, biases) in &mut self.layers {
+            weights.data.fill(0.0);
+            biases.data.fill(0.0);
+        }
+    }
+
+    /// Adds `other`'s gradients layer by layer, e.g. to reduce the partial
+    /// sums of several workers.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn try_add_assign(&mut self, other: &GradientBuffers) -> Result<(), TrainError> {
+        for (layer, (sum, (w, b))) in self.layers.iter_mut().zip(&other.layers).enumerate() {
+            sum.0.try_add_assign_exact(w)
+                .and_then(|()| sum.1.try_add_assign_exact(b))
+                .map_err(|error| TrainError::GradientShape { layer, error })?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for GradientBuffers {
+    type Target = [(Matrix, Matrix)];
+
+    fn deref(&self) -> &[(Matrix, Matrix)] {
+        &self.layers
+    }
+}
+
+impl DerefMut for GradientBuffers {
+    fn deref_mut(&mut self) -> &mut [(Matrix, Matrix)] {
+        &mut self.layers
+    }
+}
@@ -0,0 +1,160 @@
+use rand::seq::SliceRandom;
+
+use crate::loss::bce::BceLoss;
+use crate::loss::loss::Loss;
+use crate::network::graph::Graph;
+use crate::optim::optimizer::Optimizer;
+use crate::train::error::TrainError;
+use crate::train::gradients::GradientBuffers;
+use crate::train::loop_fn::{accumulate_gradients, backpropagate_to_input, fuses_sigmoid_bce};
+
+/// Trains a `Graph` for one epoch with mini-batch gradient descent.
+///
+/// Every head's loss is backpropagated through the head, summed (scaled by
+/// the head's `weight`) at the trunk's output, and split back across the
+/// branches.  The optimizer sees one layer index per layer across the whole
+/// graph, numbered in `Graph::networks` order.
+///
+/// # Arguments
+/// * `graph`     — the graph to train (mutated in place)
+/// * `inputs`    — per sample, one input vector per branch
+/// * `targets`   — per sample, one target vector per head
+/// * `optimizer` — update rule (`Sgd`, `Adam`, …)
+/// * `batch_size` — samples per mini-batch
+///
+/// # Returns
+/// Mean loss of each head over the epoch (unweighted), in head order.
+///
+/// # Panics
+/// Panics with the `TrainError` message on invalid arguments;
+/// `try_train_graph` returns them instead.
+pub fn train_graph(
+    graph: &mut Graph,
+    inputs: &[Vec<Vec<f64>>],
+    targets: &[Vec<Vec<f64>>],
+    optimizer: &mut dyn Optimizer,
+    batch_size: usize,
+) -> Vec<f64> {
+    try_train_graph(graph, inputs, targets, optimizer, batch_size)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as `train_graph`, but invalid arguments are returned as a
+/// `TrainError` (checked before the graph is touched).
+pub fn try_train_graph(
+    graph: &mut Graph,
+    inputs: &[Vec<Vec<f64>>],
+    targets: &[Vec<Vec<f64>>],
+    optimizer: &mut dyn Optimizer,
+    batch_size: usize,
+) -> Result<Vec<f64>, TrainError> {
+    check_samples(graph, inputs, targets)?;
+    if batch_size == 0 {
+        return Err(TrainError::InvalidConfig("batch_size must be at least 1".into()));
+    }
+
+    let n = inputs.len();
+    let mut total_loss = vec![0.0; graph.heads.len()];
+    let mut indices: Vec<usize> = (0..n).collect();
+    indices.shuffle(&mut rand::thread_rng());
+
+    let mut acc_grads: Vec<GradientBuffers> = graph.networks().map(GradientBuffers::for_network).collect();
+    for batch in indices.chunks(batch_size) {
+        acc_grads.iter_mut().for_each(GradientBuffers::zero);
+        for &idx in batch {
+            let losses = accumulate_sample(graph, &inputs[idx], &targets[idx], &mut acc_grads)?;
+            total_loss.iter_mut().zip(losses).for_each(|(t, l)| *t += l);
+        }
+
+        let inv_batch = 1.0 / batch.len() as f64;
+        let mut layer_idx = 0;
+        for (network, grads) in graph.networks_mut().zip(acc_grads.iter_mut()) {
+            for (layer, (w_acc, b_acc)) in network.layers.iter_mut().zip(grads.iter_mut()) {
+                w_acc.scale_mut(inv_batch);
+                b_acc.scale_mut(inv_batch);
+                optimizer.step(layer_idx, layer.as_mut(), w_acc, b_acc);
+                layer_idx += 1;
+            }
+        }
+    }
+
+    Ok(total_loss.into_iter().map(|l| l / n as f64).collect())
+}
+
+/// Forward and backward pass for one sample; adds its gradients to
+/// `acc_grads` (one entry per network, in `Graph::networks` order) and
+/// returns each head's loss.
+fn accumulate_sample(
+    graph: &mut Graph,
+    inputs: &[Vec<f64>],
+    targets: &[Vec<f64>],
+    acc_grads: &mut [GradientBuffers],
+) -> Result<Vec<f64>, TrainError> {
+    let branch_outputs: Vec<Vec<f64>> = graph.branches.iter_mut()
+        .zip(inputs)
+        .map(|(branch, input)| branch.forward(input.clone()))
+        .collect();
+    let joined = branch_outputs.concat();
+    let shared = graph.trunk.forward(joined.clone());
+
+    let (branch_grads, rest) = acc_grads.split_at_mut(graph.branches.len());
+    let (trunk_grads, head_grads) = rest.split_first_mut().expect("graph has a trunk");
+
+    let mut losses = Vec::with_capacity(graph.heads.len());
+    let mut shared_delta = vec![0.0; shared.len()];
+    for ((head, target), grads) in graph.heads.iter_mut().zip(targets).zip(head_grads.iter_mut()) {
+        let output = head.network.forward(shared.clone());
+        losses.push(head.loss.loss(&output, target));
+
+        let fused = fuses_sigmoid_bce(&head.network, head.loss);
+        let mut delta = if fused {
+            BceLoss::logit_derivative(&output, target)
+        } else {
+            head.loss.derivative(&output, target)
+        };
+        delta.iter_mut().for_each(|d| *d *= head.weight);
+
+        let input_delta = backpropagate_to_input(&head.network, &shared, delta, fused, grads)?;
+        shared_delta.iter_mut().zip(input_delta).for_each(|(s, d)| *s += d);
+    }
+
+    let joined_delta = backpropagate_to_input(&graph.trunk, &joined, shared_delta, false, trunk_grads)?;
+    let mut offset = 0;
+    for ((branch, input), (output, grads)) in graph.branches.iter().zip(inputs).zip(branch_outputs.iter().zip(branch_grads)) {
+        let delta = joined_delta[offset..offset + output.len()].to_vec();
+        offset += output.len();
+        accumulate_gradients(branch, input, delta, grads)?;
+    }
+    Ok(losses)
+}
+
+/// Validates the sample counts and, where the networks fix them, the input
+/// and target widths.
+fn check_samples(graph: &Graph, inputs: &[Vec<Vec<f64>>], targets: &[Vec<Vec<f64>>]) -> Result<(), TrainError> {
+    let split = "train";
+    if inputs.is_empty() {
+        return Err(TrainError::EmptyInputs { split });
+    }
+    if inputs.len() != targets.len() {
+        return Err(TrainError::LengthMismatch { split, inputs: inputs.len(), labels: targets.len() });
+    }
+    for (sample, (sample_inputs, sample_targets)) in inputs.iter().zip(targets).enumerate() {
+        if sample_inputs.len() != graph.branches.len() || sample_targets.len() != graph.heads.len() {
+            return Err(TrainError::InvalidConfig(format!(
+                "sample {} has {} inputs and {} targets, but the graph has {} branches and {} heads",
+                sample, sample_inputs.len(), sample_targets.len(), graph.branches.len(), graph.heads.len()
+            )));
+        }
+        for (branch, input) in graph.branches.iter().zip(sample_inputs) {
+            if let Some(expected) = branch.layers.first().map(|l| l.input_size()).filter(|&w| w != input.len()) {
+                return Err(TrainError::InputShape { split, sample, expected, actual: input.len() });
+            }
+        }
+        for (head, target) in graph.heads.iter().zip(sample_targets) {
+            if let Some(expected) = head.network.layers.last().map(|l| l.output_size()).filter(|&w| w != target.len()) {
+                return Err(TrainError::LabelShape { split, sample, expected, actual: target.len() });
+            }
+        }
+    }
+    Ok(())
+}
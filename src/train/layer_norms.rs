@@ -0,0 +1,103 @@
+use serde::{Serialize, Deserialize};
+
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+
+/// Norm diagnostics of one layer's weights over an epoch.
+///
+/// Collected when `TrainConfig::record_layer_norms` is enabled and attached
+/// to `EpochStats::layer_norms`.  All norms are Frobenius (L2) norms over
+/// the weight matrix; biases are left out.  A `gradient_norm` that collapses
+/// towards zero while other layers still learn points at dead ReLUs; one
+/// that keeps growing (or a large `max_gradient_norm`) at exploding
+/// gradients.  An `update_ratio` around `1e-3` is the usual healthy range:
+/// much smaller and the layer barely moves, much larger and it is unstable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerNorms {
+    /// 0-based layer index.
+    pub layer: usize,
+    /// The layer's name, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `‖W‖` at the end of the epoch.
+    pub weight_norm: f64,
+    /// Mean over the epoch's applied mini-batches of `‖∂L/∂W‖`, the
+    /// batch-averaged gradient handed to the optimizer (including the
+    /// L1/L2 penalty).
+    pub gradient_norm: f64,
+    /// Largest of those per-batch gradient norms.
+    pub max_gradient_norm: f64,
+    /// Mean over the applied mini-batches of `‖ΔW‖ / ‖W‖`, the size of the
+    /// optimizer step relative to the weights before it (0 while the weights
+    /// are all zero).
+    pub update_ratio: f64,
+}
+
+/// Running per-layer norm sums of one epoch.  `before_step` and
+/// `after_step` bracket every optimizer step that is applied.
+pub(crate) struct NormTracker {
+    /// Weights before the current step; the buffers are reused.
+    previous: Vec<Vec<f64>>,
+    gradient_sum: Vec<f64>,
+    gradient_max: Vec<f64>,
+    ratio_sum: Vec<f64>,
+    steps: usize,
+}
+
+impl NormTracker {
+    pub(crate) fn new(network: &Network) -> NormTracker {
+        let n = network.layers.len();
+        NormTracker {
+            previous: network.layers.iter().map(|layer| layer.params().0.data.clone()).collect(),
+            gradient_sum: vec![0.0; n],
+            gradient_max: vec![0.0; n],
+            ratio_sum: vec![0.0; n],
+            steps: 0,
+        }
+    }
+
+    /// Records the gradient norms of a batch whose summed gradients are
+    /// `acc_grads` (divided by `batch_size`, as the step will), and saves
+    /// the current weights.
+    pub(crate) fn before_step(&mut self, network: &Network, acc_grads: &[(Matrix, Matrix)], batch_size: f64) {
+        for (i, (layer, (weights_grad, _))) in network.layers.iter().zip(acc_grads).enumerate() {
+            let norm = l2_norm(&weights_grad.data) / batch_size;
+            self.gradient_sum[i] += norm;
+            self.gradient_max[i] = self.gradient_max[i].max(norm);
+            self.previous[i].copy_from_slice(&layer.params().0.data);
+        }
+    }
+
+    /// Records how far the step moved every layer's weights.
+    pub(crate) fn after_step(&mut self, network: &Network) {
+        for (i, layer) in network.layers.iter().enumerate() {
+            let before = &self.previous[i];
+            let weights = &layer.params().0.data;
+            let step = weights.iter().zip(before).map(|(w, b)| (w - b) * (w - b)).sum::<f64>().sqrt();
+            let norm = l2_norm(before);
+            if norm > 0.0 {
+                self.ratio_sum[i] += step / norm;
+            }
+        }
+        self.steps += 1;
+    }
+
+    /// The epoch's diagnostics, one `LayerNorms` per layer of `network`.
+    pub(crate) fn norms(&self, network: &Network) -> Vec<LayerNorms> {
+        let steps = self.steps.max(1) as f64;
+        network.layers.iter().enumerate()
+            .map(|(i, layer)| LayerNorms {
+                layer: i,
+                name: layer.name().map(str::to_owned),
+                weight_norm: l2_norm(&layer.params().0.data),
+                gradient_norm: self.gradient_sum[i] / steps,
+                max_gradient_norm: self.gradient_max[i],
+                update_ratio: self.ratio_sum[i] / steps,
+            })
+            .collect()
+    }
+}
+
+fn l2_norm(values: &[f64]) -> f64 {
+    values.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
@@ -0,0 +1,64 @@
+use serde::{Serialize, Deserialize};
+
+use crate::layers::ops::LayerOps;
+use crate::math::matrix::Matrix;
+use crate::network::network::Network;
+
+/// Summary statistics of one layer's parameters at the end of an epoch.
+///
+/// Collected when `TrainConfig::record_layer_stats` is enabled and attached to
+/// `EpochStats::layer_stats`.  A layer whose weight std collapses towards zero
+/// (or whose max-abs keeps growing) is usually dead or diverging.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerStats {
+    /// 0-based layer index.
+    pub layer: usize,
+    /// The layer's name, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub weight_mean: f64,
+    pub weight_std: f64,
+    pub weight_max_abs: f64,
+    pub bias_mean: f64,
+    pub bias_std: f64,
+    pub bias_max_abs: f64,
+}
+
+impl LayerStats {
+    /// Computes the statistics for `layer`, tagging them with `index`.
+    pub fn from_layer(index: usize, layer: &dyn LayerOps) -> LayerStats {
+        let (weights, biases) = layer.params();
+        let (weight_mean, weight_std, weight_max_abs) = summarize(weights);
+        let (bias_mean, bias_std, bias_max_abs) = summarize(biases);
+        LayerStats {
+            layer: index,
+            name: layer.name().map(str::to_owned),
+            weight_mean,
+            weight_std,
+            weight_max_abs,
+            bias_mean,
+            bias_std,
+            bias_max_abs,
+        }
+    }
+
+    /// Computes the statistics for every layer of `network`, in order.
+    pub fn for_network(network: &Network) -> Vec<LayerStats> {
+        network.layers.iter().enumerate()
+            .map(|(i, layer)| LayerStats::from_layer(i, layer.as_ref()))
+            .collect()
+    }
+}
+
+/// Returns `(mean, population std, max |x|)` over all matrix entries.
+fn summarize(m: &Matrix) -> (f64, f64, f64) {
+    let n = (m.rows * m.cols) as f64;
+    if n == 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+    let values = || m.data.iter();
+    let mean = values().sum::<f64>() / n;
+    let var = values().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let max_abs = values().fold(0.0f64, |acc, x| acc.max(x.abs()));
+    (mean, var.sqrt(), max_abs)
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Deserialize};
+
+use crate::network::network::Network;
+
+/// Time one layer spent in the training passes of an epoch.
+///
+/// Collected when `TrainConfig::record_layer_timings` is enabled and attached
+/// to `EpochStats::layer_timings`.  Only the forward and backward passes over
+/// the training samples are measured; validation, accuracy and optimizer
+/// steps are not.  With the `parallel` feature the times of all worker
+/// threads are summed, so they add up to CPU time rather than wall-clock
+/// time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerTiming {
+    /// 0-based layer index.
+    pub layer: usize,
+    /// The layer's name, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The layer's type (`LayerOps::kind`, e.g. `"dense"`).
+    pub kind: String,
+    /// Milliseconds spent in `forward`.
+    pub forward_ms: f64,
+    /// Milliseconds spent backpropagating through the layer.
+    pub backward_ms: f64,
+}
+
+impl LayerTiming {
+    /// Forward plus backward time, in milliseconds.
+    pub fn total_ms(&self) -> f64 {
+        self.forward_ms + self.backward_ms
+    }
+}
+
+/// Running per-layer forward/backward totals of one epoch.
+pub(crate) struct LayerTimer {
+    forward: Vec<Duration>,
+    backward: Vec<Duration>,
+}
+
+impl LayerTimer {
+    /// Zeroed totals for `n_layers` layers.
+    pub(crate) fn new(n_layers: usize) -> LayerTimer {
+        LayerTimer { forward: vec![Duration::ZERO; n_layers], backward: vec![Duration::ZERO; n_layers] }
+    }
+
+    /// `Network::forward`, timing every layer.
+    pub(crate) fn forward(&mut self, network: &mut Network, input: Vec<f64>) -> Vec<f64> {
+        let mut current = input;
+        for (layer, total) in network.layers.iter_mut().zip(&mut self.forward) {
+            let start = Instant::now();
+            current = layer.forward(&current);
+            *total += start.elapsed();
+        }
+        current
+    }
+
+    /// Adds time spent backpropagating through layer `layer`.
+    pub(crate) fn add_backward(&mut self, layer: usize, elapsed: Duration) {
+        self.backward[layer] += elapsed;
+    }
+
+    /// Adds `other`'s totals, e.g. those of a worker thread.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn add(&mut self, other: &LayerTimer) {
+        self.forward.iter_mut().zip(&other.forward).for_each(|(t, o)| *t += *o);
+        self.backward.iter_mut().zip(&other.backward).for_each(|(t, o)| *t += *o);
+    }
+
+    /// The totals as one `LayerTiming` per layer of `network`, in order.
+    pub(crate) fn timings(&self, network: &Network) -> Vec<LayerTiming> {
+        network.layers.iter().enumerate()
+            .map(|(i, layer)| LayerTiming {
+                layer: i,
+                name: layer.name().map(str::to_owned),
+                kind: layer.kind().to_owned(),
+                forward_ms: self.forward[i].as_secs_f64() * 1e3,
+                backward_ms: self.backward[i].as_secs_f64() * 1e3,
+            })
+            .collect()
+    }
+}
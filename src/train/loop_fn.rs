@@ -1,22 +1,39 @@
 use std::sync::atomic::Ordering;
 use std::time::Instant;
 
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-use crate::loss::loss_type::LossType;
-use crate::loss::mse::MseLoss;
-use crate::loss::cross_entropy::CrossEntropyLoss;
+use crate::activation::activation::ActivationFunction;
+use crate::data::dataset::Dataset;
+use crate::data::scaling::Scaling;
 use crate::loss::bce::BceLoss;
-use crate::loss::mae::MaeLoss;
-use crate::loss::huber::HuberLoss;
+use crate::loss::cross_entropy::CrossEntropyLoss;
+use crate::loss::loss::Loss;
+use crate::loss::loss_type::LossType;
+use crate::loss::reduction::Reduction;
 use crate::math::matrix::Matrix;
 use crate::network::network::Network;
-use crate::optim::sgd::Sgd;
+use crate::optim::optimizer::Optimizer;
+use crate::preprocess::polynomial::PolynomialFeatures;
+use crate::train::batch_stats::BatchStats;
+use crate::train::callback::TrainCallback;
 use crate::train::epoch_stats::EpochStats;
+use crate::train::error::{NonFinitePolicy, NonFiniteSource, TrainError};
+use crate::train::gradients::GradientBuffers;
+use crate::train::layer_stats::LayerStats;
+use crate::train::layer_norms::NormTracker;
+use crate::train::layer_timing::LayerTimer;
+use crate::train::mixup::{mixup, sample_beta};
+use crate::train::outcome::TrainOutcome;
+use crate::train::sample_order::SampleOrder;
 use crate::train::train_config::TrainConfig;
 
 // ---------------------------------------------------------------------------
-// Public entry point
+// Public entry points
 // ---------------------------------------------------------------------------
 
 /// Trains `network` for `config.epochs` epochs and returns the mean training
@@ -24,43 +41,268 @@ use crate::train::train_config::TrainConfig;
 ///
 /// # Arguments
 /// - `network`      — mutable reference to the network; modified in place
-/// - `train_inputs` — training samples, each a `Vec<f64>` of length `input_size`
-/// - `train_labels` — corresponding targets, same length as `train_inputs`
-/// - `val_inputs`   — optional validation samples
-/// - `val_labels`   — optional validation targets (required iff `val_inputs` is `Some`)
-/// - `optimizer`    — SGD optimizer (carries learning rate)
-/// - `config`       — hyperparameters, optional progress channel, optional stop flag
+/// - `train`     — training samples (a `Split`, `(&inputs, &labels)`, or any
+///   other `Dataset`); inputs of length `input_size`
+/// - `val`       — optional validation samples
+/// - `optimizer` — update rule (`Sgd`, `Adam`, …); carries the learning rate
+/// - `config`    — hyperparameters, optional progress channel, optional stop flag
+///
+/// Samples are fetched from the datasets one at a time, as each mini-batch
+/// (or evaluation pass) reaches them, so a lazily loaded dataset is never
+/// held in memory as a whole.
 ///
 /// # Early termination
 /// The loop breaks early if:
 /// - the `progress_tx` receiver has been dropped (natural SSE disconnect), **or**
-/// - `config.stop_flag` is set to `true`.
+/// - `config.stop_flag` is set to `true`, **or**
+/// - a callback's `TrainCallback::should_stop` returns `true`
+///   (`train_loop_with_callbacks` only).
+///
+/// # Learning-rate schedule
+/// `optimizer.learning_rate()` is the base rate; `config.lr_schedule` may
+/// scale it per epoch (e.g. cosine annealing with warm restarts).  The base
+/// rate is restored when the loop returns.
+///
+/// # Resuming
+/// Epochs `1..=config.initial_epoch` are skipped, so a run restored from a
+/// `Checkpoint` (network, optimizer and epoch) continues where it stopped,
+/// including its position in the learning-rate schedule.
+///
+/// # Mixup
+/// When `config.mixup_alpha` is `Some(alpha)`, each training sample is
+/// replaced by a convex combination with another sample of the same
+/// mini-batch (inputs and labels alike), with the mixing coefficient drawn
+/// from `Beta(alpha, alpha)`.  Reported training loss is measured on the
+/// mixed samples; accuracy is measured on the original data.  The same holds
+/// for `config.image_augment` (random flips, rotations, shifts, brightness
+/// and noise; see `ImageAugment`) and `config.augment`, a custom per-sample
+/// transform, both applied afterwards in that order.
+///
+/// # Input scaling
+/// When `config.input_scaling` is set, per-feature statistics are fitted on
+/// `train` and recorded in `network.metadata.input_scaling` before the
+/// first epoch; training and validation then see scaled copies of the
+/// inputs, while `predict` and the other inference paths scale raw inputs
+/// themselves.  A network that already records a scaling (e.g. a loaded
+/// model being fine-tuned) keeps it, and its data is scaled with it even
+/// when `config.input_scaling` is `None`.
+///
+/// # Polynomial features
+/// When `config.polynomial_features` is set (or the network already
+/// records an expansion), `train` and `val` hold raw samples of
+/// `n_features` values; every input is expanded before scaling, the
+/// scaling is fitted on the expanded inputs, and the expansion is recorded
+/// in `network.metadata.polynomial_features` so the inference paths expand
+/// raw inputs the same way.
+///
+/// # Sample weighting
+/// When `config.sample_weights` and/or `config.sample_schedule` are set, each
+/// sample's loss and gradient are scaled by its effective weight, and the
+/// reported training loss is the weighted mean.
+///
+/// # Non-finite values
+/// A NaN or infinite loss or gradient is caught before the batch is applied.
+/// With `config.non_finite` at its default (`NonFinitePolicy::Abort`) this
+/// function panics with the `TrainError` message (epoch, batch and layer);
+/// use `try_train_loop` to get the error as a value instead.
 ///
 /// # Panics
-/// Panics if `train_inputs` is empty, lengths mismatch, or `batch_size == 0`.
+/// Panics with the `TrainError` message if `train` is empty, sample widths
+/// do not match the network, `batch_size == 0`,
+/// `reduction` is `Reduction::None`, `sample_weights` or `class_weights` has
+/// the wrong length, `class_weights` is set without the built-in
+/// `CrossEntropy` loss, the schedule yields an out-of-range sample index, or
+/// training diverges (see above).  `try_train_loop` returns these instead.
 pub fn train_loop(
     network: &mut Network,
-    train_inputs: &[Vec<f64>],
-    train_labels: &[Vec<f64>],
-    val_inputs: Option<&[Vec<f64>]>,
-    val_labels: Option<&[Vec<f64>]>,
-    optimizer: &Sgd,
+    train: &dyn Dataset,
+    val: Option<&dyn Dataset>,
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+) -> f64 {
+    train_loop_with_callbacks(
+        network,
+        train,
+        val,
+        optimizer,
+        config,
+        &mut [],
+    )
+}
+
+/// Same as `train_loop`, additionally invoking every entry of `callbacks` at
+/// the start and end of the run, of each epoch, and after each mini-batch
+/// (see `TrainCallback`).  Callbacks are called in slice order.
+#[allow(clippy::too_many_arguments)]
+pub fn train_loop_with_callbacks(
+    network: &mut Network,
+    train: &dyn Dataset,
+    val: Option<&dyn Dataset>,
+    optimizer: &mut dyn Optimizer,
     config: &TrainConfig,
+    callbacks: &mut [&mut dyn TrainCallback],
 ) -> f64 {
-    assert!(!train_inputs.is_empty(), "train_inputs must not be empty");
-    assert_eq!(
-        train_inputs.len(),
-        train_labels.len(),
-        "train_inputs and train_labels must have equal length"
-    );
-    assert!(config.batch_size > 0, "batch_size must be at least 1");
+    try_train_loop(
+        network,
+        train,
+        val,
+        optimizer,
+        config,
+        callbacks,
+    )
+    .map(|outcome| outcome.final_loss)
+    .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as `train_loop_with_callbacks`, but returns a `TrainError` instead
+/// of panicking, and a `TrainOutcome` describing how the run ended.
+///
+/// Arguments are checked up front — empty or mismatched splits, a first
+/// sample and label that do not fit the network, and settings that do not
+/// fit the data — and reported without touching the network or calling any
+/// callback.  Every other sample is checked when it is fetched, so a
+/// malformed one ends the run with `TrainError::InputShape` / `LabelShape`
+/// (after `on_train_end`) instead of panicking.
+/// A run that diverges (see `TrainConfig::non_finite`) restores the learning
+/// rate and still runs `on_train_end`.
+#[allow(clippy::too_many_arguments)]
+pub fn try_train_loop(
+    network: &mut Network,
+    train: &dyn Dataset,
+    val: Option<&dyn Dataset>,
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+    callbacks: &mut [&mut dyn TrainCallback],
+) -> Result<TrainOutcome, TrainError> {
+    let expansion = network.polynomial_features().or(config.polynomial_features.as_ref()).cloned();
+    validate_arguments(network, expansion.as_ref(), train, val, config)?;
+
+    let widths = sample_widths(network, expansion.as_ref());
+    let prepare = |split, dataset, scaling| PreparedDataset {
+        dataset,
+        split,
+        widths,
+        expansion: expansion.as_ref(),
+        scaling,
+    };
+    let scaling = input_scaling(network, &prepare("train", train, None), config)?;
+
+    if let Some(ref expansion) = expansion {
+        network.set_polynomial_features(expansion.clone());
+    }
+    if let Some(ref scaling) = scaling {
+        network.set_input_scaling(scaling.clone());
+    }
+    let train = prepare("train", train, scaling.as_ref());
+    let val = val.map(|val| prepare("validation", val, scaling.as_ref()));
+    run_train_loop(network, &train, val.as_ref(), optimizer, config, callbacks)
+}
+
+/// The scaling to train with: the one `network` already records, else one
+/// fitted on `train` (already expanded) if `config.input_scaling` asks for it.
+/// Fitting reads every sample, so their shapes are checked first.
+fn input_scaling(
+    network: &Network,
+    train: &PreparedDataset,
+    config: &TrainConfig,
+) -> Result<Option<Scaling>, TrainError> {
+    let width = train.get(0).0.len();
+    match network.input_scaling() {
+        Some(scaling) if scaling.len() != width => Err(TrainError::InvalidConfig(format!(
+            "the network's input scaling covers {} features but the samples have {}",
+            scaling.len(),
+            width
+        ))),
+        Some(scaling) => Ok(Some(scaling.clone())),
+        None => match config.input_scaling {
+            Some(method) => {
+                (0..train.len()).try_for_each(|i| train.try_get(i).map(drop))?;
+                Ok(Some(Scaling::fit_dataset(method, train)))
+            }
+            None => Ok(None),
+        },
+    }
+}
+
+/// `dataset` with `expansion` and then `scaling` applied to every input as
+/// it is fetched, as `Network::predict` applies them to raw inputs.
+///
+/// Only the first sample is checked before training, so the loop fetches
+/// through `try_get`, which checks each raw sample against `widths` (see
+/// `sample_widths`): a malformed sample deep in a streamed dataset is
+/// reported when its batch is assembled rather than by an extra full pass.
+struct PreparedDataset<'a> {
+    dataset: &'a dyn Dataset,
+    split: &'static str,
+    widths: Option<(usize, usize)>,
+    expansion: Option<&'a PolynomialFeatures>,
+    scaling: Option<&'a Scaling>,
+}
+
+impl PreparedDataset<'_> {
+    /// Sample `index`, prepared; `TrainError::InputShape` / `LabelShape` if
+    /// the raw sample does not fit the network.
+    fn try_get(&self, index: usize) -> Result<(Vec<f64>, Vec<f64>), TrainError> {
+        let (input, label) = self.dataset.get(index);
+        if let Some((n_in, n_out)) = self.widths {
+            check_sample(self.split, index, &input, &label, n_in, n_out)?;
+        }
+        Ok(self.prepare(input, label))
+    }
+
+    fn prepare(&self, mut input: Vec<f64>, label: Vec<f64>) -> (Vec<f64>, Vec<f64>) {
+        if let Some(expansion) = self.expansion {
+            input = expansion.transform(&input);
+        }
+        if let Some(scaling) = self.scaling {
+            scaling.apply_in_place(&mut input);
+        }
+        (input, label)
+    }
+}
+
+impl Dataset for PreparedDataset<'_> {
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
 
+    fn get(&self, index: usize) -> (Vec<f64>, Vec<f64>) {
+        let (input, label) = self.dataset.get(index);
+        self.prepare(input, label)
+    }
+
+    fn labels_len(&self) -> usize {
+        self.dataset.labels_len()
+    }
+}
+
+/// `try_train_loop` after the arguments are checked and the inputs
+/// expanded and scaled.
+fn run_train_loop(
+    network: &mut Network,
+    train: &PreparedDataset,
+    val: Option<&PreparedDataset>,
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+    callbacks: &mut [&mut dyn TrainCallback],
+) -> Result<TrainOutcome, TrainError> {
     let mut last_train_loss = 0.0;
+    let mut last_epoch = config.initial_epoch;
+    let mut stopped_early = false;
+    // Per-sample losses of the previous epoch, for `SampleOrder::LossSorted`.
+    let mut sample_losses = (config.sample_order == SampleOrder::LossSorted)
+        .then(|| vec![f64::NAN; train.len()]);
+    let base_lr = optimizer.learning_rate();
 
-    for epoch in 1..=config.epochs {
+    for cb in callbacks.iter_mut() {
+        cb.on_train_start(config.epochs);
+    }
+
+    for epoch in config.initial_epoch + 1..=config.epochs {
         // Check stop flag at the top of each epoch.
         if let Some(ref flag) = config.stop_flag {
             if flag.load(Ordering::Relaxed) {
+                stopped_early = true;
                 break;
             }
         }
@@ -68,36 +310,61 @@ pub fn train_loop(
         let t_start = Instant::now();
 
         // ── One full pass over the training data ───────────────────────────
+        let mut rng = epoch_rng(config.seed, epoch);
+        let order = match epoch_order(epoch, train.len(), config, sample_losses.as_deref(), &mut rng) {
+            Ok(order) => order,
+            Err(e) => {
+                for cb in callbacks.iter_mut() {
+                    cb.on_train_end(network);
+                }
+                return Err(e);
+            }
+        };
+        let total_batches = order.len().div_ceil(config.batch_size);
+        for cb in callbacks.iter_mut() {
+            cb.on_epoch_start(epoch, total_batches);
+        }
+        optimizer.set_learning_rate(config.lr_schedule.learning_rate(base_lr, epoch));
+        let mut timer = config.record_layer_timings.then(|| LayerTimer::new(network.layers.len()));
+        let mut norms = config.record_layer_norms.then(|| NormTracker::new(network));
         let train_loss = run_one_epoch(
             network,
-            train_inputs,
-            train_labels,
+            train,
+            epoch,
+            &order,
             optimizer,
-            config.batch_size,
-            config.loss_type,
+            config,
+            callbacks,
+            sample_losses.as_deref_mut(),
+            timer.as_mut(),
+            norms.as_mut(),
+            &mut rng,
         );
+        // Back to the base rate so callbacks (checkpoints) see the real one.
+        optimizer.set_learning_rate(base_lr);
+        let train_loss = match train_loss {
+            Ok(loss) => loss,
+            Err(e) => {
+                for cb in callbacks.iter_mut() {
+                    cb.on_train_end(network);
+                }
+                return Err(e);
+            }
+        };
         last_train_loss = train_loss;
+        last_epoch = epoch;
 
         let elapsed_ms = t_start.elapsed().as_millis() as u64;
 
-        // ── Accuracy ──────────────────────────────────────────────────────
-        let train_accuracy = match config.loss_type {
-            LossType::CrossEntropy       => Some(compute_accuracy_multiclass(network, train_inputs, train_labels)),
-            LossType::BinaryCrossEntropy => Some(compute_accuracy_binary(network, train_inputs, train_labels)),
-            _                            => None,
-        };
-
-        // ── Validation ────────────────────────────────────────────────────
-        let (val_loss, val_accuracy) = if let (Some(vi), Some(vl)) = (val_inputs, val_labels) {
-            let vl_val = compute_eval_loss(network, vi, vl, config.loss_type);
-            let va = match config.loss_type {
-                LossType::CrossEntropy       => Some(compute_accuracy_multiclass(network, vi, vl)),
-                LossType::BinaryCrossEntropy => Some(compute_accuracy_binary(network, vi, vl)),
-                _                            => None,
-            };
-            (Some(vl_val), va)
-        } else {
-            (None, None)
+        // ── Accuracy and validation ───────────────────────────────────────
+        let EpochEvaluation { train_accuracy, val_loss, val_accuracy } = match evaluate_epoch(network, train, val, config) {
+            Ok(evaluation) => evaluation,
+            Err(e) => {
+                for cb in callbacks.iter_mut() {
+                    cb.on_train_end(network);
+                }
+                return Err(e);
+            }
         };
 
         // ── Emit progress ─────────────────────────────────────────────────
@@ -109,182 +376,841 @@ pub fn train_loop(
             train_accuracy,
             val_accuracy,
             elapsed_ms,
+            layer_stats: if config.record_layer_stats {
+                Some(LayerStats::for_network(network))
+            } else {
+                None
+            },
+            layer_timings: timer.map(|timer| timer.timings(network)),
+            layer_norms: norms.map(|norms| norms.norms(network)),
         };
 
+        for cb in callbacks.iter_mut() {
+            cb.on_epoch_end(network, &stats);
+            cb.on_epoch_state(network, &*optimizer, &stats);
+        }
+
         if let Some(ref tx) = config.progress_tx {
             // If the receiver has been dropped, stop training.
             if tx.send(stats).is_err() {
+                stopped_early = epoch < config.epochs;
                 break;
             }
         }
 
+        if callbacks.iter().any(|cb| cb.should_stop()) {
+            stopped_early = epoch < config.epochs;
+            break;
+        }
+
         // Check stop flag again after potentially expensive eval.
         if let Some(ref flag) = config.stop_flag {
             if flag.load(Ordering::Relaxed) {
+                stopped_early = epoch < config.epochs;
                 break;
             }
         }
     }
 
-    last_train_loss
+    for cb in callbacks.iter_mut() {
+        cb.on_train_end(network);
+    }
+
+    Ok(TrainOutcome { final_loss: last_train_loss, last_epoch, stopped_early })
+}
+
+/// End-of-epoch metrics, as reported in `EpochStats`.
+struct EpochEvaluation {
+    train_accuracy: Option<f64>,
+    val_loss: Option<f64>,
+    val_accuracy: Option<f64>,
+}
+
+/// Measures `train` and `val` after an epoch.  Accuracy is measured for the
+/// classification losses only.
+fn evaluate_epoch(
+    network: &mut Network,
+    train: &PreparedDataset,
+    val: Option<&PreparedDataset>,
+    config: &TrainConfig,
+) -> Result<EpochEvaluation, TrainError> {
+    let accuracy = |network: &mut Network, dataset| match config.loss_type {
+        LossType::CrossEntropy       => compute_accuracy_multiclass(network, dataset).map(Some),
+        LossType::BinaryCrossEntropy => compute_accuracy_binary(network, dataset).map(Some),
+        _                            => Ok(None),
+    };
+    let train_accuracy = accuracy(network, train)?;
+    let (val_loss, val_accuracy) = match val {
+        Some(val) => (Some(compute_eval_loss(network, val, config)?), accuracy(network, val)?),
+        None => (None, None),
+    };
+    Ok(EpochEvaluation { train_accuracy, val_loss, val_accuracy })
+}
+
+// ---------------------------------------------------------------------------
+// Argument validation
+// ---------------------------------------------------------------------------
+
+/// Checks everything `try_train_loop` would otherwise trip over mid-run.
+/// Samples are raw: `expansion`, if any, has yet to be applied.
+fn validate_arguments(
+    network: &Network,
+    expansion: Option<&PolynomialFeatures>,
+    train: &dyn Dataset,
+    val: Option<&dyn Dataset>,
+    config: &TrainConfig,
+) -> Result<(), TrainError> {
+    if train.is_empty() {
+        return Err(TrainError::EmptyInputs { split: "train" });
+    }
+    let n_in = network.layers.first().map_or(0, |l| l.input_size());
+    if let Some(expansion) = expansion.filter(|e| e.output_size() != n_in) {
+        return Err(TrainError::InvalidConfig(format!(
+            "polynomial_features expands {} features to {} but the network takes {}",
+            expansion.n_features,
+            expansion.output_size(),
+            n_in
+        )));
+    }
+    check_dataset(network, expansion, "train", train)?;
+    if let Some(val) = val {
+        check_dataset(network, expansion, "validation", val)?;
+    }
+    if config.batch_size == 0 {
+        return Err(TrainError::InvalidConfig("batch_size must be at least 1".into()));
+    }
+    if config.reduction == Some(Reduction::None) {
+        return Err(TrainError::InvalidConfig("reduction none has no scalar training loss".into()));
+    }
+    if let Some(ref image_augment) = config.image_augment {
+        if let Err(problems) = image_augment.validate() {
+            return Err(TrainError::InvalidConfig(problems.join("; ")));
+        }
+        if image_augment.len() != n_in {
+            return Err(TrainError::InvalidConfig(format!(
+                "image_augment expects {}×{}×{} = {} values per sample but the network takes {}",
+                image_augment.width,
+                image_augment.height,
+                image_augment.channels,
+                image_augment.len(),
+                n_in
+            )));
+        }
+    }
+    if let Some(ref weights) = config.sample_weights {
+        if weights.len() != train.len() {
+            return Err(TrainError::InvalidConfig(format!(
+                "sample_weights has {} entries for {} training samples",
+                weights.len(),
+                train.len()
+            )));
+        }
+    }
+    if let Some(ref weights) = config.class_weights {
+        if config.custom_loss.is_some() {
+            return Err(TrainError::InvalidConfig("class_weights do not apply to a custom loss".into()));
+        }
+        if config.loss_type != LossType::CrossEntropy {
+            return Err(TrainError::InvalidConfig(format!(
+                "class_weights require the cross_entropy loss, not {:?}",
+                config.loss_type
+            )));
+        }
+        let n_classes = train.get(0).1.len();
+        if weights.len() != n_classes {
+            return Err(TrainError::InvalidConfig(format!(
+                "class_weights has {} entries for {} classes",
+                weights.len(),
+                n_classes
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `inputs` and `labels` pair up and match the network's input
+/// and output sizes.  An empty split passes.
+pub(crate) fn check_split(
+    network: &Network,
+    split: &'static str,
+    inputs: &[Vec<f64>],
+    labels: &[Vec<f64>],
+) -> Result<(), TrainError> {
+    if inputs.len() != labels.len() {
+        return Err(TrainError::LengthMismatch { split, inputs: inputs.len(), labels: labels.len() });
+    }
+    let (Some(first), Some(last)) = (network.layers.first(), network.layers.last()) else {
+        return Ok(());
+    };
+    let (n_in, n_out) = (first.input_size(), last.output_size());
+    for (sample, (input, label)) in inputs.iter().zip(labels.iter()).enumerate() {
+        if input.len() != n_in {
+            return Err(TrainError::InputShape { split, sample, expected: n_in, actual: input.len() });
+        }
+        if label.len() != n_out {
+            return Err(TrainError::LabelShape { split, sample, expected: n_out, actual: label.len() });
+        }
+    }
+    Ok(())
+}
+
+/// `check_split` for a `Dataset`: checks that its labels pair up with its
+/// inputs and that the first sample fits the network (see `sample_widths`).
+/// The other samples are checked as training fetches them, so a streamed
+/// dataset is not read an extra time.
+fn check_dataset(
+    network: &Network,
+    expansion: Option<&PolynomialFeatures>,
+    split: &'static str,
+    dataset: &dyn Dataset,
+) -> Result<(), TrainError> {
+    if dataset.labels_len() != dataset.len() {
+        return Err(TrainError::LengthMismatch { split, inputs: dataset.len(), labels: dataset.labels_len() });
+    }
+    match sample_widths(network, expansion) {
+        Some((n_in, n_out)) if !dataset.is_empty() => {
+            let (input, label) = dataset.get(0);
+            check_sample(split, 0, &input, &label, n_in, n_out)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `(input, label)` widths a raw sample must have: the network's input and
+/// output sizes, or `expansion`'s `n_features` inputs when set.  `None` for
+/// a network without layers.
+fn sample_widths(network: &Network, expansion: Option<&PolynomialFeatures>) -> Option<(usize, usize)> {
+    let (first, last) = (network.layers.first()?, network.layers.last()?);
+    Some((expansion.map_or(first.input_size(), |e| e.n_features), last.output_size()))
+}
+
+fn check_sample(
+    split: &'static str,
+    sample: usize,
+    input: &[f64],
+    label: &[f64],
+    n_in: usize,
+    n_out: usize,
+) -> Result<(), TrainError> {
+    if input.len() != n_in {
+        return Err(TrainError::InputShape { split, sample, expected: n_in, actual: input.len() });
+    }
+    if label.len() != n_out {
+        return Err(TrainError::LabelShape { split, sample, expected: n_out, actual: label.len() });
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
 // Private helpers
 // ---------------------------------------------------------------------------
 
-/// Runs one full epoch of mini-batch SGD over the training data.
-/// Returns the mean loss over all samples.
+/// Random generator for one epoch: derived from `seed` and the epoch number
+/// when seeded (so resuming at any epoch reproduces the same stream), fresh
+/// entropy otherwise.  The two are mixed so that adjacent seeds do not
+/// share streams (seed `s`, epoch `e + 1` vs. seed `s + 1`, epoch `e`).
+pub(crate) fn epoch_rng(seed: Option<u64>, epoch: usize) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(splitmix64(seed ^ (epoch as u64).rotate_left(32))),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// SplitMix64 finalizer: a bijective 64-bit mix in which every input bit
+/// affects every output bit.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Builds the `(sample_index, weight)` visiting order for one epoch.
+///
+/// Defaults to `config.sample_order` with unit weights; the curriculum
+/// schedule (if any) replaces it, and static per-sample weights are
+/// multiplied in.
+fn epoch_order(
+    epoch: usize,
+    n: usize,
+    config: &TrainConfig,
+    previous_losses: Option<&[f64]>,
+    rng: &mut StdRng,
+) -> Result<Vec<(usize, f64)>, TrainError> {
+    let mut order: Vec<(usize, f64)> = match config.sample_schedule {
+        Some(ref schedule) => schedule(epoch, n),
+        None => config.sample_order
+            .order(n, previous_losses, rng)
+            .into_iter()
+            .map(|i| (i, 1.0))
+            .collect(),
+    };
+    for (idx, weight) in order.iter_mut() {
+        if *idx >= n {
+            return Err(TrainError::InvalidConfig(format!(
+                "sample_schedule returned index {} for {} samples",
+                idx, n
+            )));
+        }
+        if let Some(ref weights) = config.sample_weights {
+            *weight *= weights[*idx];
+        }
+    }
+    Ok(order)
+}
+
+/// Runs one full epoch of mini-batch SGD over the samples listed in `order`.
+/// Returns the weighted mean loss over those samples (over the applied
+/// batches when `NonFinitePolicy::SkipBatch` dropped some; NaN if it dropped
+/// them all).
+///
+/// `epoch` (1-based) is only used to label the `BatchStats` sent on
+/// `config.batch_tx`.  When `sample_losses` is given, each visited sample's
+/// (unweighted) loss is written at its index.  When `timer` is given, the
+/// per-layer forward/backward times are added to it; when `norms` is, every
+/// applied step is recorded in it.
+///
+/// With `config.mixup_alpha` set, every sample is blended with a randomly
+/// chosen partner from the same mini-batch before the forward pass; then
+/// `config.image_augment` and `config.augment` (if any) transform the result.
+#[allow(clippy::too_many_arguments)]
 fn run_one_epoch(
     network: &mut Network,
-    inputs: &[Vec<f64>],
-    labels: &[Vec<f64>],
-    optimizer: &Sgd,
-    batch_size: usize,
-    loss_type: LossType,
-) -> f64 {
-    let n = inputs.len();
+    train: &PreparedDataset,
+    epoch: usize,
+    order: &[(usize, f64)],
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+    callbacks: &mut [&mut dyn TrainCallback],
+    mut sample_losses: Option<&mut [f64]>,
+    mut timer: Option<&mut LayerTimer>,
+    mut norms: Option<&mut NormTracker>,
+    rng: &mut StdRng,
+) -> Result<f64, TrainError> {
+    let n = order.len();
+    let total_batches = n.div_ceil(config.batch_size);
     let mut total_loss = 0.0;
+    let mut total_weight = 0.0;
+    let mut skipped_batches = 0;
+    let fused = config.custom_loss.is_none() && fuses_sigmoid_bce(network, config.loss_type);
+    let mut acc_grads = GradientBuffers::for_network(network);
+    let mut replicas = worker_replicas(network, config);
 
-    // Shuffle sample order each epoch.
-    let mut indices: Vec<usize> = (0..n).collect();
-    indices.shuffle(&mut rand::thread_rng());
-
-    for batch_start in (0..n).step_by(batch_size) {
-        let batch_end = (batch_start + batch_size).min(n);
+    for batch_start in (0..n).step_by(config.batch_size) {
+        let batch_end = (batch_start + config.batch_size).min(n);
         let actual_batch_size = (batch_end - batch_start) as f64;
+        let batch = &order[batch_start..batch_end];
 
-        // Zero-initialize accumulated gradient storage.
-        let mut acc_grads: Vec<(Matrix, Matrix)> = network.layers.iter()
-            .map(|layer| (
-                Matrix::zeros(layer.weights.rows, layer.weights.cols),
-                Matrix::zeros(layer.biases.rows, layer.biases.cols),
-            ))
-            .collect();
+        // Mixup partners: a random permutation of the batch positions.
+        let partners: Option<Vec<usize>> = config.mixup_alpha.map(|_| {
+            let mut p: Vec<usize> = (0..batch.len()).collect();
+            p.shuffle(rng);
+            p
+        });
+
+        // Prepare the batch's samples in visiting order first, so the random
+        // draws do not depend on how the gradients are computed.
+        let mut samples = Vec::with_capacity(batch.len());
+        for (pos, &(index, weight)) in batch.iter().enumerate() {
+            let (mut input, mut expected) = match (config.mixup_alpha, &partners) {
+                (Some(alpha), Some(partners)) => {
+                    let (input, label) = train.try_get(index)?;
+                    let (other_input, other_label) = train.try_get(batch[partners[pos]].0)?;
+                    let lambda = sample_beta(rng, alpha);
+                    mixup(&input, &label, &other_input, &other_label, lambda)
+                }
+                _ => train.try_get(index)?,
+            };
+            if let Some(ref image_augment) = config.image_augment {
+                image_augment.apply_with_rng(&mut input, rng);
+            }
+            if let Some(ref augment) = config.augment {
+                augment(&mut input, &mut expected);
+            }
+            let expected = if config.loss_type == LossType::CrossEntropy && config.label_smoothing > 0.0 {
+                smooth_labels(&expected, config.label_smoothing)
+            } else {
+                expected
+            };
+            samples.push(BatchSample { index, weight, input, expected });
+        }
+
+        acc_grads.zero();
+        let BatchLoss {
+            loss: batch_loss,
+            weight: batch_weight,
+            sample_losses: losses,
+        } = batch_gradients(network, &mut replicas, &samples, config, fused, &mut acc_grads, timer.as_deref_mut())?;
+        if let Some(ref mut sample_losses) = sample_losses {
+            for (index, loss) in losses {
+                sample_losses[index] = loss;
+            }
+        }
 
-        // Accumulate gradients over the mini-batch.
-        for &idx in &indices[batch_start..batch_end] {
-            let input    = &inputs[idx];
-            let expected = &labels[idx];
+        let batch_number = batch_start / config.batch_size + 1;
+        let non_finite = if batch_loss.is_finite() {
+            first_non_finite_layer(&acc_grads).map(|layer| NonFiniteSource::Gradient { layer })
+        } else {
+            Some(NonFiniteSource::Loss)
+        };
+        match (non_finite, config.non_finite) {
+            (None, _) => {
+                add_weight_penalty(network, &mut acc_grads, config.l1, config.l2, actual_batch_size);
+                if let Some(ref mut norms) = norms {
+                    norms.before_step(network, &acc_grads, actual_batch_size);
+                }
+                apply_mean_gradients(network, &mut acc_grads, actual_batch_size, optimizer);
+                sync_replicas(&mut replicas, network);
+                if let Some(ref mut norms) = norms {
+                    norms.after_step(network);
+                }
+                total_loss   += batch_loss;
+                total_weight += batch_weight;
+            }
+            (Some(source), NonFinitePolicy::Abort) => {
+                return Err(TrainError::NonFinite { epoch, batch: batch_number, source });
+            }
+            // The batch is dropped; the weights are untouched.
+            (Some(_), NonFinitePolicy::SkipBatch) => skipped_batches += 1,
+        }
 
-            let output = network.forward(input.clone());
+        for cb in callbacks.iter_mut() {
+            cb.on_batch_end(batch_number, total_batches);
+        }
+        if let Some(ref tx) = config.batch_tx {
+            // Progress only: a receiver that went away is not a stop request.
+            let _ = tx.send(BatchStats {
+                epoch,
+                batch: batch_number,
+                total_batches,
+                loss: if batch_weight > 0.0 { batch_loss / batch_weight } else { 0.0 },
+            });
+        }
+    }
 
-            total_loss += compute_loss(&output, expected, loss_type);
+    Ok(if total_weight > 0.0 {
+        total_loss / total_weight
+    } else if skipped_batches == total_batches && total_batches > 0 {
+        // Every batch was discarded: there is no meaningful loss to report.
+        f64::NAN
+    } else {
+        0.0
+    })
+}
 
-            let error  = compute_loss_derivative(&output, expected, loss_type);
-            let mut delta = Matrix::from_data(vec![error]);
+/// One prepared training sample of a mini-batch.
+struct BatchSample {
+    index: usize,
+    weight: f64,
+    input: Vec<f64>,
+    expected: Vec<f64>,
+}
 
-            // Backward pass.
-            for i in (0..network.layers.len()).rev() {
-                let input_for_layer = if i == 0 {
-                    Matrix::from_data(vec![input.clone()])
-                } else {
-                    network.layers[i - 1].neurons.clone()
-                };
+/// Weighted loss of (part of) a mini-batch whose gradients went into a
+/// `GradientBuffers`.
+///
+/// # Fields
+/// - `loss`          — sum of weighted sample losses
+/// - `weight`        — sum of sample weights
+/// - `sample_losses` — `(sample_index, unweighted_loss)` per sample, in order
+struct BatchLoss {
+    loss: f64,
+    weight: f64,
+    sample_losses: Vec<(usize, f64)>,
+}
 
-                let (w_grad, b_grad) = network.layers[i].compute_gradients(
-                    delta.clone(),
-                    &input_for_layer,
-                );
+/// Smallest share of a mini-batch worth a thread of its own: each worker
+/// zeroes and reduces a full set of gradient buffers, which costs about as
+/// much as one sample's pass.
+#[cfg(feature = "parallel")]
+const MIN_SAMPLES_PER_WORKER: usize = 4;
 
-                if i > 0 {
-                    delta = b_grad.clone() * network.layers[i].weights.transpose();
-                }
+/// One parallel worker's copy of the network and the gradient accumulators
+/// it sums its chunk into.
+struct Replica {
+    network: Network,
+    #[cfg(feature = "parallel")]
+    grads: GradientBuffers,
+}
+
+/// Replicas for the parallel path of `batch_gradients`, one per worker a
+/// full batch can use; none without the `parallel` feature or when batches
+/// are too small to split.  Made once per epoch: the networks are kept in
+/// step with `network` by `sync_replicas` after every optimizer step, and
+/// the buffers are zeroed before every batch.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn worker_replicas(network: &Network, config: &TrainConfig) -> Vec<Replica> {
+    #[cfg(feature = "parallel")]
+    {
+        let workers = rayon::current_num_threads().min(config.batch_size / MIN_SAMPLES_PER_WORKER);
+        if workers > 1 {
+            return (0..workers)
+                .map(|_| Replica { network: network.clone(), grads: GradientBuffers::for_network(network) })
+                .collect();
+        }
+    }
+    Vec::new()
+}
 
-                acc_grads[i].0 = acc_grads[i].0.clone() + w_grad;
-                acc_grads[i].1 = acc_grads[i].1.clone() + b_grad;
+/// Copies `network`'s weights and biases into every replica; the layers
+/// hold no other state that training changes.
+fn sync_replicas(replicas: &mut [Replica], network: &Network) {
+    for replica in replicas {
+        for (layer, source) in replica.network.layers.iter_mut().zip(&network.layers) {
+            let ((weights, biases), (source_weights, source_biases)) = (layer.params_mut(), source.params());
+            weights.data.copy_from_slice(&source_weights.data);
+            biases.data.copy_from_slice(&source_biases.data);
+        }
+    }
+}
+
+/// Forward and backward passes over `samples`, adding each sample's
+/// weighted gradients into `grads` (and the per-layer times into `timer`).
+///
+/// With the `parallel` feature the samples are split into contiguous
+/// chunks, one per rayon worker (at least `MIN_SAMPLES_PER_WORKER` samples
+/// each); every worker runs its chunk on its own replica of `network` into
+/// the replica's buffers (see `worker_replicas`), and the chunks' sums are
+/// added to `grads` in order.  The result matches the single-threaded path
+/// up to floating-point rounding and, for a fixed thread count, is the same
+/// on every run.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn batch_gradients(
+    network: &mut Network,
+    replicas: &mut [Replica],
+    samples: &[BatchSample],
+    config: &TrainConfig,
+    fused: bool,
+    grads: &mut GradientBuffers,
+    timer: Option<&mut LayerTimer>,
+) -> Result<BatchLoss, TrainError> {
+    #[cfg(feature = "parallel")]
+    {
+        let workers = replicas.len().min(samples.len() / MIN_SAMPLES_PER_WORKER);
+        if workers > 1 {
+            let timed = timer.is_some();
+            let parts: Vec<_> = samples
+                .par_chunks(samples.len().div_ceil(workers))
+                .zip(replicas.par_iter_mut())
+                .map(|(chunk, Replica { network, grads })| {
+                    grads.zero();
+                    let mut part_timer = timed.then(|| LayerTimer::new(network.layers.len()));
+                    sample_gradients(network, chunk, config, fused, grads, part_timer.as_mut())
+                        .map(|loss| (part_timer, loss))
+                })
+                .collect();
+            let mut timer = timer;
+            let mut total = BatchLoss { loss: 0.0, weight: 0.0, sample_losses: Vec::with_capacity(samples.len()) };
+            for (part, replica) in parts.into_iter().zip(replicas.iter()) {
+                let (part_timer, part) = part?;
+                grads.try_add_assign(&replica.grads)?;
+                if let (Some(timer), Some(part_timer)) = (timer.as_deref_mut(), part_timer) {
+                    timer.add(&part_timer);
+                }
+                total.loss += part.loss;
+                total.weight += part.weight;
+                total.sample_losses.extend(part.sample_losses);
             }
+            return Ok(total);
         }
+    }
+    sample_gradients(network, samples, config, fused, grads, timer)
+}
 
-        // Average and apply.
-        let inv_batch = 1.0 / actual_batch_size;
-        for (i, (w_acc, b_acc)) in acc_grads.into_iter().enumerate() {
-            let w_avg = w_acc.map(|x| x * inv_batch);
-            let b_avg = b_acc.map(|x| x * inv_batch);
-            optimizer.step(&mut network.layers[i], w_avg, b_avg);
+/// Single-threaded `batch_gradients`: runs every sample through `network`
+/// in turn and accumulates its weighted gradients.
+fn sample_gradients(
+    network: &mut Network,
+    samples: &[BatchSample],
+    config: &TrainConfig,
+    fused: bool,
+    grads: &mut GradientBuffers,
+    mut timer: Option<&mut LayerTimer>,
+) -> Result<BatchLoss, TrainError> {
+    let mut total = BatchLoss {
+        loss: 0.0,
+        weight: 0.0,
+        sample_losses: Vec::with_capacity(samples.len()),
+    };
+    for sample in samples {
+        let output = match timer {
+            Some(ref mut timer) => timer.forward(network, sample.input.clone()),
+            None => network.forward(sample.input.clone()),
+        };
+
+        let (loss, delta) = sample_loss_and_delta(&output, &sample.expected, config, fused);
+        total.loss   += sample.weight * loss;
+        total.weight += sample.weight;
+        total.sample_losses.push((sample.index, loss));
+
+        let error: Vec<f64> = delta
+            .into_iter()
+            .map(|g| g * sample.weight)
+            .collect();
+        backpropagate(network, &sample.input, error, fused, false, grads, timer.as_deref_mut())?;
+    }
+    Ok(total)
+}
+
+/// Training loss and output delta for one sample under `config`: the custom
+/// loss if one is set, otherwise class weights for cross-entropy, then the
+/// configured `reduction` (or the loss type's own convention when unset).
+///
+/// With `fused_sigmoid_bce` the delta is the Sigmoid + BCE gradient w.r.t.
+/// the output logits (see `BceLoss::logit_derivative`) and must be
+/// backpropagated with `accumulate_logit_gradients`.
+fn sample_loss_and_delta(
+    output: &[f64],
+    expected: &[f64],
+    config: &TrainConfig,
+    fused_sigmoid_bce: bool,
+) -> (f64, Vec<f64>) {
+    if let Some(ref loss) = config.custom_loss {
+        return (loss.loss(output, expected), loss.derivative(output, expected));
+    }
+    let loss_type = config.loss_type;
+    if fused_sigmoid_bce {
+        let delta = BceLoss::logit_derivative(output, expected);
+        return match config.reduction {
+            Some(reduction) => (
+                loss_type.reduced_loss(output, expected, reduction).unwrap_or(f64::NAN),
+                reduction.scale_gradient(delta),
+            ),
+            None => (loss_type.loss(output, expected), delta),
+        };
+    }
+    match (loss_type, &config.class_weights, config.reduction) {
+        (LossType::CrossEntropy, Some(class_weights), reduction) => {
+            let loss = weighted_cross_entropy(output, expected, class_weights, reduction);
+            let delta = CrossEntropyLoss::weighted_derivative(output, expected, class_weights);
+            match reduction {
+                Some(Reduction::Mean) => (loss, Reduction::Mean.scale_gradient(delta)),
+                _ => (loss, delta),
+            }
         }
+        (_, _, Some(reduction)) => (
+            loss_type.reduced_loss(output, expected, reduction).unwrap_or(f64::NAN),
+            loss_type.reduced_derivative(output, expected, reduction),
+        ),
+        (_, _, None) => (
+            loss_type.loss(output, expected),
+            loss_type.derivative(output, expected),
+        ),
     }
+}
+
+/// Class-weighted cross-entropy of one sample.  The weighted terms are
+/// summed over classes; `Reduction::Mean` divides by K.
+fn weighted_cross_entropy(
+    output: &[f64],
+    expected: &[f64],
+    class_weights: &[f64],
+    reduction: Option<Reduction>,
+) -> f64 {
+    let loss = CrossEntropyLoss::weighted_loss(output, expected, class_weights);
+    match reduction {
+        Some(Reduction::Mean) => loss / output.len() as f64,
+        _ => loss,
+    }
+}
+
+/// Label smoothing: `(1 − epsilon) · y + epsilon / K` for a `K`-class target.
+fn smooth_labels(label: &[f64], epsilon: f64) -> Vec<f64> {
+    let uniform = epsilon / label.len() as f64;
+    label.iter().map(|y| (1.0 - epsilon) * y + uniform).collect()
+}
 
-    total_loss / n as f64
+/// Index of the first layer whose accumulated weight or bias gradient holds
+/// a NaN or infinity.
+fn first_non_finite_layer(acc_grads: &[(Matrix, Matrix)]) -> Option<usize> {
+    acc_grads.iter().position(|(w, b)| {
+        w.data.iter().chain(b.data.iter()).any(|g| !g.is_finite())
+    })
 }
 
-/// Scalar loss for one sample — dispatches on `LossType`.
-fn compute_loss(predicted: &[f64], expected: &[f64], loss_type: LossType) -> f64 {
-    match loss_type {
-        LossType::Mse                => MseLoss::loss(predicted, expected),
-        LossType::CrossEntropy       => CrossEntropyLoss::loss(predicted, expected),
-        LossType::BinaryCrossEntropy => BceLoss::loss(predicted, expected),
-        LossType::Mae                => MaeLoss::loss(predicted, expected),
-        LossType::Huber              => HuberLoss::loss(predicted, expected),
+/// Backpropagates `output_delta` (∂L/∂a of the output layer) for one sample
+/// and adds the resulting gradients into `acc_grads`.
+///
+/// The network must have just run `forward(input)` so that every layer's
+/// cached activations belong to this sample.  A gradient that does not fit
+/// its accumulator is reported as `TrainError::GradientShape`.
+pub(crate) fn accumulate_gradients(
+    network: &Network,
+    input: &[f64],
+    output_delta: Vec<f64>,
+    acc_grads: &mut [(Matrix, Matrix)],
+) -> Result<(), TrainError> {
+    backpropagate(network, input, output_delta, false, false, acc_grads, None).map(|_| ())
+}
+
+/// Like `accumulate_gradients`, but `logit_delta` is ∂L/∂z of the output
+/// layer, so its activation derivative is skipped (fused Sigmoid + BCE).
+pub(crate) fn accumulate_logit_gradients(
+    network: &Network,
+    input: &[f64],
+    logit_delta: Vec<f64>,
+    acc_grads: &mut [(Matrix, Matrix)],
+) -> Result<(), TrainError> {
+    backpropagate(network, input, logit_delta, true, false, acc_grads, None).map(|_| ())
+}
+
+/// Like `accumulate_gradients` (or `accumulate_logit_gradients` when
+/// `output_is_logit`), but also backpropagates through the first layer and
+/// returns ∂L/∂input — for networks fed by another network, as in a `Graph`.
+/// A network without layers returns `output_delta` unchanged.
+pub(crate) fn backpropagate_to_input(
+    network: &Network,
+    input: &[f64],
+    output_delta: Vec<f64>,
+    output_is_logit: bool,
+    acc_grads: &mut [(Matrix, Matrix)],
+) -> Result<Vec<f64>, TrainError> {
+    backpropagate(network, input, output_delta, output_is_logit, true, acc_grads, None)
+}
+
+/// `true` when training `network` with `loss_type` uses the fused
+/// Sigmoid + BCE gradient: the output layer is Sigmoid and the loss is the
+/// built-in binary cross-entropy.
+pub(crate) fn fuses_sigmoid_bce(network: &Network, loss_type: LossType) -> bool {
+    loss_type == LossType::BinaryCrossEntropy
+        && network.layers.last().and_then(|l| l.activation()) == Some(&ActivationFunction::Sigmoid)
+}
+
+fn backpropagate(
+    network: &Network,
+    input: &[f64],
+    output_delta: Vec<f64>,
+    output_is_logit: bool,
+    want_input_delta: bool,
+    acc_grads: &mut [(Matrix, Matrix)],
+    mut timer: Option<&mut LayerTimer>,
+) -> Result<Vec<f64>, TrainError> {
+    let last = network.layers.len().saturating_sub(1);
+    let mut delta = output_delta;
+
+    for i in (0..network.layers.len()).rev() {
+        let layer = &network.layers[i];
+        let input_for_layer = if i == 0 { input } else { network.layers[i - 1].output() };
+
+        let want_delta = i > 0 || want_input_delta;
+        let is_logit = output_is_logit && i == last;
+        let start = timer.is_some().then(Instant::now);
+        let input_delta = layer.backward_into(input_for_layer, &delta, is_logit, want_delta, &mut acc_grads[i])
+            .map_err(|error| TrainError::GradientShape { layer: i, error })?;
+        if let (Some(timer), Some(start)) = (timer.as_deref_mut(), start) {
+            timer.add_backward(i, start.elapsed());
+        }
+        if let Some(input_delta) = input_delta {
+            delta = input_delta;
+        }
     }
+    Ok(delta)
 }
 
-/// Per-output gradient for one sample — dispatches on `LossType`.
-fn compute_loss_derivative(predicted: &[f64], expected: &[f64], loss_type: LossType) -> Vec<f64> {
-    match loss_type {
-        LossType::Mse                => MseLoss::derivative(predicted, expected),
-        LossType::CrossEntropy       => CrossEntropyLoss::derivative(predicted, expected),
-        LossType::BinaryCrossEntropy => BceLoss::derivative(predicted, expected),
-        LossType::Mae                => MaeLoss::derivative(predicted, expected),
-        LossType::Huber              => HuberLoss::derivative(predicted, expected),
+/// Adds the L1/L2 penalty gradient `l1 · sign(w) + l2 · w` to every weight
+/// gradient in `acc_grads` (biases are not regularized).  A layer's own
+/// `LayerOps::l2` replaces the global `l2` for that layer.
+///
+/// The penalty is scaled by `batch_size` so that it survives the division in
+/// `apply_mean_gradients` unchanged: the decay per step does not depend on
+/// how many samples the batch held.
+pub(crate) fn add_weight_penalty(
+    network: &Network,
+    acc_grads: &mut [(Matrix, Matrix)],
+    l1: f64,
+    l2: f64,
+    batch_size: f64,
+) {
+    for (layer, (w_acc, _)) in network.layers.iter().zip(acc_grads.iter_mut()) {
+        let l2 = layer.l2().unwrap_or(l2);
+        if l1 == 0.0 && l2 == 0.0 {
+            continue;
+        }
+        for (g, &w) in w_acc.data.iter_mut().zip(layer.params().0.data.iter()) {
+            // sign(0) is taken as 0 so L1 does not push zero weights around.
+            let sign = if w > 0.0 { 1.0 } else if w < 0.0 { -1.0 } else { 0.0 };
+            *g += batch_size * (l1 * sign + l2 * w);
+        }
+    }
+}
+
+/// Divides the accumulated gradients by `batch_size` and applies them with
+/// one optimizer step per layer.
+/// The scaling happens in place, so `acc_grads` must be zeroed before it
+/// accumulates the next batch.
+pub(crate) fn apply_mean_gradients(
+    network: &mut Network,
+    acc_grads: &mut [(Matrix, Matrix)],
+    batch_size: f64,
+    optimizer: &mut dyn Optimizer,
+) {
+    let inv_batch = 1.0 / batch_size;
+    for (i, (w_acc, b_acc)) in acc_grads.iter_mut().enumerate() {
+        w_acc.scale_mut(inv_batch);
+        b_acc.scale_mut(inv_batch);
+        optimizer.step(i, network.layers[i].as_mut(), w_acc, b_acc);
     }
 }
 
 /// Mean loss over a full dataset without gradient accumulation (eval mode).
+/// `class_weights` apply as in training, so `val_loss` and `train_loss`
+/// measure the same quantity.
 fn compute_eval_loss(
     network: &mut Network,
-    inputs: &[Vec<f64>],
-    labels: &[Vec<f64>],
-    loss_type: LossType,
-) -> f64 {
-    let n = inputs.len();
+    dataset: &PreparedDataset,
+    config: &TrainConfig,
+) -> Result<f64, TrainError> {
+    let n = dataset.len();
     if n == 0 {
-        return 0.0;
+        return Ok(0.0);
+    }
+    let loss_type = config.loss_type;
+    let mut total = 0.0;
+    for i in 0..n {
+        let (input, label) = dataset.try_get(i)?;
+        let output = network.forward(input);
+        total += match (&config.custom_loss, loss_type, &config.class_weights, config.reduction) {
+            (Some(loss), ..) => loss.loss(&output, &label),
+            (None, LossType::CrossEntropy, Some(class_weights), reduction) => {
+                weighted_cross_entropy(&output, &label, class_weights, reduction)
+            }
+            (None, _, _, Some(reduction)) => loss_type.reduced_loss(&output, &label, reduction).unwrap_or(f64::NAN),
+            (None, _, _, None) => loss_type.loss(&output, &label),
+        };
     }
-    let total: f64 = inputs.iter().zip(labels.iter())
-        .map(|(input, label)| {
-            let output = network.forward(input.clone());
-            compute_loss(&output, label, loss_type)
-        })
-        .sum();
-    total / n as f64
+    Ok(total / n as f64)
 }
 
 /// Fraction of samples classified correctly (argmax match).
 /// Used for `CrossEntropy` runs only.
-fn compute_accuracy_multiclass(
-    network: &mut Network,
-    inputs: &[Vec<f64>],
-    labels: &[Vec<f64>],
-) -> f64 {
-    let n = inputs.len();
+fn compute_accuracy_multiclass(network: &mut Network, dataset: &PreparedDataset) -> Result<f64, TrainError> {
+    let n = dataset.len();
     if n == 0 {
-        return 0.0;
+        return Ok(0.0);
+    }
+    let mut correct = 0usize;
+    for i in 0..n {
+        let (input, label) = dataset.try_get(i)?;
+        let output = network.forward(input);
+        if argmax(&output) == argmax(&label) {
+            correct += 1;
+        }
     }
-    let correct: usize = inputs.iter().zip(labels.iter())
-        .filter(|(input, label)| {
-            let output = network.forward((*input).clone());
-            argmax(&output) == argmax(label)
-        })
-        .count();
-    correct as f64 / n as f64
+    Ok(correct as f64 / n as f64)
 }
 
 /// Fraction of output nodes predicted correctly using a 0.5 threshold.
 /// Used for `BinaryCrossEntropy` runs only.
-fn compute_accuracy_binary(
-    network: &mut Network,
-    inputs: &[Vec<f64>],
-    labels: &[Vec<f64>],
-) -> f64 {
-    let n = inputs.len();
+fn compute_accuracy_binary(network: &mut Network, dataset: &PreparedDataset) -> Result<f64, TrainError> {
+    let n = dataset.len();
     if n == 0 {
-        return 0.0;
+        return Ok(0.0);
     }
     let mut total_correct = 0usize;
     let mut total_nodes   = 0usize;
-    for (input, label) in inputs.iter().zip(labels.iter()) {
-        let output = network.forward(input.clone());
+    for i in 0..n {
+        let (input, label) = dataset.try_get(i)?;
+        let output = network.forward(input);
         for (p, y) in output.iter().zip(label.iter()) {
             if (*p >= 0.5) == (*y >= 0.5) {
                 total_correct += 1;
@@ -292,11 +1218,11 @@ fn compute_accuracy_binary(
             total_nodes += 1;
         }
     }
-    if total_nodes == 0 { 0.0 } else { total_correct as f64 / total_nodes as f64 }
+    Ok(if total_nodes == 0 { 0.0 } else { total_correct as f64 / total_nodes as f64 })
 }
 
 /// Index of the maximum element in a slice.
-fn argmax(v: &[f64]) -> usize {
+pub(crate) fn argmax(v: &[f64]) -> usize {
     v.iter()
         .enumerate()
         .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
@@ -0,0 +1,153 @@
+use rand::seq::SliceRandom;
+
+use crate::loss::bce::BceLoss;
+use crate::loss::loss::Loss;
+use crate::loss::loss_type::LossType;
+use crate::network::network::Network;
+use crate::optim::optimizer::Optimizer;
+use crate::train::gradients::GradientBuffers;
+use crate::train::loop_fn::{
+    accumulate_gradients, accumulate_logit_gradients, apply_mean_gradients, fuses_sigmoid_bce,
+};
+
+/// Settings for `lr_finder`.
+///
+/// # Fields
+/// - `loss_type`   — loss to train and report
+/// - `min_lr`      — learning rate of the first mini-batch
+/// - `max_lr`      — learning rate of the last mini-batch
+/// - `num_batches` — number of mini-batches in the sweep; the rate grows by
+///   the same factor every batch
+/// - `batch_size`  — samples per mini-batch
+/// - `smoothing`   — exponential smoothing factor for the reported loss in
+///   `[0, 1)`; 0 reports the raw per-batch loss
+/// - `divergence_factor` — the sweep stops early once the smoothed loss
+///   exceeds this multiple of the best loss seen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LrFinderConfig {
+    pub loss_type: LossType,
+    pub min_lr: f64,
+    pub max_lr: f64,
+    pub num_batches: usize,
+    pub batch_size: usize,
+    pub smoothing: f64,
+    pub divergence_factor: f64,
+}
+
+impl LrFinderConfig {
+    /// Sweeps 1e-7 → 10 over 100 mini-batches of 32, with 0.98 smoothing and
+    /// stopping once the loss is 4× its best.
+    pub fn new(loss_type: LossType) -> LrFinderConfig {
+        LrFinderConfig {
+            loss_type,
+            min_lr: 1e-7,
+            max_lr: 10.0,
+            num_batches: 100,
+            batch_size: 32,
+            smoothing: 0.98,
+            divergence_factor: 4.0,
+        }
+    }
+}
+
+/// Learning-rate range test (Smith, 2017): trains a copy of `network` for up
+/// to `config.num_batches` mini-batches while increasing the learning rate
+/// exponentially from `min_lr` to `max_lr`, and returns the
+/// `(learning_rate, smoothed_loss)` pair of every batch.
+///
+/// Plot loss against learning rate (log scale): a good rate sits where the
+/// loss falls fastest, roughly an order of magnitude below the minimum —
+/// `suggested_learning_rate` picks that point.  `network` is left untouched;
+/// `optimizer` is stepped by the sweep (its learning rate is restored
+/// afterwards), so pass a fresh one and create another for the real run.
+/// Samples are drawn in shuffled order, wrapping around if the sweep needs
+/// more than one pass.
+///
+/// # Panics
+/// Panics if `inputs` is empty, lengths mismatch, `batch_size` or
+/// `num_batches` is 0, or `min_lr`/`max_lr` are not `0 < min_lr < max_lr`.
+pub fn lr_finder(
+    network: &Network,
+    inputs: &[Vec<f64>],
+    labels: &[Vec<f64>],
+    optimizer: &mut dyn Optimizer,
+    config: &LrFinderConfig,
+) -> Vec<(f64, f64)> {
+    assert!(!inputs.is_empty(), "inputs must not be empty");
+    assert_eq!(inputs.len(), labels.len(), "inputs and labels must have equal length");
+    assert!(config.batch_size > 0, "batch_size must be at least 1");
+    assert!(config.num_batches > 0, "num_batches must be at least 1");
+    assert!(
+        config.min_lr > 0.0 && config.min_lr < config.max_lr,
+        "learning rates must satisfy 0 < min_lr < max_lr"
+    );
+
+    let mut network = network.clone();
+    let base_lr = optimizer.learning_rate();
+    let growth = (config.max_lr / config.min_lr).powf(1.0 / (config.num_batches.max(2) - 1) as f64);
+
+    let mut rng = rand::thread_rng();
+    let mut order: Vec<usize> = (0..inputs.len()).collect();
+    order.shuffle(&mut rng);
+    let mut cursor = 0;
+
+    let mut points = Vec::with_capacity(config.num_batches);
+    let mut avg_loss = 0.0;
+    let mut best_loss = f64::INFINITY;
+    let mut lr = config.min_lr;
+    let fused = fuses_sigmoid_bce(&network, config.loss_type);
+    let mut acc_grads = GradientBuffers::for_network(&network);
+
+    for batch in 1..=config.num_batches {
+        optimizer.set_learning_rate(lr);
+        acc_grads.zero();
+        let mut batch_loss = 0.0;
+
+        for _ in 0..config.batch_size {
+            if cursor == order.len() {
+                order.shuffle(&mut rng);
+                cursor = 0;
+            }
+            let idx = order[cursor];
+            cursor += 1;
+
+            let output = network.forward(inputs[idx].clone());
+            batch_loss += config.loss_type.loss(&output, &labels[idx]);
+            if fused {
+                let error = BceLoss::logit_derivative(&output, &labels[idx]);
+                accumulate_logit_gradients(&network, &inputs[idx], error, &mut acc_grads)
+                    .unwrap_or_else(|e| panic!("{}", e));
+            } else {
+                let error = config.loss_type.derivative(&output, &labels[idx]);
+                accumulate_gradients(&network, &inputs[idx], error, &mut acc_grads)
+                    .unwrap_or_else(|e| panic!("{}", e));
+            }
+        }
+        apply_mean_gradients(&mut network, &mut acc_grads, config.batch_size as f64, optimizer);
+        batch_loss /= config.batch_size as f64;
+
+        // Bias-corrected exponential moving average, as in Adam.
+        avg_loss = config.smoothing * avg_loss + (1.0 - config.smoothing) * batch_loss;
+        let smoothed = avg_loss / (1.0 - config.smoothing.powi(batch as i32));
+        points.push((lr, smoothed));
+
+        if !smoothed.is_finite() || smoothed > config.divergence_factor * best_loss {
+            break;
+        }
+        best_loss = best_loss.min(smoothed);
+        lr *= growth;
+    }
+
+    optimizer.set_learning_rate(base_lr);
+    points
+}
+
+/// Picks a learning rate from an `lr_finder` sweep: one tenth of the rate
+/// with the lowest loss, a common rule of thumb that stays clear of the
+/// unstable region.  Returns `None` for an empty sweep.
+pub fn suggested_learning_rate(points: &[(f64, f64)]) -> Option<f64> {
+    points.iter()
+        .filter(|(_, loss)| loss.is_finite())
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|&(lr, _)| lr / 10.0)
+}
@@ -0,0 +1,79 @@
+use std::f64::consts::PI;
+use serde::{Serialize, Deserialize};
+
+/// Learning-rate schedule applied by `train_loop` at the start of every epoch.
+///
+/// The optimizer's own learning rate is the base (maximum) rate; the schedule
+/// scales it per epoch.
+///
+/// - `Constant`       — use the optimizer's learning rate unchanged (default).
+/// - `CosineRestarts` — cosine annealing with warm restarts (SGDR): within each
+///   cycle of `cycle_epochs` epochs the rate decays from the base rate down to
+///   `min_lr`, then jumps back up.  The last epoch of every cycle runs exactly
+///   at `min_lr`, which is where snapshot ensembles take their checkpoints.
+/// - `Triangular`     — cyclical learning rate (Smith, 2017): the rate climbs
+///   linearly from `min_lr` to the base rate over `step_epochs` epochs, falls
+///   back to `min_lr` over the next `step_epochs`, and repeats.  Epoch 1
+///   runs at `min_lr`.
+/// - `Triangular2`    — like `Triangular`, but the peak-to-floor amplitude is
+///   halved after every full cycle, so training settles down over time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LrSchedule {
+    #[default]
+    Constant,
+    CosineRestarts { cycle_epochs: usize, min_lr: f64 },
+    Triangular { step_epochs: usize, min_lr: f64 },
+    Triangular2 { step_epochs: usize, min_lr: f64 },
+}
+
+impl LrSchedule {
+    /// Learning rate to use for the given 1-based `epoch`.
+    pub fn learning_rate(&self, base_lr: f64, epoch: usize) -> f64 {
+        match *self {
+            LrSchedule::Constant => base_lr,
+            LrSchedule::CosineRestarts { cycle_epochs, min_lr } => {
+                if cycle_epochs <= 1 {
+                    return base_lr;
+                }
+                let pos = (epoch - 1) % cycle_epochs;
+                let t = pos as f64 / (cycle_epochs - 1) as f64;
+                min_lr + 0.5 * (base_lr - min_lr) * (1.0 + (PI * t).cos())
+            }
+            LrSchedule::Triangular { step_epochs, min_lr } => {
+                min_lr + (base_lr - min_lr) * triangle(epoch, step_epochs)
+            }
+            LrSchedule::Triangular2 { step_epochs, min_lr } => {
+                let cycle = (epoch - 1) / (2 * step_epochs.max(1));
+                let amplitude = (base_lr - min_lr) / 2f64.powi(cycle as i32);
+                min_lr + amplitude * triangle(epoch, step_epochs)
+            }
+        }
+    }
+
+    /// Returns `true` if `epoch` (1-based) is the last epoch of a cycle, i.e.
+    /// the point where the learning rate reaches its minimum (for the
+    /// triangular schedules: the last epoch before it is back at `min_lr`).
+    /// Always `false` for `Constant`.
+    pub fn is_cycle_end(&self, epoch: usize) -> bool {
+        match *self {
+            LrSchedule::Constant => false,
+            LrSchedule::CosineRestarts { cycle_epochs, .. } => {
+                cycle_epochs > 0 && epoch.is_multiple_of(cycle_epochs)
+            }
+            LrSchedule::Triangular { step_epochs, .. } | LrSchedule::Triangular2 { step_epochs, .. } => {
+                step_epochs > 0 && epoch.is_multiple_of(2 * step_epochs)
+            }
+        }
+    }
+}
+
+/// Position on the triangle wave for 1-based `epoch`: 0 at the start of each
+/// cycle, 1 after `step_epochs` epochs, back to 0 after `2 * step_epochs`.
+fn triangle(epoch: usize, step_epochs: usize) -> f64 {
+    let step = step_epochs.max(1) as f64;
+    let e = (epoch - 1) as f64;
+    let cycle = (1.0 + e / (2.0 * step)).floor();
+    let x = (e / step - 2.0 * cycle + 1.0).abs();
+    (1.0 - x).max(0.0)
+}
@@ -0,0 +1,56 @@
+use rand::Rng;
+
+use crate::math::matrix::Matrix;
+
+/// Mixup augmentation (Zhang et al., 2018): returns the convex combination
+/// `lambda · a + (1 − lambda) · b` of two samples, applied to both the inputs
+/// and the labels.
+///
+/// For one-hot labels the result is a soft target, so mixup pairs naturally
+/// with `CrossEntropy` and `BinaryCrossEntropy` losses.
+pub fn mixup(
+    input_a: &[f64],
+    label_a: &[f64],
+    input_b: &[f64],
+    label_b: &[f64],
+    lambda: f64,
+) -> (Vec<f64>, Vec<f64>) {
+    let blend = |a: &[f64], b: &[f64]| -> Vec<f64> {
+        a.iter().zip(b.iter()).map(|(x, y)| lambda * x + (1.0 - lambda) * y).collect()
+    };
+    (blend(input_a, input_b), blend(label_a, label_b))
+}
+
+/// Samples from the symmetric Beta(alpha, alpha) distribution as
+/// `X / (X + Y)` with `X, Y ~ Gamma(alpha, 1)`.
+///
+/// # Panics
+/// Panics if `alpha <= 0`.
+pub fn sample_beta<R: Rng + ?Sized>(rng: &mut R, alpha: f64) -> f64 {
+    assert!(alpha > 0.0, "mixup alpha must be positive");
+    let x = sample_gamma(rng, alpha);
+    let y = sample_gamma(rng, alpha);
+    if x + y > 0.0 { x / (x + y) } else { 0.5 }
+}
+
+/// Samples from Gamma(shape, 1) using the Marsaglia–Tsang method.
+/// Shapes below 1 are boosted: `Gamma(a) = Gamma(a + 1) · U^(1/a)`.
+fn sample_gamma<R: Rng + ?Sized>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = 1.0 - rng.gen::<f64>();
+        return sample_gamma(rng, shape + 1.0) * u.powf(1.0 / shape);
+    }
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = Matrix::sample_standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = 1.0 - rng.gen::<f64>();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
@@ -1,9 +1,49 @@
 pub mod trainer;
 pub mod epoch_stats;
+pub mod batch_stats;
 pub mod train_config;
 pub mod loop_fn;
+pub mod lr_schedule;
+pub mod sample_order;
+pub mod snapshot;
+pub mod distill;
+pub mod graph;
+pub mod autoencoder;
+pub mod mixup;
+pub mod augment;
+pub mod layer_stats;
+pub mod layer_timing;
+pub mod layer_norms;
+pub mod callback;
+pub mod error;
+pub mod outcome;
+pub mod early_stopping;
+pub mod reporter;
+pub mod checkpoint;
+pub mod lr_finder;
+pub mod grad_check;
+mod gradients;
 
-pub use trainer::train_network;
+pub use trainer::{train_network, try_train_network};
 pub use epoch_stats::EpochStats;
-pub use train_config::TrainConfig;
-pub use loop_fn::train_loop;
+pub use batch_stats::BatchStats;
+pub use outcome::TrainOutcome;
+pub use layer_stats::LayerStats;
+pub use layer_timing::LayerTiming;
+pub use layer_norms::LayerNorms;
+pub use train_config::{TrainConfig, SampleSchedule, SampleTransform};
+pub use augment::{gaussian_noise, ImageAugment};
+pub use loop_fn::{train_loop, train_loop_with_callbacks, try_train_loop};
+pub use error::{NonFinitePolicy, NonFiniteSource, TrainError};
+pub use callback::TrainCallback;
+pub use early_stopping::EarlyStopping;
+pub use reporter::ConsoleReporter;
+pub use checkpoint::{Checkpoint, CheckpointWriter};
+pub use lr_finder::{lr_finder, suggested_learning_rate, LrFinderConfig};
+pub use grad_check::{grad_check, GradCheck};
+pub use lr_schedule::LrSchedule;
+pub use sample_order::SampleOrder;
+pub use snapshot::train_snapshot_ensemble;
+pub use distill::distill;
+pub use graph::{train_graph, try_train_graph};
+pub use autoencoder::{train_autoencoder, try_train_autoencoder};
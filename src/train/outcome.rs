@@ -0,0 +1,15 @@
+/// Summary of a finished `try_train_loop` run.
+///
+/// # Fields
+/// - `final_loss`    — mean training loss of the last completed epoch
+///   (0 if no epoch ran)
+/// - `last_epoch`    — 1-based number of the last completed epoch;
+///   `config.initial_epoch` if none ran
+/// - `stopped_early` — `true` when the stop flag, a dropped progress receiver
+///   or a callback ended the run before `config.epochs`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainOutcome {
+    pub final_loss: f64,
+    pub last_epoch: usize,
+    pub stopped_early: bool,
+}
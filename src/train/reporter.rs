@@ -0,0 +1,174 @@
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::network::network::Network;
+use crate::train::callback::TrainCallback;
+use crate::train::epoch_stats::EpochStats;
+
+/// Terminal progress output for `train_loop_with_callbacks`.
+///
+/// Prints, on stdout:
+/// - an in-place progress bar for the running epoch (redrawn with `\r`),
+/// - one metrics line per completed epoch, replacing the bar,
+/// - a short summary once training ends.
+///
+/// Public fields may be changed after `new()`:
+/// - `show_progress` — draw the per-epoch progress bar (default `true`);
+///   turn off when stdout is not a terminal, e.g. when logging to a file
+/// - `bar_width`     — width of the progress bar in characters (default 30)
+pub struct ConsoleReporter {
+    pub show_progress: bool,
+    pub bar_width: usize,
+    total_epochs: usize,
+    epoch: usize,
+    filled: usize,
+    run_start: Option<Instant>,
+    last: Option<EpochStats>,
+    best_val_loss: Option<(usize, f64)>,
+}
+
+impl ConsoleReporter {
+    pub fn new() -> ConsoleReporter {
+        ConsoleReporter {
+            show_progress: true,
+            bar_width: 30,
+            total_epochs: 0,
+            epoch: 0,
+            filled: 0,
+            run_start: None,
+            last: None,
+            best_val_loss: None,
+        }
+    }
+
+    fn draw_bar(&self, batch: usize, total_batches: usize) {
+        let percent = (batch * 100).checked_div(total_batches).unwrap_or(100);
+        let bar: String = (0..self.bar_width)
+            .map(|i| if i < self.filled { '=' } else if i == self.filled { '>' } else { ' ' })
+            .collect();
+        print!(
+            "\rEpoch {:>w$}/{} [{}] {:>3}%",
+            self.epoch,
+            self.total_epochs,
+            bar,
+            percent,
+            w = epoch_width(self.total_epochs),
+        );
+        io::stdout().flush().ok();
+    }
+}
+
+impl Default for ConsoleReporter {
+    fn default() -> Self {
+        ConsoleReporter::new()
+    }
+}
+
+impl TrainCallback for ConsoleReporter {
+    fn on_train_start(&mut self, total_epochs: usize) {
+        self.total_epochs = total_epochs;
+        self.run_start = Some(Instant::now());
+        self.last = None;
+        self.best_val_loss = None;
+    }
+
+    fn on_epoch_start(&mut self, epoch: usize, total_batches: usize) {
+        self.epoch = epoch;
+        self.filled = 0;
+        if self.show_progress {
+            self.draw_bar(0, total_batches);
+        }
+    }
+
+    fn on_batch_end(&mut self, batch: usize, total_batches: usize) {
+        if !self.show_progress || total_batches == 0 {
+            return;
+        }
+        // Only redraw when the bar actually grows — a large dataset has
+        // thousands of batches per epoch.
+        let filled = batch * self.bar_width / total_batches;
+        if filled != self.filled || batch == total_batches {
+            self.filled = filled;
+            self.draw_bar(batch, total_batches);
+        }
+    }
+
+    fn on_epoch_end(&mut self, _network: &Network, stats: &EpochStats) {
+        let mut line = format!(
+            "Epoch {:>w$}/{}  loss {:>10.6}",
+            stats.epoch,
+            stats.total_epochs,
+            stats.train_loss,
+            w = epoch_width(stats.total_epochs),
+        );
+        if let Some(acc) = stats.train_accuracy {
+            line.push_str(&format!("  acc {:>6.2}%", acc * 100.0));
+        }
+        if let Some(val_loss) = stats.val_loss {
+            line.push_str(&format!("  val_loss {:>10.6}", val_loss));
+        }
+        if let Some(val_acc) = stats.val_accuracy {
+            line.push_str(&format!("  val_acc {:>6.2}%", val_acc * 100.0));
+        }
+        line.push_str(&format!("  {:>8}", format_ms(stats.elapsed_ms)));
+
+        if self.show_progress {
+            // Clear the progress bar before printing the metrics line over it.
+            print!("\r{}\r", " ".repeat(self.bar_width + epoch_width(self.total_epochs) * 2 + 16));
+        }
+        println!("{}", line);
+
+        if let Some(val_loss) = stats.val_loss {
+            if self.best_val_loss.is_none_or(|(_, best)| val_loss < best) {
+                self.best_val_loss = Some((stats.epoch, val_loss));
+            }
+        }
+        self.last = Some(stats.clone());
+    }
+
+    fn on_train_end(&mut self, _network: &Network) {
+        let total_ms = self.run_start
+            .map(|t| t.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+        let Some(ref last) = self.last else {
+            println!("Training stopped before the first epoch completed.");
+            return;
+        };
+
+        println!(
+            "\nTraining finished: {}/{} epochs in {}",
+            last.epoch,
+            self.total_epochs,
+            format_ms(total_ms),
+        );
+        println!("  final train loss  {:.6}", last.train_loss);
+        if let Some(acc) = last.train_accuracy {
+            println!("  final train acc   {:.2}%", acc * 100.0);
+        }
+        if let Some(val_loss) = last.val_loss {
+            println!("  final val loss    {:.6}", val_loss);
+        }
+        if let Some(val_acc) = last.val_accuracy {
+            println!("  final val acc     {:.2}%", val_acc * 100.0);
+        }
+        if let Some((epoch, best)) = self.best_val_loss {
+            println!("  best val loss     {:.6} (epoch {})", best, epoch);
+        }
+    }
+}
+
+/// Number of digits needed to print `total_epochs`, so epoch columns align.
+fn epoch_width(total_epochs: usize) -> usize {
+    total_epochs.max(1).to_string().len()
+}
+
+/// Human-readable duration: `850ms`, `12.3s`, `4m 05s`.
+fn format_ms(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m {:02}s", ms / 60_000, (ms % 60_000) / 1_000)
+    }
+}
@@ -0,0 +1,38 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Serialize, Deserialize};
+
+/// Order in which `train_loop` visits the training samples each epoch.
+///
+/// - `Shuffle`    — a fresh random permutation every epoch (default)
+/// - `Sequential` — dataset order, every epoch; handy for deterministic
+///   debugging without fixing a seed
+/// - `LossSorted` — curriculum order: easiest first, ranked by each sample's
+///   training loss in the previous epoch.  The first epoch of a run has no
+///   losses yet and is shuffled.
+///
+/// `TrainConfig::sample_schedule`, when set, replaces the ordering entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleOrder {
+    #[default]
+    Shuffle,
+    Sequential,
+    LossSorted,
+}
+
+impl SampleOrder {
+    /// Sample indices `0..n` in visiting order.  `previous_losses` holds the
+    /// last epoch's loss per sample (`LossSorted` only; NaN = not visited).
+    pub fn order(&self, n: usize, previous_losses: Option<&[f64]>, rng: &mut StdRng) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..n).collect();
+        match (self, previous_losses) {
+            (SampleOrder::Sequential, _) => {}
+            (SampleOrder::LossSorted, Some(losses)) if !losses.iter().any(|l| l.is_nan()) => {
+                indices.sort_by(|&a, &b| losses[a].total_cmp(&losses[b]));
+            }
+            _ => indices.shuffle(rng),
+        }
+        indices
+    }
+}
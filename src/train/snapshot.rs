@@ -0,0 +1,65 @@
+use crate::data::dataset::Dataset;
+use crate::network::ensemble::Ensemble;
+use crate::network::network::Network;
+use crate::optim::optimizer::Optimizer;
+use crate::train::callback::TrainCallback;
+use crate::train::epoch_stats::EpochStats;
+use crate::train::loop_fn::train_loop_with_callbacks;
+use crate::train::lr_schedule::LrSchedule;
+use crate::train::train_config::TrainConfig;
+
+/// Trains `network` with a cyclic learning rate and returns a snapshot
+/// ensemble (Huang et al., "Snapshot Ensembles: Train 1, get M for free").
+///
+/// `config.lr_schedule` must be `LrSchedule::CosineRestarts`.  The network is
+/// copied into the ensemble at the end of every cycle — the point where the
+/// learning rate hits its minimum and the weights sit in a local optimum —
+/// so `config.epochs / cycle_epochs` members are produced.  `network` itself
+/// ends up holding the final snapshot.
+///
+/// Everything else (progress channel, stop flag, sample weighting) behaves as
+/// in `train_loop`.  Stopping early keeps the snapshots collected so far.
+///
+/// # Panics
+/// Panics if `config.lr_schedule` is not `CosineRestarts`, or for any of the
+/// reasons listed on `train_loop`.
+pub fn train_snapshot_ensemble(
+    network: &mut Network,
+    train: &dyn Dataset,
+    val: Option<&dyn Dataset>,
+    optimizer: &mut dyn Optimizer,
+    config: &TrainConfig,
+) -> Ensemble {
+    assert!(
+        matches!(config.lr_schedule, LrSchedule::CosineRestarts { .. }),
+        "snapshot ensembles require LrSchedule::CosineRestarts"
+    );
+
+    let mut collector = SnapshotCollector {
+        schedule: config.lr_schedule,
+        ensemble: Ensemble::default(),
+    };
+    train_loop_with_callbacks(
+        network,
+        train,
+        val,
+        optimizer,
+        config,
+        &mut [&mut collector],
+    );
+    collector.ensemble
+}
+
+/// Copies the network into the ensemble whenever a learning-rate cycle ends.
+struct SnapshotCollector {
+    schedule: LrSchedule,
+    ensemble: Ensemble,
+}
+
+impl TrainCallback for SnapshotCollector {
+    fn on_epoch_end(&mut self, network: &Network, stats: &EpochStats) {
+        if self.schedule.is_cycle_end(stats.epoch) {
+            self.ensemble.push(network.clone());
+        }
+    }
+}
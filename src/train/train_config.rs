@@ -1,7 +1,18 @@
 use std::sync::mpsc;
 use std::sync::{Arc, atomic::AtomicBool};
+use crate::data::scaling::ScalingMethod;
+use crate::loss::loss::Loss;
+use crate::preprocess::polynomial::PolynomialFeatures;
 use crate::loss::loss_type::LossType;
+use crate::loss::reduction::Reduction;
+use crate::train::augment::ImageAugment;
+use crate::train::batch_stats::BatchStats;
 use crate::train::epoch_stats::EpochStats;
+use crate::train::error::NonFinitePolicy;
+use crate::train::lr_schedule::LrSchedule;
+use crate::train::sample_order::SampleOrder;
+#[cfg(feature = "toml")]
+use crate::config::{parse_section, ConfigError};
 
 /// Configuration for a `train_loop` run.
 ///
@@ -10,27 +21,317 @@ use crate::train::epoch_stats::EpochStats;
 /// - `batch_size`  — samples per mini-batch; use `1` for online SGD
 /// - `loss_type`   — which loss function to use (`Mse` or `CrossEntropy`)
 /// - `progress_tx` — optional channel sender; one `EpochStats` is sent per
-///                   completed epoch.  If the receiver is dropped the loop
-///                   terminates early (clean shutdown).
+///   completed epoch.  If the receiver is dropped the loop
+///   terminates early (clean shutdown).
+/// - `batch_tx`    — optional channel sender; one `BatchStats` is sent after
+///   every mini-batch for intra-epoch progress.  Unlike `progress_tx`, a
+///   dropped receiver does not stop training.
 /// - `stop_flag`   — optional atomic flag; when set to `true` from another
-///                   thread the loop terminates after the current epoch.
+///   thread the loop terminates after the current epoch.
+/// - `sample_weights`  — optional importance weight per training sample (same
+///   length as the training set); each sample's loss and gradient are scaled
+///   by its weight.
+/// - `sample_order` — order in which samples are visited each epoch: full
+///   shuffle (default), sequential, or sorted by the previous epoch's loss
+///   (see `SampleOrder`).
+/// - `sample_schedule` — optional curriculum hook deciding which samples are
+///   visited each epoch, in which order and with which weight.  Replaces
+///   `sample_order` when set.
+/// - `lr_schedule` — per-epoch learning-rate schedule; the optimizer's rate is
+///   the base rate (default: `LrSchedule::Constant`).
+/// - `mixup_alpha` — enables mixup augmentation for classification; the
+///   mixing coefficient is drawn from `Beta(alpha, alpha)` (0.2–0.4 is a
+///   good starting point).  `None` disables it.
+/// - `augment`     — optional per-sample transform applied on the fly to a
+///   copy of every training sample (after mixup, before the forward pass),
+///   e.g. `gaussian_noise`.  Validation data is never augmented.
+/// - `image_augment` — optional random image transforms (flips, small
+///   rotations and shifts, brightness jitter, noise) applied to every
+///   training sample after mixup and before `augment`, drawn from the
+///   epoch's generator (see `ImageAugment`).  `None` (default) disables it.
+/// - `record_layer_stats` — when `true`, every `EpochStats` carries per-layer
+///   weight/bias mean, std and max-abs (see `LayerStats`).
+/// - `record_layer_timings` — when `true`, every `EpochStats` of
+///   `train_loop` carries the time each layer spent in the training forward
+///   and backward passes (see `LayerTiming`), to find the layer that
+///   dominates the runtime.  The clock reads add a little overhead, so it is
+///   off by default.
+/// - `record_layer_norms` — when `true`, every `EpochStats` of `train_loop`
+///   carries each layer's weight norm, gradient norm and update ratio (see
+///   `LayerNorms`), to spot dead ReLUs and exploding gradients.  Off by
+///   default: it copies the weights around every step.
+/// - `initial_epoch` — number of epochs already completed; training resumes
+///   at epoch `initial_epoch + 1` and still ends at `epochs`.  Set it from
+///   `Checkpoint::epoch` when resuming (default 0).
+/// - `l1` / `l2`   — weight-decay strengths: the loss being minimized gains
+///   `l1 · Σ|w| + (l2 / 2) · Σw²` over all weights (biases are exempt).
+///   Both default to 0 (off).  A layer with its own `l2` (`LayerSpec::l2`,
+///   `Layer::with_l2`) uses that instead of the global one.  The reported
+///   `train_loss` is the data loss alone, so it stays comparable across
+///   regularization settings.
+/// - `seed`        — makes the shuffling (and mixup sampling) reproducible:
+///   two runs with the same seed, starting from the same network (see
+///   `Network::new_seeded`), produce identical loss curves.  Each epoch's
+///   generator is derived from the seed and the epoch number, so a resumed
+///   run matches an uninterrupted one.  `None` (default) draws fresh
+///   randomness.
+/// - `class_weights` — optional per-class weight vector for `CrossEntropy`
+///   (one entry per output); each sample's loss and gradient are scaled by
+///   the weight of its class (see `CrossEntropyLoss::weighted_loss` and
+///   `CrossEntropyLoss::balanced_weights`).  The reported training and
+///   validation losses are both the weighted ones.  Training rejects them
+///   with any other loss type or a `custom_loss`.
+/// - `label_smoothing` — epsilon for `CrossEntropy` targets: each label `y`
+///   over `K` classes is trained as `(1 − ε) · y + ε / K`, which keeps the
+///   Softmax from becoming overconfident (0.05–0.1 is typical; default 0 =
+///   off).  Training loss is measured against the smoothed targets;
+///   accuracy and validation loss use the original labels.
+/// - `reduction`   — how each sample's per-output loss terms are combined,
+///   for training, the reported losses and the gradients alike (see
+///   `Reduction`).  `None` (default) keeps each loss type's own convention:
+///   cross-entropy sums over classes, the other losses average.
+///   `Reduction::Mean` makes `train_loss` / `val_loss` comparable across
+///   loss types.  `Reduction::None` is rejected by `validate`.
+/// - `custom_loss` — optional user-defined loss used for training and
+///   validation loss instead of `loss_type`'s built-in one.  `loss_type`
+///   still decides how accuracy is measured.  `class_weights` and
+///   `reduction` only apply to the built-in losses.
+/// - `non_finite`  — what to do when a batch yields a NaN/infinite loss or
+///   gradient: abort with `TrainError::NonFinite` (default) or skip the batch.
+/// - `input_scaling` — fit per-feature scaling (`Standard` or `MinMax`) on
+///   the training inputs, train on scaled inputs and record the statistics
+///   in the model's metadata so inference applies them to raw inputs (see
+///   `train_loop`).  `None` (default) trains on the inputs as given.
+/// - `polynomial_features` — expand every raw input with degree-2 products
+///   (see `PolynomialFeatures`) before scaling, and record the expansion in
+///   the model's metadata so inference expands raw inputs too.  The
+///   network's first layer takes `output_size()` values; the data keeps
+///   `n_features`.  `None` (default) trains on the inputs as given.
 pub struct TrainConfig {
     pub epochs: usize,
     pub batch_size: usize,
     pub loss_type: LossType,
     pub progress_tx: Option<mpsc::Sender<EpochStats>>,
+    pub batch_tx: Option<mpsc::Sender<BatchStats>>,
     pub stop_flag: Option<Arc<AtomicBool>>,
+    pub sample_weights: Option<Vec<f64>>,
+    pub sample_order: SampleOrder,
+    pub sample_schedule: Option<SampleSchedule>,
+    pub lr_schedule: LrSchedule,
+    pub mixup_alpha: Option<f64>,
+    pub augment: Option<SampleTransform>,
+    pub image_augment: Option<ImageAugment>,
+    pub record_layer_stats: bool,
+    pub record_layer_timings: bool,
+    pub record_layer_norms: bool,
+    pub initial_epoch: usize,
+    pub l1: f64,
+    pub l2: f64,
+    pub seed: Option<u64>,
+    pub class_weights: Option<Vec<f64>>,
+    pub label_smoothing: f64,
+    pub reduction: Option<Reduction>,
+    pub custom_loss: Option<Box<dyn Loss>>,
+    pub non_finite: NonFinitePolicy,
+    pub input_scaling: Option<ScalingMethod>,
+    pub polynomial_features: Option<PolynomialFeatures>,
+}
+
+/// Per-epoch sample schedule used for curriculum learning.
+///
+/// Called at the start of every epoch with `(epoch, n_samples)` (`epoch` is
+/// 1-based) and returns the `(sample_index, weight)` pairs to train on, in
+/// visiting order.  Samples may be omitted (e.g. hard examples held back in
+/// early epochs) or repeated.  The returned weight is multiplied with
+/// `TrainConfig::sample_weights` when both are set.
+pub type SampleSchedule = Box<dyn Fn(usize, usize) -> Vec<(usize, f64)> + Send + Sync>;
+
+/// Per-sample data augmentation hook.
+///
+/// Called with mutable copies of one training sample's input and label each
+/// time the sample is visited; the dataset itself is never modified, so a
+/// fresh variant is produced every epoch.
+pub type SampleTransform = Box<dyn Fn(&mut Vec<f64>, &mut Vec<f64>) + Send + Sync>;
+
+/// The subset of `TrainConfig` that can be written in a config file; the
+/// channels and callbacks are runtime-only.
+#[cfg(feature = "toml")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrainConfigFile {
+    epochs: usize,
+    batch_size: usize,
+    loss: LossType,
+    #[serde(default)]
+    sample_order: SampleOrder,
+    #[serde(default)]
+    lr_schedule: LrSchedule,
+    #[serde(default)]
+    mixup_alpha: Option<f64>,
+    #[serde(default)]
+    image_augment: Option<ImageAugment>,
+    #[serde(default)]
+    record_layer_stats: bool,
+    #[serde(default)]
+    record_layer_timings: bool,
+    #[serde(default)]
+    record_layer_norms: bool,
+    #[serde(default)]
+    l1: f64,
+    #[serde(default)]
+    l2: f64,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    class_weights: Option<Vec<f64>>,
+    #[serde(default)]
+    label_smoothing: f64,
+    #[serde(default)]
+    reduction: Option<Reduction>,
+    #[serde(default)]
+    input_scaling: Option<ScalingMethod>,
+    #[serde(default)]
+    polynomial_features: Option<PolynomialFeatures>,
 }
 
 impl TrainConfig {
-    /// Creates a minimal `TrainConfig` with no progress channel and no stop flag.
+    /// Creates a minimal `TrainConfig` with no progress channels, no stop flag
+    /// and uniformly weighted, shuffled samples.
     pub fn new(epochs: usize, batch_size: usize, loss_type: LossType) -> Self {
         TrainConfig {
             epochs,
             batch_size,
             loss_type,
             progress_tx: None,
+            batch_tx: None,
             stop_flag: None,
+            sample_weights: None,
+            sample_order: SampleOrder::Shuffle,
+            sample_schedule: None,
+            lr_schedule: LrSchedule::Constant,
+            mixup_alpha: None,
+            augment: None,
+            image_augment: None,
+            record_layer_stats: false,
+            record_layer_timings: false,
+            record_layer_norms: false,
+            initial_epoch: 0,
+            l1: 0.0,
+            l2: 0.0,
+            seed: None,
+            class_weights: None,
+            label_smoothing: 0.0,
+            reduction: None,
+            custom_loss: None,
+            non_finite: NonFinitePolicy::Abort,
+            input_scaling: None,
+            polynomial_features: None,
+        }
+    }
+
+    /// Parses the serializable fields (`epochs`, `batch_size`, `loss`, and
+    /// optionally `sample_order`, `lr_schedule`, `mixup_alpha`, `image_augment`,
+    /// `record_layer_stats`, `record_layer_timings`, `record_layer_norms`, `l1`,
+    /// `l2`, `seed`, `class_weights`, `label_smoothing`, `reduction`,
+    /// `input_scaling`, `polynomial_features`) from TOML — a standalone
+    /// document or the `[train]` table of an experiment file (see `config`) —
+    /// and validates them.  The remaining fields start out as in `new`.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(doc: &str) -> Result<TrainConfig, ConfigError> {
+        let file: TrainConfigFile = parse_section(doc, "train")?
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.to_string().trim_end().to_owned()))?;
+        let mut config = TrainConfig::new(file.epochs, file.batch_size, file.loss);
+        config.sample_order       = file.sample_order;
+        config.lr_schedule        = file.lr_schedule;
+        config.mixup_alpha        = file.mixup_alpha;
+        config.image_augment      = file.image_augment;
+        config.record_layer_stats = file.record_layer_stats;
+        config.record_layer_timings = file.record_layer_timings;
+        config.record_layer_norms = file.record_layer_norms;
+        config.l1                 = file.l1;
+        config.l2                 = file.l2;
+        config.seed               = file.seed;
+        config.class_weights      = file.class_weights;
+        config.label_smoothing    = file.label_smoothing;
+        config.reduction          = file.reduction;
+        config.input_scaling      = file.input_scaling;
+        config.polynomial_features = file.polynomial_features;
+        config.validate().map_err(ConfigError::Invalid)?;
+        Ok(config)
+    }
+
+    /// Reads and parses a TOML file with `from_toml`.
+    #[cfg(feature = "toml")]
+    pub fn load_toml(path: &str) -> Result<TrainConfig, ConfigError> {
+        TrainConfig::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks the hyperparameters for values `train_loop` cannot run with.
+    /// Returns one message per problem.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.epochs == 0 {
+            errors.push("epochs must be at least 1".to_owned());
+        }
+        if self.batch_size == 0 {
+            errors.push("batch_size must be at least 1".to_owned());
+        }
+        if let Some(alpha) = self.mixup_alpha {
+            if !(alpha > 0.0 && alpha.is_finite()) {
+                errors.push(format!("mixup_alpha must be positive, got {}", alpha));
+            }
+        }
+        if let Some(Err(problems)) = self.image_augment.as_ref().map(ImageAugment::validate) {
+            errors.extend(problems);
+        }
+        if let LrSchedule::CosineRestarts { cycle_epochs, min_lr } = self.lr_schedule {
+            if cycle_epochs == 0 {
+                errors.push("lr_schedule.cycle_epochs must be at least 1".to_owned());
+            }
+            if !(min_lr >= 0.0 && min_lr.is_finite()) {
+                errors.push(format!("lr_schedule.min_lr must be non-negative, got {}", min_lr));
+            }
+        }
+        if let LrSchedule::Triangular { step_epochs, min_lr } | LrSchedule::Triangular2 { step_epochs, min_lr } =
+            self.lr_schedule
+        {
+            if step_epochs == 0 {
+                errors.push("lr_schedule.step_epochs must be at least 1".to_owned());
+            }
+            if !(min_lr >= 0.0 && min_lr.is_finite()) {
+                errors.push(format!("lr_schedule.min_lr must be non-negative, got {}", min_lr));
+            }
+        }
+        for (name, value) in [("l1", self.l1), ("l2", self.l2)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                errors.push(format!("{} must be non-negative, got {}", name, value));
+            }
+        }
+        if let Some(weights) = &self.class_weights {
+            if self.loss_type != LossType::CrossEntropy {
+                errors.push("class_weights require the cross_entropy loss".to_owned());
+            }
+            if weights.iter().any(|w| !(*w >= 0.0 && w.is_finite())) {
+                errors.push("class_weights must be non-negative".to_owned());
+            }
+        }
+        if !(0.0..1.0).contains(&self.label_smoothing) {
+            errors.push(format!("label_smoothing must lie in [0, 1), got {}", self.label_smoothing));
+        } else if self.label_smoothing > 0.0 && self.loss_type != LossType::CrossEntropy {
+            errors.push("label_smoothing requires the cross_entropy loss".to_owned());
+        }
+        if self.reduction == Some(Reduction::None) {
+            errors.push("reduction \"none\" has no scalar training loss; use mean or sum".to_owned());
+        }
+        if self.custom_loss.is_some() && (self.class_weights.is_some() || self.reduction.is_some()) {
+            errors.push("class_weights and reduction do not apply to a custom loss".to_owned());
+        }
+        if let Some(weights) = &self.sample_weights {
+            if weights.iter().any(|w| !(*w >= 0.0 && w.is_finite())) {
+                errors.push("sample_weights must be non-negative".to_owned());
+            }
         }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
     }
 }
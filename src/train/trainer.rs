@@ -1,9 +1,11 @@
 use rand::seq::SliceRandom;
 use crate::{
-    math::matrix::Matrix,
     network::network::Network,
     loss::mse::MseLoss,
-    optim::sgd::Sgd,
+    optim::optimizer::Optimizer,
+    train::error::TrainError,
+    train::gradients::GradientBuffers,
+    train::loop_fn::check_split,
 };
 
 /// Trains the network for one epoch using mini-batch SGD.
@@ -12,22 +14,44 @@ use crate::{
 /// * `network`          — the network to train (mutated in place)
 /// * `inputs`           — slice of input samples
 /// * `expected_outputs` — corresponding target outputs, same length as `inputs`
-/// * `optimizer`        — SGD optimizer (holds learning rate)
+/// * `optimizer`        — update rule (`Sgd`, `Adam`, …; holds learning rate)
 /// * `batch_size`       — number of samples per mini-batch; pass `1` for
-///                        online (sample-by-sample) SGD
+///   online (sample-by-sample) SGD
 ///
 /// # Returns
 /// Mean loss over all samples in the epoch.
+///
+/// # Panics
+/// Panics with the `TrainError` message if `inputs` is empty, the lengths
+/// or sample widths do not match the network, or `batch_size == 0`;
+/// `try_train_network` returns these instead.
 pub fn train_network(
     network: &mut Network,
     inputs: &[Vec<f64>],
     expected_outputs: &[Vec<f64>],
-    optimizer: &Sgd,
+    optimizer: &mut dyn Optimizer,
     batch_size: usize,
 ) -> f64 {
-    assert!(!inputs.is_empty(), "inputs must not be empty");
-    assert_eq!(inputs.len(), expected_outputs.len(), "inputs and expected_outputs must have equal length");
-    assert!(batch_size > 0, "batch_size must be at least 1");
+    try_train_network(network, inputs, expected_outputs, optimizer, batch_size)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Same as `train_network`, but invalid arguments are returned as a
+/// `TrainError` (checked before the network is touched).
+pub fn try_train_network(
+    network: &mut Network,
+    inputs: &[Vec<f64>],
+    expected_outputs: &[Vec<f64>],
+    optimizer: &mut dyn Optimizer,
+    batch_size: usize,
+) -> Result<f64, TrainError> {
+    if inputs.is_empty() {
+        return Err(TrainError::EmptyInputs { split: "train" });
+    }
+    check_split(network, "train", inputs, expected_outputs)?;
+    if batch_size == 0 {
+        return Err(TrainError::InvalidConfig("batch_size must be at least 1".into()));
+    }
 
     let n = inputs.len();
     let mut total_loss = 0.0;
@@ -36,21 +60,16 @@ pub fn train_network(
     let mut indices: Vec<usize> = (0..n).collect();
     indices.shuffle(&mut rand::thread_rng());
 
+    // Accumulated gradient storage: one (w_grad, b_grad) pair per layer,
+    // allocated once and reset before every mini-batch.
+    let mut acc_grads = GradientBuffers::for_network(network);
+
     // Process in mini-batches.
     for batch_start in (0..n).step_by(batch_size) {
         let batch_end = (batch_start + batch_size).min(n);
         let actual_batch_size = (batch_end - batch_start) as f64;
 
-        // Initialize accumulated gradient storage: one (w_grad, b_grad) pair
-        // per layer, all zeros with the correct shapes.
-        let mut acc_grads: Vec<(Matrix, Matrix)> = network.layers.iter()
-            .map(|layer| {
-                (
-                    Matrix::zeros(layer.weights.rows, layer.weights.cols),
-                    Matrix::zeros(layer.biases.rows, layer.biases.cols),
-                )
-            })
-            .collect();
+        acc_grads.zero();
 
         // Accumulate gradients over all samples in the mini-batch.
         for &idx in &indices[batch_start..batch_end] {
@@ -64,42 +83,37 @@ pub fn train_network(
             total_loss += MseLoss::loss(&output, expected);
 
             // Initial delta: ∂L/∂a_output
-            let error = MseLoss::derivative(&output, expected);
-            let mut delta = Matrix::from_data(vec![error]);
+            let mut delta = MseLoss::derivative(&output, expected);
 
             // Backward pass — accumulate raw gradients (not yet scaled by lr).
             for i in (0..network.layers.len()).rev() {
-                let input_for_layer = if i == 0 {
-                    Matrix::from_data(vec![input.clone()])
+                let input_for_layer: &[f64] = if i == 0 {
+                    input
                 } else {
-                    network.layers[i - 1].neurons.clone()
+                    network.layers[i - 1].output()
                 };
 
-                let (w_grad, b_grad) = network.layers[i].compute_gradients(
-                    delta.clone(),
-                    &input_for_layer,
-                );
+                // Accumulate: acc += grad, straight into the buffers.
+                let input_delta = network.layers[i]
+                    .backward_into(input_for_layer, &delta, false, i > 0, &mut acc_grads[i])
+                    .map_err(|error| TrainError::GradientShape { layer: i, error })?;
 
-                if i > 0 {
-                    // Propagate δ back through weights to the previous layer.
-                    delta = b_grad.clone() * network.layers[i].weights.transpose();
+                if let Some(input_delta) = input_delta {
+                    // Propagate δ back to the previous layer.
+                    delta = input_delta;
                 }
-
-                // Accumulate: acc += grad  (element-wise addition)
-                acc_grads[i].0 = acc_grads[i].0.clone() + w_grad;
-                acc_grads[i].1 = acc_grads[i].1.clone() + b_grad;
             }
         }
 
         // Apply averaged gradients: divide accumulated sum by batch size, then
         // call the optimizer once per layer.
         let inv_batch = 1.0 / actual_batch_size;
-        for (i, (w_acc, b_acc)) in acc_grads.into_iter().enumerate() {
-            let w_avg = w_acc.map(|x| x * inv_batch);
-            let b_avg = b_acc.map(|x| x * inv_batch);
-            optimizer.step(&mut network.layers[i], w_avg, b_avg);
+        for (i, (w_acc, b_acc)) in acc_grads.iter_mut().enumerate() {
+            w_acc.scale_mut(inv_batch);
+            b_acc.scale_mut(inv_batch);
+            optimizer.step(i, network.layers[i].as_mut(), w_acc, b_acc);
         }
     }
 
-    total_loss / n as f64
+    Ok(total_loss / n as f64)
 }
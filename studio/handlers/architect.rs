@@ -1,9 +1,10 @@
 use tiny_http::{Request, Response};
 use std::io::Cursor;
 
-use ferrite_nn::{ActivationFunction, LossType, NetworkSpec, LayerSpec};
+use ferrite_nn::{ActivationFunction, LossType, NetworkSpec, LayerSpec, ScalingMethod, WeightInit};
+use ferrite_nn::network::cost::{format_bytes, format_count};
 
-use crate::state::{FlashMessage, Hyperparams, SharedState, TrainingStatus};
+use crate::state::{ClassBalance, FlashMessage, Hyperparams, SharedState, TrainingStatus};
 use crate::util::form::{parse_form, form_get};
 use crate::render::{render_page, Page};
 
@@ -39,6 +40,20 @@ pub fn handle_post(request: &mut Request, state: SharedState) -> Response<Cursor
     let lr_s         = form_get(&pairs, "learning_rate").unwrap_or("0.01").to_owned();
     let bs_s         = form_get(&pairs, "batch_size").unwrap_or("32").to_owned();
     let ep_s         = form_get(&pairs, "epochs").unwrap_or("50").to_owned();
+    let l1_s         = form_get(&pairs, "l1").unwrap_or("0").to_owned();
+    let l2_s         = form_get(&pairs, "l2").unwrap_or("0").to_owned();
+    let balance      = match form_get(&pairs, "class_weights") {
+        Some("balanced")    => ClassBalance::Weights,
+        Some("oversample")  => ClassBalance::Oversample,
+        Some("undersample") => ClassBalance::Undersample,
+        _                   => ClassBalance::None,
+    };
+    let seed_s       = form_get(&pairs, "seed").unwrap_or("").to_owned();
+    let scaling      = match form_get(&pairs, "input_scaling") {
+        Some("standard") => Some(ScalingMethod::Standard),
+        Some("min_max")  => Some(ScalingMethod::MinMax),
+        _                => None,
+    };
     let layers_json  = form_get(&pairs, "layers_json").unwrap_or("[]").to_owned();
 
     // Helper: return error page using current state as defaults.
@@ -75,9 +90,36 @@ pub fn handle_post(request: &mut Request, state: SharedState) -> Response<Cursor
         _ => return show_err("Epochs must be a positive integer.", &state),
     };
 
+    let l1: f64 = match l1_s.trim().parse::<f64>() {
+        Ok(v) if v >= 0.0 && v.is_finite() => v,
+        _ => return show_err("L1 penalty must be a non-negative number.", &state),
+    };
+
+    let l2: f64 = match l2_s.trim().parse::<f64>() {
+        Ok(v) if v >= 0.0 && v.is_finite() => v,
+        _ => return show_err("L2 penalty must be a non-negative number.", &state),
+    };
+
+    let seed: Option<u64> = match seed_s.trim() {
+        "" => None,
+        s => match s.parse() {
+            Ok(v) => Some(v),
+            Err(_) => return show_err("Seed must be empty or a non-negative integer.", &state),
+        },
+    };
+
     // Parse layers JSON (sent by the JS prepareSubmit() function).
     #[derive(serde::Deserialize)]
-    struct RawLayer { neurons: usize, activation: String }
+    struct RawLayer {
+        neurons: usize,
+        activation: String,
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        init: String,
+        #[serde(default)]
+        init_limit: String,
+    }
 
     let raw_layers: Vec<RawLayer> = match serde_json::from_str(&layers_json) {
         Ok(v) => v,
@@ -88,18 +130,32 @@ pub fn handle_post(request: &mut Request, state: SharedState) -> Response<Cursor
         return show_err("Add at least one layer.", &state);
     }
 
-    for rl in &raw_layers {
+    for (i, rl) in raw_layers.iter().enumerate() {
         if rl.neurons == 0 {
             return show_err("Each layer must have at least 1 neuron.", &state);
         }
+        let layer_name = rl.name.trim();
+        if !layer_name.is_empty()
+            && raw_layers[..i].iter().any(|other| other.name.trim() == layer_name)
+        {
+            return show_err(&format!("Layer name '{}' is used more than once.", layer_name), &state);
+        }
+        if let Err(e) = rl.activation.parse::<ActivationFunction>() {
+            return show_err(&format!("Layer {}: {}.", i + 1, e), &state);
+        }
+        if let Err(e) = parse_init(&rl.init, &rl.init_limit) {
+            return show_err(&e, &state);
+        }
     }
 
     // Build LayerSpec list.
     let mut layer_specs: Vec<LayerSpec> = Vec::new();
     let mut prev_size = input_size;
     for rl in &raw_layers {
-        let activation = parse_activation(&rl.activation);
-        layer_specs.push(LayerSpec { size: rl.neurons, input_size: prev_size, activation });
+        let activation: ActivationFunction = rl.activation.parse().expect("validated above");
+        let name = Some(rl.name.trim().to_owned()).filter(|n| !n.is_empty());
+        let init = parse_init(&rl.init, &rl.init_limit).unwrap_or(None);
+        layer_specs.push(LayerSpec { size: rl.neurons, input_size: prev_size, activation, name, init, l2: None });
         prev_size = rl.neurons;
     }
 
@@ -132,16 +188,31 @@ pub fn handle_post(request: &mut Request, state: SharedState) -> Response<Cursor
         );
     }
 
-    let mut spec = NetworkSpec { name: name.clone(), layers: layer_specs, loss, metadata: None };
+    let mut spec = NetworkSpec { name, layers: layer_specs, loss, metadata: None };
     if !description.is_empty() {
         spec.metadata = Some(ferrite_nn::ModelMetadata {
             description: Some(description),
             input_type:  None,
+            feature_names: None,
             output_labels: None,
+            metrics:     None,
+            temperature: None,
+            fingerprint: None,
+            input_scaling: None,
+            polynomial_features: None,
         });
     }
+    spec.normalize();
+    if let Err(errors) = spec.validate() {
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return show_err(&format!("Invalid architecture: {}.", messages.join("; ")), &state);
+    }
+    let name = spec.name.clone();
 
-    let hyperparams = Hyperparams { learning_rate: lr, batch_size: bs, epochs: ep };
+    let hyperparams = Hyperparams {
+        learning_rate: lr, batch_size: bs, epochs: ep, l1, l2, class_balance: balance, seed,
+        input_scaling: scaling,
+    };
 
     let mut st = state.lock().unwrap();
     st.spec        = Some(spec);
@@ -183,6 +254,11 @@ fn build_arch_page(
     let lr         = hyperparams.as_ref().map(|h| h.learning_rate).unwrap_or(0.01);
     let bs         = hyperparams.as_ref().map(|h| h.batch_size).unwrap_or(32);
     let ep         = hyperparams.as_ref().map(|h| h.epochs).unwrap_or(50);
+    let l1         = hyperparams.as_ref().map(|h| h.l1).unwrap_or(0.0);
+    let l2         = hyperparams.as_ref().map(|h| h.l2).unwrap_or(0.0);
+    let balance    = hyperparams.as_ref().map(|h| h.class_balance).unwrap_or(ClassBalance::None);
+    let seed       = hyperparams.as_ref().and_then(|h| h.seed);
+    let scaling    = hyperparams.as_ref().and_then(|h| h.input_scaling);
 
     let layer_rows = spec.as_ref()
         .map(|s| build_layer_rows(&s.layers))
@@ -194,6 +270,10 @@ fn build_arch_page(
                 html_escape(e))
     }).unwrap_or_default();
 
+    let graph_html = spec.as_ref().map(|s| {
+        format!(r#"<div class="card"><h2>Diagram</h2>{}</div>{}"#, s.to_svg(), build_cost_html(s))
+    }).unwrap_or_default();
+
     let sel_mse   = if loss == LossType::Mse                { " selected" } else { "" };
     let sel_ce    = if loss == LossType::CrossEntropy        { " selected" } else { "" };
     let sel_bce   = if loss == LossType::BinaryCrossEntropy  { " selected" } else { "" };
@@ -215,50 +295,132 @@ fn build_arch_page(
             .replace("{{ARCH_LR}}", &lr.to_string())
             .replace("{{ARCH_BS}}", &bs.to_string())
             .replace("{{ARCH_EP}}", &ep.to_string())
+            .replace("{{ARCH_L1}}", &l1.to_string())
+            .replace("{{ARCH_L2}}", &l2.to_string())
+            .replace("{{ARCH_SEED}}", &seed.map(|s| s.to_string()).unwrap_or_default())
+            .replace("{{SEL_CW_NONE}}", if balance == ClassBalance::None { " selected" } else { "" })
+            .replace("{{SEL_CW_BALANCED}}", if balance == ClassBalance::Weights { " selected" } else { "" })
+            .replace("{{SEL_CW_OVERSAMPLE}}", if balance == ClassBalance::Oversample { " selected" } else { "" })
+            .replace("{{SEL_CW_UNDERSAMPLE}}", if balance == ClassBalance::Undersample { " selected" } else { "" })
+            .replace("{{SEL_SCALE_NONE}}", if scaling.is_none() { " selected" } else { "" })
+            .replace("{{SEL_SCALE_STANDARD}}", if scaling == Some(ScalingMethod::Standard) { " selected" } else { "" })
+            .replace("{{SEL_SCALE_MINMAX}}", if scaling == Some(ScalingMethod::MinMax) { " selected" } else { "" })
             .replace("{{ARCH_ERROR}}", &error_html)
+            .replace("{{ARCH_GRAPH}}", &graph_html)
     })
 }
 
+/// Compute and memory estimate for the saved architecture.
+fn build_cost_html(spec: &NetworkSpec) -> String {
+    let cost = spec.cost_estimate();
+    let rows: String = cost.layers.iter().enumerate().map(|(i, layer)| {
+        let label = match layer.name {
+            Some(ref name) => html_escape(name),
+            None           => format!("Layer {}", i + 1),
+        };
+        format!("<tr><th>{}</th><td>{} MACs</td><td>{} params</td></tr>",
+            label, format_count(layer.macs), format_count(layer.params))
+    }).collect();
+    format!(
+        r#"<div class="card"><h2>Cost Estimate</h2>
+<table class="summary-table">
+  {rows}
+  <tr><th>Total per sample</th><td>{macs} MACs</td><td>{params} params</td></tr>
+  <tr><th>Parameter memory</th><td colspan="2">{param_bytes}</td></tr>
+  <tr><th>Activations per sample</th><td colspan="2">{act_bytes} (× batch size while training)</td></tr>
+</table></div>"#,
+        rows        = rows,
+        macs        = format_count(cost.total_macs),
+        params      = format_count(cost.total_params),
+        param_bytes = format_bytes(cost.param_bytes),
+        act_bytes   = format_bytes(cost.activation_bytes),
+    )
+}
+
 const ACTIVATION_OPTIONS: &[(&str, &str)] = &[
     ("sigmoid",    "Sigmoid"),
     ("relu",       "ReLU"),
     ("tanh",       "Tanh"),
-    ("leaky_relu", "Leaky ReLU (α=0.01)"),
-    ("elu",        "ELU (α=1.0)"),
+    ("leaky_relu(0.01)", "Leaky ReLU (α=0.01)"),
+    ("elu(1)",     "ELU (α=1.0)"),
     ("gelu",       "GELU"),
     ("swish",      "Swish"),
+    ("selu",       "SELU"),
+    ("softplus",   "Softplus"),
+    ("mish",       "Mish"),
+    ("hard_sigmoid", "Hard sigmoid"),
+    ("hard_swish", "Hard swish"),
     ("identity",   "Identity"),
     ("softmax",    "Softmax"),
 ];
 
+const INIT_OPTIONS: &[(&str, &str)] = &[
+    ("",               "Auto (He / Xavier)"),
+    ("he",             "He"),
+    ("xavier",         "Xavier"),
+    ("xavier_uniform", "Xavier uniform"),
+    ("lecun",          "LeCun"),
+    ("uniform",        "Uniform (±limit)"),
+    ("zeros",          "Zeros"),
+    ("orthogonal",     "Orthogonal"),
+];
+
+/// Default `±limit` shown for the uniform scheme.
+const DEFAULT_INIT_LIMIT: f64 = 0.05;
+
 fn build_layer_rows(layers: &[LayerSpec]) -> String {
     layers.iter().enumerate().map(|(i, ls)| {
         let idx     = i + 1;
-        let act_str = activation_to_str(&ls.activation);
-        let opts: String = ACTIVATION_OPTIONS.iter().map(|&(val, label)| {
-            let sel = if val == act_str { " selected" } else { "" };
-            format!("<option value=\"{}\"{}>{}</option>", val, sel, label)
-        }).collect();
+        let opts = activation_options(&ls.activation);
+        let name = ls.name.as_deref().unwrap_or("");
+        let init_cell = build_init_cell(ls.init.as_ref());
         format!(
-            r#"<tr id="lr-{idx}"><td>{idx}</td><td><input type="number" class="neurons-input" data-field="neurons" value="{sz}" min="1"></td><td><select class="act-select" data-field="activation">{opts}</select></td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer({idx})">Remove</button></td></tr>"#,
-            idx = idx, sz = ls.size, opts = opts
+            r#"<tr id="lr-{idx}"><td>{idx}</td><td><input type="text" class="name-input" data-field="name" value="{name}" placeholder="optional"></td><td><input type="number" class="neurons-input" data-field="neurons" value="{sz}" min="1"></td><td><select class="act-select" data-field="activation">{opts}</select></td><td>{init_cell}</td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer({idx})">Remove</button></td></tr>"#,
+            idx = idx, name = html_escape(name), sz = ls.size, opts = opts, init_cell = init_cell
         )
     }).collect::<Vec<_>>().join("\n")
 }
 
-fn default_layer_rows() -> String {
-    let opts_relu: String = ACTIVATION_OPTIONS.iter().map(|&(val, label)| {
-        let sel = if val == "relu" { " selected" } else { "" };
+/// The init `<select>` plus the limit input used by the uniform scheme.
+fn build_init_cell(init: Option<&WeightInit>) -> String {
+    let selected = init.map(init_to_str).unwrap_or("");
+    let limit = match init {
+        Some(WeightInit::Uniform(limit)) => *limit,
+        _ => DEFAULT_INIT_LIMIT,
+    };
+    let opts: String = INIT_OPTIONS.iter().map(|&(val, label)| {
+        let sel = if val == selected { " selected" } else { "" };
         format!("<option value=\"{}\"{}>{}</option>", val, sel, label)
     }).collect();
-    let opts_softmax: String = ACTIVATION_OPTIONS.iter().map(|&(val, label)| {
-        let sel = if val == "softmax" { " selected" } else { "" };
+    format!(
+        r#"<select class="init-select" data-field="init">{}</select> <input type="number" class="limit-input" data-field="init_limit" value="{}" min="0" step="any" title="Uniform limit">"#,
+        opts, limit
+    )
+}
+
+/// `<option>`s for the activation select with `selected` chosen.  An
+/// activation with a non-default parameter (e.g. `leaky_relu(0.2)` from a
+/// loaded spec) gets an extra option so saving does not reset it.
+fn activation_options(selected: &ActivationFunction) -> String {
+    let selected = selected.to_string();
+    let mut opts: String = ACTIVATION_OPTIONS.iter().map(|&(val, label)| {
+        let sel = if val == selected { " selected" } else { "" };
         format!("<option value=\"{}\"{}>{}</option>", val, sel, label)
     }).collect();
+    if !ACTIVATION_OPTIONS.iter().any(|&(val, _)| val == selected) {
+        opts.push_str(&format!("<option value=\"{0}\" selected>{0}</option>", html_escape(&selected)));
+    }
+    opts
+}
+
+fn default_layer_rows() -> String {
+    let opts_relu    = activation_options(&ActivationFunction::ReLU);
+    let opts_softmax = activation_options(&ActivationFunction::Softmax);
+    let init_cell = build_init_cell(None);
     format!(
-        r#"<tr id="lr-1"><td>1</td><td><input type="number" class="neurons-input" data-field="neurons" value="8" min="1"></td><td><select class="act-select" data-field="activation">{}</select></td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer(1)">Remove</button></td></tr>
-<tr id="lr-2"><td>2</td><td><input type="number" class="neurons-input" data-field="neurons" value="2" min="1"></td><td><select class="act-select" data-field="activation">{}</select></td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer(2)">Remove</button></td></tr>"#,
-        opts_relu, opts_softmax
+        r#"<tr id="lr-1"><td>1</td><td><input type="text" class="name-input" data-field="name" value="" placeholder="optional"></td><td><input type="number" class="neurons-input" data-field="neurons" value="8" min="1"></td><td><select class="act-select" data-field="activation">{}</select></td><td>{}</td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer(1)">Remove</button></td></tr>
+<tr id="lr-2"><td>2</td><td><input type="text" class="name-input" data-field="name" value="" placeholder="optional"></td><td><input type="number" class="neurons-input" data-field="neurons" value="2" min="1"></td><td><select class="act-select" data-field="activation">{}</select></td><td>{}</td><td><button type="button" class="btn btn-secondary btn-sm" onclick="removeLayer(2)">Remove</button></td></tr>"#,
+        opts_relu, init_cell, opts_softmax, init_cell
     )
 }
 
@@ -266,31 +428,35 @@ fn default_layer_rows() -> String {
 // Shared helpers (also used by other handlers)
 // ---------------------------------------------------------------------------
 
-pub fn parse_activation(s: &str) -> ActivationFunction {
-    match s {
-        "relu"       => ActivationFunction::ReLU,
-        "softmax"    => ActivationFunction::Softmax,
-        "identity"   => ActivationFunction::Identity,
-        "tanh"       => ActivationFunction::Tanh,
-        "leaky_relu" => ActivationFunction::LeakyReLU { alpha: 0.01 },
-        "elu"        => ActivationFunction::Elu { alpha: 1.0 },
-        "gelu"       => ActivationFunction::Gelu,
-        "swish"      => ActivationFunction::Swish,
-        _            => ActivationFunction::Sigmoid,
-    }
+/// Parses the init select value; an empty value means the activation-based
+/// default.  `limit` is only read for the uniform scheme.
+pub fn parse_init(s: &str, limit: &str) -> Result<Option<WeightInit>, String> {
+    let init = match s {
+        ""               => return Ok(None),
+        "he"             => WeightInit::He,
+        "xavier"         => WeightInit::Xavier,
+        "xavier_uniform" => WeightInit::XavierUniform,
+        "lecun"          => WeightInit::LeCun,
+        "zeros"          => WeightInit::Zeros,
+        "orthogonal"     => WeightInit::Orthogonal,
+        "uniform"        => match limit.trim().parse::<f64>() {
+            Ok(v) if v > 0.0 && v.is_finite() => WeightInit::Uniform(v),
+            _ => return Err("Uniform init limit must be a positive number.".to_owned()),
+        },
+        other            => return Err(format!("Unknown weight init '{}'.", other)),
+    };
+    Ok(Some(init))
 }
 
-pub fn activation_to_str(a: &ActivationFunction) -> &'static str {
-    match a {
-        ActivationFunction::ReLU             => "relu",
-        ActivationFunction::Softmax          => "softmax",
-        ActivationFunction::Identity         => "identity",
-        ActivationFunction::Sigmoid          => "sigmoid",
-        ActivationFunction::Tanh             => "tanh",
-        ActivationFunction::LeakyReLU { .. } => "leaky_relu",
-        ActivationFunction::Elu { .. }       => "elu",
-        ActivationFunction::Gelu             => "gelu",
-        ActivationFunction::Swish            => "swish",
+pub fn init_to_str(init: &WeightInit) -> &'static str {
+    match init {
+        WeightInit::He            => "he",
+        WeightInit::Xavier        => "xavier",
+        WeightInit::XavierUniform => "xavier_uniform",
+        WeightInit::LeCun         => "lecun",
+        WeightInit::Uniform(_)    => "uniform",
+        WeightInit::Zeros         => "zeros",
+        WeightInit::Orthogonal    => "orthogonal",
     }
 }
 
@@ -6,8 +6,9 @@ use crate::util::form::{parse_form, form_get};
 use crate::util::multipart::{extract_boundary, multipart_extract_file,
                               multipart_extract_file_by_name,
                               extract_all_text_fields};
-use crate::util::csv::{parse_csv, LabelMode, builtin_xor, builtin_circles, builtin_blobs};
-use crate::util::idx::parse_idx_pair;
+use crate::util::builtin::{builtin_xor, builtin_circles, builtin_blobs};
+use ferrite_nn::{split_samples, SplitStrategy};
+use ferrite_nn::data::{csv_feature_names, parse_csv, parse_idx_pair, LabelMode};
 use crate::render::{render_page, Page};
 use crate::handlers::architect::{render_flash_html, html_escape};
 
@@ -91,7 +92,8 @@ pub fn handle_upload(request: &mut Request, state: SharedState) -> Response<Curs
         }
     }
 
-    let ds = build_dataset_state(inputs, labels, val_split, "CSV upload".to_owned());
+    let mut ds = build_dataset_state(inputs, labels, val_split, "CSV upload".to_owned());
+    ds.feature_names = csv_feature_names(&csv_bytes, label_mode);
 
     let mut st = state.lock().unwrap();
     st.dataset = Some(ds);
@@ -258,28 +260,24 @@ fn build_dataset_state(
     let feature_count = inputs.first().map(|r| r.len()).unwrap_or(0);
     let label_count   = labels.first().map(|r| r.len()).unwrap_or(0);
 
-    let val_n = (total * val_split_pct as usize) / 100;
-    let train_n = total - val_n;
-
     let preview_rows: Vec<(Vec<f64>, Vec<f64>)> = inputs.iter().zip(labels.iter())
         .take(5)
         .map(|(i, l)| (i.clone(), l.clone()))
         .collect();
 
-    let (train_inputs, val_inputs) = inputs.split_at(train_n);
-    let (train_labels, val_labels) = labels.split_at(train_n);
+    // Stratified, so a CSV sorted by class still gets every class in both
+    // splits.
+    let data = split_samples(inputs, labels, val_split_pct as f64 / 100.0, 0.0, SplitStrategy::Stratified);
 
     DatasetState {
-        train_inputs:  train_inputs.to_vec(),
-        train_labels:  train_labels.to_vec(),
-        val_inputs:    val_inputs.to_vec(),
-        val_labels:    val_labels.to_vec(),
+        data,
         feature_count,
         label_count,
         total_rows: total,
         val_split_pct,
         source_name,
         preview_rows,
+        feature_names: None,
     }
 }
 
@@ -329,6 +327,14 @@ fn build_dataset_page(
     })
 }
 
+/// Feature count, followed by the column names when the CSV had a header.
+fn feature_summary(ds: &DatasetState) -> String {
+    match &ds.feature_names {
+        Some(names) => format!("{} ({})", ds.feature_count, html_escape(&names.join(", "))),
+        None        => ds.feature_count.to_string(),
+    }
+}
+
 fn build_summary_html(ds: &DatasetState) -> String {
     let preview: String = ds.preview_rows.iter().enumerate().map(|(i, (inp, lbl))| {
         let feat_str: String = inp.iter().map(|v| format!("{:.4}", v)).collect::<Vec<_>>().join(", ");
@@ -355,10 +361,10 @@ fn build_summary_html(ds: &DatasetState) -> String {
 </div>"#,
         source       = html_escape(&ds.source_name),
         total        = ds.total_rows,
-        feats        = ds.feature_count,
+        feats        = feature_summary(ds),
         lbls         = ds.label_count,
-        train_n      = ds.train_inputs.len(),
-        val_n        = ds.val_inputs.len(),
+        train_n      = ds.data.train.len(),
+        val_n        = ds.data.val.len(),
         split        = ds.val_split_pct,
         preview_count = ds.preview_rows.len(),
         preview      = preview,
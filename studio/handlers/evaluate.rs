@@ -1,6 +1,10 @@
 use std::io::Cursor;
 use tiny_http::Response;
 
+use ferrite_nn::{
+    evaluate, permutation_importance, Average, EvalReport, FeatureImportance, InputType, Metric,
+};
+
 use crate::state::{SharedState, TrainingStatus};
 use crate::render::{render_page, Page};
 
@@ -56,16 +60,29 @@ pub fn handle_get(state: SharedState) -> Response<Cursor<Vec<u8>>> {
         time = total_time,
     );
 
-    // Confusion matrix from trained network on validation set.
-    let confusion_html = if let (Some(network_ref), Some(ds)) = (&st.trained_network, &st.dataset) {
-        if !ds.val_inputs.is_empty() {
+    // Per-class metrics and confusion matrix from the trained network on the
+    // validation set.
+    let confusion_html = match (&st.trained_network, &st.dataset, &st.spec) {
+        (Some(network_ref), Some(ds), Some(spec)) if !ds.data.val.is_empty() => {
             let mut net = network_ref.clone();
-            build_confusion_matrix_html(&mut net, &ds.val_inputs, &ds.val_labels)
-        } else {
-            String::new()
+            let report = evaluate(&mut net, &ds.data.val, spec.loss);
+            build_class_report_html(&report)
         }
-    } else {
-        String::new()
+        _ => String::new(),
+    };
+
+    // Permutation feature importance — tabular models only; image inputs
+    // have too many (and individually meaningless) features.
+    let importance_html = match (&st.trained_network, &st.dataset, &st.spec) {
+        (Some(network), Some(ds), Some(spec))
+            if !ds.data.val.is_empty()
+                && ds.data.feature_count() <= MAX_IMPORTANCE_FEATURES
+                && !is_image_input(spec) =>
+        {
+            let importances = permutation_importance(network, &ds.data.val, Metric::Loss(spec.loss));
+            build_importance_html(&importances)
+        }
+        _ => String::new(),
     };
 
     drop(st);
@@ -74,7 +91,7 @@ pub fn handle_get(state: SharedState) -> Response<Cursor<Vec<u8>>> {
         tmpl
             .replace("{{EVAL_LOSS_SVG}}", &svg)
             .replace("{{EVAL_METRICS_TABLE}}", &metrics_table)
-            .replace("{{EVAL_CONFUSION}}", &confusion_html)
+            .replace("{{EVAL_CONFUSION}}", &format!("{}{}", confusion_html, importance_html))
     }))
 }
 
@@ -210,26 +227,10 @@ fn build_svg_loss_curve(history: &[ferrite_nn::EpochStats]) -> String {
 // Confusion matrix
 // ---------------------------------------------------------------------------
 
-fn build_confusion_matrix_html(
-    network: &mut ferrite_nn::Network,
-    val_inputs: &[Vec<f64>],
-    val_labels: &[Vec<f64>],
-) -> String {
-    if val_labels.is_empty() { return String::new(); }
-
-    let n_classes = val_labels[0].len();
+fn build_class_report_html(report: &EvalReport) -> String {
+    let n_classes = report.n_classes();
     if n_classes < 2 { return String::new(); }
-
-    let mut matrix = vec![vec![0usize; n_classes]; n_classes];
-
-    for (input, label) in val_inputs.iter().zip(val_labels.iter()) {
-        let output = network.forward(input.clone());
-        let predicted = argmax(&output);
-        let truth     = argmax(label);
-        if predicted < n_classes && truth < n_classes {
-            matrix[truth][predicted] += 1;
-        }
-    }
+    let matrix = &report.confusion;
 
     let max_off_diag = matrix.iter().enumerate()
         .flat_map(|(r, row)| row.iter().enumerate().filter(move |(c, _)| *c != r).map(|(_, &v)| v))
@@ -255,8 +256,31 @@ fn build_confusion_matrix_html(
         format!("<tr><th>T:{}</th>{}</tr>", r, cells)
     }).collect();
 
+    let mut class_rows: String = report.per_class.iter().map(|c| {
+        format!(
+            "<tr><th>{}</th><td>{:.4}</td><td>{:.4}</td><td>{:.4}</td><td>{}</td></tr>",
+            c.class, c.precision, c.recall, c.f1, c.support
+        )
+    }).collect();
+    for (label, average) in [("Macro avg", Average::Macro), ("Micro avg", Average::Micro)] {
+        if let Some(m) = report.averaged(average) {
+            class_rows.push_str(&format!(
+                "<tr><th>{}</th><td>{:.4}</td><td>{:.4}</td><td>{:.4}</td><td>{}</td></tr>",
+                label, m.precision, m.recall, m.f1, report.samples
+            ));
+        }
+    }
+
     format!(
-        r#"<div class="card"><h2>Confusion Matrix (Validation Set)</h2>
+        r#"<div class="card"><h2>Per-Class Metrics (Validation Set)</h2>
+<div style="overflow-x:auto">
+<table class="summary-table">
+  <thead><tr><th>Class</th><th>Precision</th><th>Recall</th><th>F1</th><th>Support</th></tr></thead>
+  <tbody>{class_rows}</tbody>
+</table>
+</div>
+</div>
+<div class="card"><h2>Confusion Matrix (Validation Set)</h2>
 <p class="hint" style="margin-bottom:10px">Rows = true class, Columns = predicted class. Green diagonal = correct predictions.</p>
 <div style="overflow-x:auto">
 <table class="conf-matrix">
@@ -265,14 +289,44 @@ fn build_confusion_matrix_html(
 </table>
 </div>
 </div>"#,
-        header = header, rows = rows
+        class_rows = class_rows, header = header, rows = rows
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Feature importance
+// ---------------------------------------------------------------------------
+
+/// Feature importance is skipped above this many input features.
+const MAX_IMPORTANCE_FEATURES: usize = 64;
+
+fn is_image_input(spec: &ferrite_nn::NetworkSpec) -> bool {
+    matches!(
+        spec.metadata.as_ref().and_then(|m| m.input_type.as_ref()),
+        Some(InputType::ImageGrayscale { .. } | InputType::ImageRgb { .. })
     )
 }
 
-fn argmax(v: &[f64]) -> usize {
-    v.iter()
-        .enumerate()
-        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-        .map(|(i, _)| i)
-        .unwrap_or(0)
+fn build_importance_html(importances: &[FeatureImportance]) -> String {
+    if importances.is_empty() { return String::new(); }
+    let max = importances.iter().map(|f| f.importance.abs()).fold(0.0f64, f64::max).max(1e-12);
+
+    let rows: String = importances.iter().map(|f| {
+        let width = (f.importance.max(0.0) / max * 260.0) as u32;
+        format!(
+            r#"<tr><th>x{}</th><td><div class="bar-wrap"><div class="bar-fill" style="width:{}px"></div></div></td><td>{:+.5}</td><td>± {:.5}</td></tr>"#,
+            f.feature, width, f.importance, f.std_dev
+        )
+    }).collect();
+
+    format!(
+        r#"<div class="card"><h2>Feature Importance (Validation Set)</h2>
+<p class="hint" style="margin-bottom:10px">Increase in validation loss when a feature column is shuffled. Features near zero are barely used by the model.</p>
+<table class="summary-table">
+  <thead><tr><th>Feature</th><th></th><th>Loss increase</th><th>Std dev</th></tr></thead>
+  <tbody>{rows}</tbody>
+</table>
+</div>"#,
+        rows = rows
+    )
 }
@@ -1,17 +1,22 @@
 use std::io::Cursor;
 use tiny_http::Response;
 
+use ferrite_nn::ModelRegistry;
+use ferrite_nn::network::registry::DEFAULT_MODEL_DIR;
+
 /// `GET /models/{name}/download`
 ///
 /// Serves the JSON file for the named model as a downloadable attachment.
 pub fn handle_download(name: &str) -> Response<Cursor<Vec<u8>>> {
-    // Basic sanity check — reject empty names or path traversal attempts.
-    if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+    // Only serve files the registry knows about — this also rejects empty
+    // names and path traversal attempts.
+    let entry = ModelRegistry::open(DEFAULT_MODEL_DIR).ok()
+        .and_then(|registry| registry.get(name).cloned());
+    let Some(entry) = entry else {
         return crate::routes::not_found();
-    }
+    };
 
-    let path = format!("trained_models/{}.json", name);
-    match std::fs::read_to_string(&path) {
+    match std::fs::read_to_string(&entry.path) {
         Ok(json) => {
             let filename = format!("{}.json", name);
             crate::routes::json_download_response(json, &filename)
@@ -1,13 +1,17 @@
 use std::io::Cursor;
 use tiny_http::{Request, Response};
 
-use ferrite_nn::{ActivationFunction, InputType, Network};
+use std::sync::Arc;
+use ferrite_nn::{CategoricalEncoder, InferenceSession, InputType, ModelRegistry, Network, ScalingMethod};
+use ferrite_nn::data::ColumnEncoder;
+use ferrite_nn::network::registry::DEFAULT_MODEL_DIR;
+use ferrite_nn::serve::{handle_predict_json, parse_image_input, parse_numeric_input,
+                        parse_tabular_input, parse_text_input, ClassScore, Prediction};
 
 use crate::state::SharedState;
 use crate::util::form::{parse_form, form_get};
 use crate::util::multipart::{extract_boundary, multipart_extract_file, extract_text_field,
                               find_subsequence, split_on};
-use crate::util::image::{image_bytes_to_grayscale_input, image_bytes_to_rgb_input};
 use crate::render::{render_page, Page};
 use crate::handlers::architect::html_escape;
 
@@ -52,7 +56,7 @@ pub fn handle_infer(request: &mut Request, state: SharedState) -> Response<Curso
             .unwrap_or_default();
 
         let result = match multipart_extract_file(&body_bytes, &boundary) {
-            Some(bytes) if !bytes.is_empty() => run_inference_image(&model_name, &bytes),
+            Some(bytes) if !bytes.is_empty() => run_inference_image(&state, &model_name, &bytes),
             _ => error_html("No image file was uploaded."),
         };
         (model_name, result)
@@ -61,8 +65,19 @@ pub fn handle_infer(request: &mut Request, state: SharedState) -> Response<Curso
         let _ = request.as_reader().read_to_string(&mut body);
         let pairs      = parse_form(&body);
         let model_name = form_get(&pairs, "model").unwrap_or("").to_owned();
-        let raw_inputs = form_get(&pairs, "inputs").unwrap_or("").to_owned();
-        let result     = run_inference_numeric(&model_name, &raw_inputs);
+        let result = match form_get(&pairs, "text") {
+            Some(text) => run_inference_text(&state, &model_name, text),
+            None if form_get(&pairs, "cell_0").is_some() => {
+                run_inference_tabular(&state, &model_name, &joined_fields(&pairs, "cell"))
+            }
+            None => {
+                let raw_inputs = match form_get(&pairs, "inputs") {
+                    Some(raw) => raw.to_owned(),
+                    None      => joined_fields(&pairs, "feature"),
+                };
+                run_inference_numeric(&state, &model_name, &raw_inputs)
+            }
+        };
         (model_name, result)
     };
 
@@ -103,24 +118,9 @@ fn build_test_page(selected: &str, result_html: &str, tab_unlock: u8) -> String
 // ---------------------------------------------------------------------------
 
 fn list_models() -> Vec<String> {
-    let dir = "trained_models";
-    match std::fs::read_dir(dir) {
-        Ok(entries) => {
-            let mut names: Vec<String> = entries.flatten()
-                .filter_map(|e| {
-                    let path = e.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                        path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_owned())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            names.sort();
-            names
-        }
-        Err(_) => vec![],
-    }
+    ModelRegistry::open(DEFAULT_MODEL_DIR)
+        .map(|registry| registry.names())
+        .unwrap_or_default()
 }
 
 fn build_model_options(models: &[String], selected: &str) -> String {
@@ -139,7 +139,7 @@ fn build_model_options(models: &[String], selected: &str) -> String {
 
 fn build_input_section(model_name: &str) -> (&'static str, String) {
     if model_name.is_empty() {
-        return numeric_section();
+        return numeric_section(None);
     }
     let path = format!("trained_models/{}.json", model_name);
     let network = Network::load_json(&path).ok();
@@ -154,10 +154,26 @@ fn build_input_section(model_name: &str) -> (&'static str, String) {
         Some(InputType::ImageRgb { width, height }) => {
             image_section(*width, *height, "RGB")
         }
-        _ => numeric_section(),
+        Some(InputType::Text { vectorizer }) => text_section(vectorizer.len()),
+        Some(InputType::Tabular { encoder }) => tabular_section(encoder),
+        _ => {
+            let scaling = network.as_ref().and_then(|n| n.input_scaling()).map(|s| s.method());
+            match network.as_ref().and_then(feature_names) {
+                Some(names) => named_section(names, scaling),
+                None        => numeric_section(scaling),
+            }
+        }
     }
 }
 
+/// The model's feature names, if it has one per input.
+fn feature_names(network: &Network) -> Option<&[String]> {
+    let expected = network.input_size();
+    network.metadata.as_ref()
+        .and_then(|m| m.feature_names.as_deref())
+        .filter(|names| names.len() == expected)
+}
+
 fn image_section(width: u32, height: u32, color_mode: &str) -> (&'static str, String) {
     let hint = format!("{} image — will be resized to {}x{} and normalized.", color_mode, width, height);
     (
@@ -181,13 +197,99 @@ document.getElementById('image_file').addEventListener('change', function() {{
     )
 }
 
-fn numeric_section() -> (&'static str, String) {
+fn text_section(vocabulary_size: usize) -> (&'static str, String) {
+    (
+        "application/x-www-form-urlencoded",
+        format!(
+            r#"<label for="text">Input text</label>
+<textarea id="text" name="text" rows="5" placeholder="Type or paste the text to classify"></textarea>
+<p class="hint">Raw text — encoded as a bag of words over the model's {}-word vocabulary.</p>"#,
+            vocabulary_size
+        ),
+    )
+}
+
+/// One field per raw column, named `cell_0`, `cell_1`, … (read back by
+/// `joined_fields`): a dropdown of the known categories for a categorical
+/// column, a text box for a numeric one.
+fn tabular_section(encoder: &CategoricalEncoder) -> (&'static str, String) {
+    let fields: String = encoder.columns.iter().enumerate().map(|(i, column)| {
+        let name = encoder.names.as_ref()
+            .and_then(|names| names.get(i))
+            .map_or_else(|| format!("Column {}", i + 1), |n| html_escape(n));
+        let control = match column {
+            ColumnEncoder::Categorical { categories, .. } => {
+                let options: String = categories.iter()
+                    .map(|c| format!(r#"<option value="{c}">{c}</option>"#, c = html_escape(c)))
+                    .collect();
+                format!(r#"<select id="cell_{i}" name="cell_{i}">{options}</select>"#, i = i, options = options)
+            }
+            ColumnEncoder::Numeric => {
+                format!(r#"<input type="text" id="cell_{i}" name="cell_{i}" placeholder="0.0">"#, i = i)
+            }
+        };
+        format!(r#"<div><label for="cell_{i}">{name}</label>{control}</div>"#, i = i, name = name, control = control)
+    }).collect();
+    (
+        "application/x-www-form-urlencoded",
+        format!(
+            r#"<div style="display:grid;grid-template-columns:repeat(auto-fill,minmax(160px,1fr));gap:10px">{}</div>
+<p class="hint">One value per column — categories are encoded as in training.</p>"#,
+            fields
+        ),
+    )
+}
+
+/// Extra hint for a model with input scaling: values are entered raw.
+fn scaling_hint(scaling: Option<ScalingMethod>) -> &'static str {
+    match scaling {
+        Some(ScalingMethod::Standard) => " Enter raw values — the model standardizes them as in training.",
+        Some(ScalingMethod::MinMax)   => " Enter raw values — the model rescales them as in training.",
+        None                          => "",
+    }
+}
+
+/// One labeled field per feature, named `feature_0`, `feature_1`, … (read
+/// back by `joined_fields`).
+fn named_section(names: &[String], scaling: Option<ScalingMethod>) -> (&'static str, String) {
+    let fields: String = names.iter().enumerate().map(|(i, name)| {
+        format!(
+            r#"<div><label for="feature_{i}">{name}</label><input type="text" id="feature_{i}" name="feature_{i}" placeholder="0.0"></div>"#,
+            i = i, name = html_escape(name)
+        )
+    }).collect();
     (
         "application/x-www-form-urlencoded",
-        r#"<label for="inputs">Input values</label>
+        format!(
+            r#"<div style="display:grid;grid-template-columns:repeat(auto-fill,minmax(160px,1fr));gap:10px">{}</div>
+<p class="hint">One number per feature.{}</p>"#,
+            fields, scaling_hint(scaling)
+        ),
+    )
+}
+
+/// Joins the `<prefix>_N` fields of a `named_section` (`feature`) or
+/// `tabular_section` (`cell`) form into the comma-separated form
+/// `parse_numeric_input` / `parse_tabular_input` expect.
+fn joined_fields(pairs: &[(String, String)], prefix: &str) -> String {
+    (0..)
+        .map_while(|i| form_get(pairs, &format!("{}_{}", prefix, i)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// `scaling` is the model's input scaling, if any; the hint then tells the
+/// user to enter raw values.
+fn numeric_section(scaling: Option<ScalingMethod>) -> (&'static str, String) {
+    (
+        "application/x-www-form-urlencoded",
+        format!(
+            r#"<label for="inputs">Input values</label>
 <textarea id="inputs" name="inputs" rows="4"
   placeholder="Enter comma-separated numbers, e.g.:&#10;0.0, 1.0"></textarea>
-<p class="hint">Comma-separated floats — one value per input neuron.</p>"#.to_owned(),
+<p class="hint">Comma-separated floats — one value per input neuron.{}</p>"#,
+            scaling_hint(scaling)
+        ),
     )
 }
 
@@ -195,100 +297,95 @@ fn numeric_section() -> (&'static str, String) {
 // Inference runners
 // ---------------------------------------------------------------------------
 
-fn run_inference_numeric(model_name: &str, raw_inputs: &str) -> String {
-    let path = format!("trained_models/{}.json", model_name);
-    let mut network = match Network::load_json(&path) {
+fn run_inference_numeric(state: &SharedState, model_name: &str, raw_inputs: &str) -> String {
+    let store   = state.lock().unwrap().model_store.clone();
+    let network = match store.get(model_name) {
         Ok(n)  => n,
-        Err(e) => return error_html(&format!("Could not load model <strong>{}</strong>: {}", html_escape(model_name), e)),
+        Err(e) => return error_html(&html_escape(&e.to_string())),
     };
-    if network.layers.is_empty() { return error_html("Model has no layers."); }
-
-    let inputs: Vec<f64> = raw_inputs
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .filter_map(|s| s.parse::<f64>().ok())
-        .collect();
+    match parse_numeric_input(&network, raw_inputs) {
+        Ok(inputs) => run_and_format(network, &inputs),
+        Err(e)     => error_html(&html_escape(&e.to_string())),
+    }
+}
 
-    let expected_len = network.layers[0].weights.cols;
-    if inputs.len() != expected_len {
-        return error_html(&format!(
-            "Input length mismatch: model expects <strong>{}</strong> values, got <strong>{}</strong>.",
-            expected_len, inputs.len()
-        ));
+fn run_inference_text(state: &SharedState, model_name: &str, text: &str) -> String {
+    let store   = state.lock().unwrap().model_store.clone();
+    let network = match store.get(model_name) {
+        Ok(n)  => n,
+        Err(e) => return error_html(&html_escape(&e.to_string())),
+    };
+    match parse_text_input(&network, text) {
+        Ok(inputs) => run_and_format(network, &inputs),
+        Err(e)     => error_html(&html_escape(&e.to_string())),
     }
+}
 
-    let output = network.forward(inputs);
-    let labels = network.metadata.as_ref().and_then(|m| m.output_labels.as_deref());
-    format_output(&output, labels, &network.layers.last().unwrap().activator)
+fn run_inference_tabular(state: &SharedState, model_name: &str, row: &str) -> String {
+    let store   = state.lock().unwrap().model_store.clone();
+    let network = match store.get(model_name) {
+        Ok(n)  => n,
+        Err(e) => return error_html(&html_escape(&e.to_string())),
+    };
+    match parse_tabular_input(&network, row) {
+        Ok(inputs) => run_and_format(network, &inputs),
+        Err(e)     => error_html(&html_escape(&e.to_string())),
+    }
 }
 
-fn run_inference_image(model_name: &str, image_bytes: &[u8]) -> String {
-    let path = format!("trained_models/{}.json", model_name);
-    let mut network = match Network::load_json(&path) {
+fn run_inference_image(state: &SharedState, model_name: &str, image_bytes: &[u8]) -> String {
+    let store   = state.lock().unwrap().model_store.clone();
+    let network = match store.get(model_name) {
         Ok(n)  => n,
-        Err(e) => return error_html(&format!("Could not load model <strong>{}</strong>: {}", html_escape(model_name), e)),
+        Err(e) => return error_html(&html_escape(&e.to_string())),
     };
-    if network.layers.is_empty() { return error_html("Model has no layers."); }
+    match parse_image_input(&network, image_bytes) {
+        Ok(inputs) => run_and_format(network, &inputs),
+        Err(e)     => error_html(&html_escape(&e.to_string())),
+    }
+}
 
-    let input_type = network.metadata.as_ref().and_then(|m| m.input_type.as_ref()).cloned();
+fn run_and_format(network: Arc<Network>, inputs: &[f64]) -> String {
+    let mut session = InferenceSession::new(network.clone());
+    let output = session.run(inputs);
+    format_prediction(&Prediction::from_output(&network, output))
+}
 
-    let inputs = match &input_type {
-        Some(InputType::ImageGrayscale { width, height }) => {
-            match image_bytes_to_grayscale_input(image_bytes, *width, *height) {
-                Ok(v)  => v,
-                Err(e) => return error_html(&format!("Image decode error: {}", e)),
-            }
-        }
-        Some(InputType::ImageRgb { width, height }) => {
-            match image_bytes_to_rgb_input(image_bytes, *width, *height) {
-                Ok(v)  => v,
-                Err(e) => return error_html(&format!("Image decode error: {}", e)),
-            }
-        }
-        _ => return error_html("Model does not declare an image input type."),
-    };
+// ---------------------------------------------------------------------------
+// POST /api/predict
+// ---------------------------------------------------------------------------
 
-    let output = network.forward(inputs);
-    let labels = network.metadata.as_ref().and_then(|m| m.output_labels.as_deref());
-    format_output(&output, labels, &network.layers.last().unwrap().activator)
+/// JSON inference endpoint: `{"model": "...", "inputs": [...]}`.
+pub fn handle_api_predict(request: &mut Request, state: SharedState) -> Response<Cursor<Vec<u8>>> {
+    let mut body: Vec<u8> = Vec::new();
+    let _ = request.as_reader().read_to_end(&mut body);
+    let store = state.lock().unwrap().model_store.clone();
+    let (status, json) = handle_predict_json(&store, &body);
+    crate::routes::json_response(status, json)
 }
 
 // ---------------------------------------------------------------------------
 // Output formatters
 // ---------------------------------------------------------------------------
 
-fn format_output(output: &[f64], labels: Option<&[String]>, activator: &ActivationFunction) -> String {
-    match activator {
-        ActivationFunction::Softmax                         => format_softmax(output, labels),
-        ActivationFunction::Sigmoid if output.len() == 1   => format_sigmoid(output[0]),
-        _                                                   => format_raw(output),
+fn format_prediction(prediction: &Prediction) -> String {
+    match prediction {
+        Prediction::Classes { ranked }     => format_classes(ranked),
+        Prediction::Probability { value } => format_sigmoid(*value),
+        Prediction::Raw { values }         => format_raw(values),
     }
 }
 
-fn format_softmax(output: &[f64], labels: Option<&[String]>) -> String {
-    let n = output.len();
-    let (best, best_conf) = output.iter().enumerate()
-        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-        .map(|(i, &v)| (i, v))
-        .unwrap_or((0, 0.0));
-
-    let label_for = |i: usize| -> String {
-        labels.and_then(|l| l.get(i)).cloned().unwrap_or_else(|| i.to_string())
-    };
-
-    let hero = label_for(best);
-
-    let mut sorted: Vec<usize> = (0..n).collect();
-    sorted.sort_by(|&a, &b| output[b].partial_cmp(&output[a]).unwrap());
+fn format_classes(ranked: &[ClassScore]) -> String {
+    let Some(best) = ranked.first() else { return format_raw(&[]) };
 
-    let rows: String = sorted.iter().map(|&i| {
-        let pct   = output[i] * 100.0;
-        let width = (output[i] * 260.0) as u32;
-        let dim   = if i != best { " dim" } else { "" };
+    let rows: String = ranked.iter().map(|c| {
+        let pct   = c.probability * 100.0;
+        let width = (c.probability * 260.0) as u32;
+        let dim   = if c.index != best.index { " dim" } else { "" };
         format!(
             r#"<tr><td style="width:60px;font-weight:600;color:#333">{}</td><td><div class="bar-wrap"><div class="bar-fill{}" style="width:{}px"></div></div></td><td class="prob-pct">{:.1}%</td></tr>"#,
-            label_for(i), dim, width, pct
+            html_escape(&c.label), dim, width, pct
         )
     }).collect();
 
@@ -300,7 +397,7 @@ fn format_softmax(output: &[f64], labels: Option<&[String]>) -> String {
   <thead><tr><th>Class</th><th>Confidence</th><th></th></tr></thead>
   <tbody>{rows}</tbody>
 </table></div>"#,
-        hero = html_escape(&hero), conf = best_conf * 100.0, rows = rows
+        hero = html_escape(&best.label), conf = best.probability * 100.0, rows = rows
     )
 }
 
@@ -365,7 +462,7 @@ pub fn handle_import_model(request: &mut Request, state: SharedState) -> Respons
         }
     };
 
-    // Basic JSON validation: must deserialize and contain a "layers" key.
+    // The file must load as a model (older formats are migrated on load).
     let json_val: serde_json::Value = match serde_json::from_slice(&file_bytes) {
         Ok(v)  => v,
         Err(_) => {
@@ -373,8 +470,9 @@ pub fn handle_import_model(request: &mut Request, state: SharedState) -> Respons
             return crate::routes::html_response(page);
         }
     };
-    if json_val.get("layers").is_none() {
-        let page = build_test_page("", &error_html("JSON does not appear to be a Ferrite model (missing \"layers\" field)."), mask);
+    if let Err(e) = serde_json::from_value::<Network>(json_val) {
+        let msg = format!("JSON is not a valid Ferrite model: {}", html_escape(&e.to_string()));
+        let page = build_test_page("", &error_html(&msg), mask);
         return crate::routes::html_response(page);
     }
 
@@ -396,11 +494,11 @@ pub fn handle_import_model(request: &mut Request, state: SharedState) -> Respons
     // Write to trained_models/.
     let model_dir  = "trained_models";
     let model_path = format!("{}/{}.json", model_dir, model_name);
-    if let Err(_) = std::fs::create_dir_all(model_dir) {
+    if std::fs::create_dir_all(model_dir).is_err() {
         let page = build_test_page("", &error_html("Could not create trained_models/ directory."), mask);
         return crate::routes::html_response(page);
     }
-    if let Err(_) = std::fs::write(&model_path, &file_bytes) {
+    if std::fs::write(&model_path, &file_bytes).is_err() {
         let page = build_test_page("", &error_html(&format!("Could not write model to '{}'.", model_path)), mask);
         return crate::routes::html_response(page);
     }
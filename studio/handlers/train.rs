@@ -4,11 +4,15 @@ use std::panic;
 use tiny_http::Response;
 use std::io::Cursor;
 
-use ferrite_nn::{Network, Sgd, LossType, TrainConfig, train_loop};
+use std::collections::BTreeMap;
 
-use crate::state::{FlashMessage, SharedState, TrainingStatus};
+use ferrite_nn::{ActivationFunction, CrossEntropyLoss, Network, Sgd, LossType, TrainConfig, try_train_loop, Dataset,
+                 evaluate, calibrate_temperature, oversample, oversample_seeded, undersample, undersample_seeded, Split};
+use ferrite_nn::network::registry::DEFAULT_MODEL_DIR;
+
+use crate::state::{ClassBalance, FlashMessage, SharedState, TrainingStatus};
 use crate::render::{render_page, Page};
-use crate::handlers::architect::{render_flash_html, html_escape, activation_to_str};
+use crate::handlers::architect::{render_flash_html, html_escape};
 
 // ---------------------------------------------------------------------------
 // GET /train
@@ -20,7 +24,7 @@ pub fn handle_get(state: SharedState) -> Response<Cursor<Vec<u8>>> {
     let mask       = st.tab_unlock_mask();
     let spec       = st.spec.clone();
     let hp         = st.hyperparams.clone();
-    let ds         = st.dataset.as_ref().map(|d| (d.train_inputs.len(), d.val_inputs.len(), d.source_name.clone()));
+    let ds         = st.dataset.as_ref().map(|d| (d.data.train.len(), d.data.val.len(), d.source_name.clone()));
     let training   = &st.training;
     let history    = st.epoch_history.clone();
 
@@ -60,8 +64,12 @@ pub fn handle_get(state: SharedState) -> Response<Cursor<Vec<u8>>> {
 
     let arch_summary = spec.as_ref().map(|s| {
         let layers_desc: String = s.layers.iter().enumerate().map(|(i, l)| {
-            format!("<div class=\"arch-row\"><span class=\"ar-lbl\">Layer {}</span><span class=\"ar-val\">{} neurons — {}</span></div>",
-                i+1, l.size, activation_to_str(&l.activation))
+            let label = match l.name {
+                Some(ref name) => html_escape(name),
+                None           => format!("Layer {}", i + 1),
+            };
+            format!("<div class=\"arch-row\"><span class=\"ar-lbl\">{}</span><span class=\"ar-val\">{} neurons — {}</span></div>",
+                label, l.size, l.activation)
         }).collect();
         let loss_name = match s.loss {
             LossType::CrossEntropy       => "Cross-Entropy",
@@ -204,6 +212,7 @@ pub fn handle_start(state: SharedState) -> Response<Cursor<Vec<u8>>> {
     let ds     = st.dataset.clone().unwrap();
 
     let (tx, rx) = mpsc::channel::<ferrite_nn::EpochStats>();
+    let (batch_tx, batch_rx) = mpsc::channel::<ferrite_nn::BatchStats>();
     let stop_flag = Arc::new(AtomicBool::new(false));
 
     let epoch_rx = Arc::new(Mutex::new(rx));
@@ -212,6 +221,7 @@ pub fn handle_start(state: SharedState) -> Response<Cursor<Vec<u8>>> {
     st.training = TrainingStatus::Running {
         stop_flag:   stop_flag.clone(),
         epoch_rx:    epoch_rx.clone(),
+        batch_rx:    Arc::new(Mutex::new(batch_rx)),
         total_epochs,
     };
     st.epoch_history.clear();
@@ -221,21 +231,48 @@ pub fn handle_start(state: SharedState) -> Response<Cursor<Vec<u8>>> {
     // Spawn background training thread.
     let state_clone = state.clone();
     thread::spawn(move || {
-        let mut network = Network::from_spec(&spec);
-        let optimizer   = Sgd::new(hp.learning_rate);
+        let mut network = match hp.seed {
+            Some(seed) => Network::from_spec_seeded(&spec, seed),
+            None => Network::from_spec(&spec),
+        };
+        let mut optimizer = Sgd::new(hp.learning_rate);
+
+        // Resampling rebalances the training split only; validation keeps the
+        // real class distribution.
+        let mut ds = ds;
+        if matches!(spec.loss, LossType::CrossEntropy | LossType::BinaryCrossEntropy) {
+            let train = &ds.data.train;
+            let resampled = match (hp.class_balance, hp.seed) {
+                (ClassBalance::Oversample, Some(seed))  => Some(oversample_seeded(&train.inputs, &train.labels, seed)),
+                (ClassBalance::Oversample, None)        => Some(oversample(&train.inputs, &train.labels)),
+                (ClassBalance::Undersample, Some(seed)) => Some(undersample_seeded(&train.inputs, &train.labels, seed)),
+                (ClassBalance::Undersample, None)       => Some(undersample(&train.inputs, &train.labels)),
+                _ => None,
+            };
+            if let Some((inputs, labels)) = resampled {
+                ds.data.train = Split::new(inputs, labels);
+            }
+        }
 
-        let val_inputs = if ds.val_inputs.is_empty() { None } else { Some(ds.val_inputs.as_slice()) };
-        let val_labels = if ds.val_labels.is_empty() { None } else { Some(ds.val_labels.as_slice()) };
+        let val: Option<&dyn Dataset> = if ds.data.val.is_empty() { None } else { Some(&ds.data.val) };
 
         let mut config = TrainConfig::new(hp.epochs, hp.batch_size, spec.loss);
         config.progress_tx = Some(tx);
+        config.batch_tx    = Some(batch_tx);
         config.stop_flag   = Some(stop_flag.clone());
+        config.l1          = hp.l1;
+        config.l2          = hp.l2;
+        config.seed        = hp.seed;
+        config.input_scaling = hp.input_scaling;
+        if hp.class_balance == ClassBalance::Weights && spec.loss == LossType::CrossEntropy {
+            config.class_weights = Some(CrossEntropyLoss::balanced_weights(&ds.data.train.labels));
+        }
 
         println!(
             "[studio] Training started: model='{}', samples={}, val={}, epochs={}, batch_size={}, lr={}",
             spec.name,
-            ds.train_inputs.len(),
-            ds.val_inputs.len(),
+            ds.data.train.len(),
+            ds.data.val.len(),
             hp.epochs,
             hp.batch_size,
             hp.learning_rate,
@@ -243,35 +280,45 @@ pub fn handle_start(state: SharedState) -> Response<Cursor<Vec<u8>>> {
 
         let t_start = std::time::Instant::now();
 
-        // Wrap train_loop in catch_unwind so a panic (e.g. matrix dimension
-        // mismatch or numerical issue) transitions state to Failed instead of
-        // leaving the UI stuck in "Running" forever.
+        // try_train_loop reports bad data and divergence as errors; the
+        // catch_unwind is a last line of defence so any remaining panic still
+        // transitions state to Failed instead of leaving the UI stuck in
+        // "Running" forever.
         let train_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-            train_loop(
+            try_train_loop(
                 &mut network,
-                &ds.train_inputs,
-                &ds.train_labels,
-                val_inputs,
-                val_labels,
-                &optimizer,
+                &ds.data.train,
+                val,
+                &mut optimizer,
                 &config,
+                &mut [],
             )
         }));
 
-        if let Err(payload) = train_result {
-            let reason = if let Some(s) = payload.downcast_ref::<String>() {
-                format!("Training thread panicked: {}", s)
-            } else if let Some(s) = payload.downcast_ref::<&str>() {
-                format!("Training thread panicked: {}", s)
-            } else {
-                "Training thread panicked (unknown cause). Check that the \
-                 architecture input size matches the dataset feature count.".to_owned()
-            };
-            eprintln!("[studio] ERROR: {}", reason);
-            let mut st = state_clone.lock().unwrap();
-            st.training = TrainingStatus::Failed { reason };
-            return;
-        }
+        let final_train_loss = match train_result {
+            Ok(Ok(outcome)) => outcome.final_loss,
+            Ok(Err(e))   => {
+                let reason = format!("Training failed: {}", e);
+                eprintln!("[studio] ERROR: {}", reason);
+                let mut st = state_clone.lock().unwrap();
+                st.training = TrainingStatus::Failed { reason };
+                return;
+            }
+            Err(payload) => {
+                let reason = if let Some(s) = payload.downcast_ref::<String>() {
+                    format!("Training thread panicked: {}", s)
+                } else if let Some(s) = payload.downcast_ref::<&str>() {
+                    format!("Training thread panicked: {}", s)
+                } else {
+                    "Training thread panicked (unknown cause). Check that the \
+                     architecture input size matches the dataset feature count.".to_owned()
+                };
+                eprintln!("[studio] ERROR: {}", reason);
+                let mut st = state_clone.lock().unwrap();
+                st.training = TrainingStatus::Failed { reason };
+                return;
+            }
+        };
 
         let elapsed_total_ms = t_start.elapsed().as_millis() as u64;
         let was_stopped = stop_flag.load(Ordering::Relaxed);
@@ -286,11 +333,41 @@ pub fn handle_start(state: SharedState) -> Response<Cursor<Vec<u8>>> {
 
         // Save model.
         let model_name = spec.name.clone();
-        let model_dir  = "trained_models";
+        let model_dir  = DEFAULT_MODEL_DIR;
         let model_path = format!("{}/{}.json", model_dir, model_name);
         let _ = std::fs::create_dir_all(model_dir);
-        // Attach metadata from spec.
-        network.metadata = spec.metadata.clone();
+        // Attach metadata from spec, plus final metrics for the model listing;
+        // keep the input scaling fitted during training and the dataset's
+        // feature names.
+        let input_scaling = network.metadata.take().and_then(|m| m.input_scaling);
+        network.metadata = Some(ferrite_nn::ModelMetadata {
+            input_scaling,
+            feature_names: ds.feature_names.clone(),
+            ..spec.metadata.clone().unwrap_or_default()
+        });
+        let mut metrics = BTreeMap::new();
+        metrics.insert("train_loss".to_owned(), final_train_loss);
+        if !ds.data.val.is_empty() {
+            // Calibrate Softmax confidences on the validation split before
+            // measuring it, so the recorded val_loss is the calibrated one.
+            let softmax_output = network.layers.last()
+                .is_some_and(|l| l.activation() == Some(&ActivationFunction::Softmax));
+            if softmax_output {
+                let calibration = calibrate_temperature(&mut network, &ds.data.val);
+                println!(
+                    "[studio] Calibrated temperature {:.3} (val NLL {:.4} -> {:.4})",
+                    calibration.temperature, calibration.nll_before, calibration.nll_after,
+                );
+            }
+            let report = evaluate(&mut network, &ds.data.val, spec.loss);
+            metrics.insert("val_loss".to_owned(), report.loss);
+            if let Some(acc) = report.accuracy {
+                metrics.insert("val_accuracy".to_owned(), acc);
+            }
+        }
+        if let Some(metadata) = network.metadata.as_mut() {
+            metadata.metrics = Some(metrics);
+        }
         let save_ok = network.save_json(&model_path).is_ok();
 
         let mut st = state_clone.lock().unwrap();
@@ -12,6 +12,10 @@ use crate::state::{SharedState, TrainingStatus};
 ///    500 ms timeout.
 /// 2. On success — serializes the stats and writes an `event: epoch\n\n` frame.
 /// 3. On timeout — writes a keep-alive `: ping\n\n` comment.
+///
+/// After either, the latest pending `BatchStats` (if any) is written as an
+/// `event: batch` frame; older batch updates are dropped, so the client sees
+/// intra-epoch progress at most every 500 ms.
 /// 4. On channel disconnect (training finished) — writes a `done` or `stopped`
 ///    event, then closes.
 ///
@@ -28,20 +32,20 @@ pub fn handle(request: Request, state: SharedState) {
                   Connection: keep-alive\r\n\
                   X-Accel-Buffering: no\r\n\
                   \r\n";
-    if let Err(_) = write_all(&mut writer, header.as_bytes()) {
+    if write_all(&mut writer, header.as_bytes()).is_err() {
         return;
     }
 
     // Extract the receiver Arc from state (clone it out so we don't hold the lock).
-    let epoch_rx = {
+    let receivers = {
         let st = state.lock().unwrap();
         match &st.training {
-            TrainingStatus::Running { epoch_rx, .. } => Some(epoch_rx.clone()),
+            TrainingStatus::Running { epoch_rx, batch_rx, .. } => Some((epoch_rx.clone(), batch_rx.clone())),
             _ => None,
         }
     };
 
-    let rx_arc = match epoch_rx {
+    let (rx_arc, batch_rx_arc) = match receivers {
         Some(r) => r,
         None    => {
             // Training is not Running — emit an event matching the actual state.
@@ -159,6 +163,16 @@ pub fn handle(request: Request, state: SharedState) {
                 return;
             }
         }
+
+        // Forward only the newest intra-epoch update.
+        let latest_batch = {
+            let rx = batch_rx_arc.lock().unwrap();
+            rx.try_iter().last()
+        };
+        if let Some(json) = latest_batch.and_then(|b| serde_json::to_string(&b).ok()) {
+            let msg = format!("event: batch\ndata: {}\n\n", json);
+            if write_all(&mut writer, msg.as_bytes()).is_err() { return; }
+        }
     }
 }
 
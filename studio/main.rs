@@ -1,18 +1,18 @@
-/// ferrite-nn Studio
-///
-/// A full browser-based neural network creation, training, and testing platform.
-/// Served by a synchronous tiny_http server; no JavaScript frameworks required.
-///
-/// Run with:
-///   cargo run --bin studio --release
-/// Then open http://127.0.0.1:7878
-///
-/// Tabs:
-///   1. Architect — define network layers, loss, and hyperparameters
-///   2. Dataset   — upload a CSV or pick a built-in toy dataset
-///   3. Train     — train with real-time SSE loss chart
-///   4. Evaluate  — loss curve, metrics table, confusion matrix
-///   5. Test      — run inference on any saved model
+//! ferrite-nn Studio
+//!
+//! A full browser-based neural network creation, training, and testing platform.
+//! Served by a synchronous tiny_http server; no JavaScript frameworks required.
+//!
+//! Run with:
+//!   cargo run --bin studio --release
+//! Then open http://127.0.0.1:7878
+//!
+//! Tabs:
+//!   1. Architect — define network layers, loss, and hyperparameters
+//!   2. Dataset   — upload a CSV or pick a built-in toy dataset
+//!   3. Train     — train with real-time SSE loss chart
+//!   4. Evaluate  — loss curve, metrics table, confusion matrix
+//!   5. Test      — run inference on any saved model
 
 mod state;
 mod render;
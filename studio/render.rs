@@ -1,14 +1,14 @@
-/// Central template renderer for the ferrite-nn studio.
-///
-/// The studio uses a single HTML template (`studio/assets/studio.html`) with
-/// placeholder tokens like `{{TOKEN}}`.  This module loads the template at
-/// compile time and exposes a single `render_page` function that accepts a
-/// closure to do tab-specific placeholder substitution.
-///
-/// Placeholders that are global across all pages (tab_unlock, active_tab,
-/// training_running) are resolved here before calling the closure; tab-specific
-/// placeholders that were not replaced by the closure are blanked to avoid
-/// leaking raw `{{TOKEN}}` strings to the browser.
+//! Central template renderer for the ferrite-nn studio.
+//!
+//! The studio uses a single HTML template (`studio/assets/studio.html`) with
+//! placeholder tokens like `{{TOKEN}}`.  This module loads the template at
+//! compile time and exposes a single `render_page` function that accepts a
+//! closure to do tab-specific placeholder substitution.
+//!
+//! Placeholders that are global across all pages (tab_unlock, active_tab,
+//! training_running) are resolved here before calling the closure; tab-specific
+//! placeholders that were not replaced by the closure are blanked to avoid
+//! leaking raw `{{TOKEN}}` strings to the browser.
 
 const TEMPLATE: &str = include_str!("assets/studio.html");
 
@@ -49,6 +49,18 @@ pub fn json_download_response(body: String, filename: &str) -> Response<Cursor<V
     )
 }
 
+pub fn json_response(status: u16, body: String) -> Response<Cursor<Vec<u8>>> {
+    let bytes = body.into_bytes();
+    let len = bytes.len();
+    Response::new(
+        StatusCode(status),
+        vec![Header::from_bytes(b"Content-Type", b"application/json").unwrap()],
+        Cursor::new(bytes),
+        Some(len),
+        None,
+    )
+}
+
 pub fn not_found() -> Response<Cursor<Vec<u8>>> {
     let body = b"404 Not Found".to_vec();
     let len = body.len();
@@ -126,6 +138,9 @@ pub fn dispatch(mut request: Request, state: SharedState) {
         (Method::Post, "/test/infer")         => handlers::test::handle_infer(&mut request, state),
         (Method::Post, "/test/import-model")  => handlers::test::handle_import_model(&mut request, state),
 
+        // JSON inference API
+        (Method::Post, "/api/predict") => handlers::test::handle_api_predict(&mut request, state),
+
         // ── 404 ──────────────────────────────────────────────────────────
         _ => not_found(),
     };
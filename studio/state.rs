@@ -1,5 +1,7 @@
 use std::sync::{Arc, Mutex, atomic::AtomicBool, mpsc};
-use ferrite_nn::{Network, NetworkSpec, EpochStats};
+use ferrite_nn::{Network, NetworkSpec, EpochStats, BatchStats, SplitDataset, ModelStore, ScalingMethod};
+use ferrite_nn::network::registry::DEFAULT_MODEL_DIR;
+use ferrite_nn::serve::BatchConfig;
 
 // ---------------------------------------------------------------------------
 // Hyperparams
@@ -12,25 +14,47 @@ pub struct Hyperparams {
     pub learning_rate: f64,
     pub batch_size: usize,
     pub epochs: usize,
+    /// L1 / L2 weight-decay strengths (0 = off).
+    pub l1: f64,
+    pub l2: f64,
+    /// How a skewed training split is rebalanced (classification only).
+    pub class_balance: ClassBalance,
+    /// Seeds weight initialization and shuffling for a reproducible run;
+    /// `None` draws fresh randomness every time.
+    pub seed: Option<u64>,
+    /// Per-feature input scaling fitted on the training split and stored in
+    /// the saved model; `None` trains on the raw values.
+    pub input_scaling: Option<ScalingMethod>,
 }
 
 impl Default for Hyperparams {
     fn default() -> Self {
-        Hyperparams { learning_rate: 0.01, batch_size: 32, epochs: 50 }
+        Hyperparams { learning_rate: 0.01, batch_size: 32, epochs: 50, l1: 0.0, l2: 0.0, class_balance: ClassBalance::None, seed: None, input_scaling: None }
     }
 }
 
+/// Rebalancing of the training split's classes, chosen on the Architect tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassBalance {
+    None,
+    /// Weight cross-entropy by inverse class frequency.
+    Weights,
+    /// Repeat rows of the smaller classes until every class is as frequent
+    /// as the largest.
+    Oversample,
+    /// Drop rows of the larger classes down to the size of the smallest.
+    Undersample,
+}
+
 // ---------------------------------------------------------------------------
 // Dataset
 // ---------------------------------------------------------------------------
 
-/// Loaded dataset split into train / validation sets.
+/// Loaded dataset split into train / validation sets (the test split is
+/// unused by the Studio and stays empty).
 #[derive(Debug, Clone)]
 pub struct DatasetState {
-    pub train_inputs: Vec<Vec<f64>>,
-    pub train_labels: Vec<Vec<f64>>,
-    pub val_inputs:   Vec<Vec<f64>>,
-    pub val_labels:   Vec<Vec<f64>>,
+    pub data:          SplitDataset,
     pub feature_count: usize,
     pub label_count:   usize,
     pub total_rows:    usize,
@@ -39,6 +63,8 @@ pub struct DatasetState {
     pub source_name:   String,
     /// First 5 rows of raw input for the preview table (inputs + labels).
     pub preview_rows:  Vec<(Vec<f64>, Vec<f64>)>,
+    /// Feature column names from the CSV header, saved with the trained model.
+    pub feature_names: Option<Vec<String>>,
 }
 
 // ---------------------------------------------------------------------------
@@ -52,6 +78,7 @@ pub enum TrainingStatus {
     Running {
         stop_flag:    Arc<AtomicBool>,
         epoch_rx:     Arc<Mutex<mpsc::Receiver<EpochStats>>>,
+        batch_rx:     Arc<Mutex<mpsc::Receiver<BatchStats>>>,
         total_epochs: usize,
     },
     /// Training completed (naturally or via Stop) and the model was saved.
@@ -108,6 +135,10 @@ pub struct StudioState {
     pub trained_network:  Option<Network>,
     /// One-shot flash message for the next page render.
     pub flash:            Option<FlashMessage>,
+    /// Models loaded by the Test tab and `/api/predict`, reloaded when the
+    /// file changes; `/api/predict` requests are batched per model.  Clone
+    /// the `Arc` and release the state lock before calling into it.
+    pub model_store:      Arc<ModelStore>,
 }
 
 impl StudioState {
@@ -120,6 +151,9 @@ impl StudioState {
             epoch_history:   Vec::new(),
             trained_network: None,
             flash:           None,
+            model_store:     Arc::new(
+                ModelStore::new(DEFAULT_MODEL_DIR).with_batching(BatchConfig::default())
+            ),
         }
     }
 
@@ -141,11 +175,8 @@ impl StudioState {
         if self.dataset.is_some() {
             mask |= 0b0_0100; // Train
         }
-        match &self.training {
-            TrainingStatus::Done { .. } => {
-                mask |= 0b0_1000; // Evaluate
-            }
-            _ => {}
+        if let TrainingStatus::Done { .. } = &self.training {
+            mask |= 0b0_1000; // Evaluate
         }
         mask
     }
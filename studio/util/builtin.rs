@@ -0,0 +1,64 @@
+//! Built-in toy datasets offered on the Dataset tab.
+
+/// Returns the XOR dataset: 4 samples, 2 inputs, 1 one-hot output (2 classes).
+pub fn builtin_xor() -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let inputs = vec![
+        vec![0.0, 0.0],
+        vec![0.0, 1.0],
+        vec![1.0, 0.0],
+        vec![1.0, 1.0],
+    ];
+    let labels = vec![
+        vec![1.0, 0.0], // XOR = 0
+        vec![0.0, 1.0], // XOR = 1
+        vec![0.0, 1.0], // XOR = 1
+        vec![1.0, 0.0], // XOR = 0
+    ];
+    (inputs, labels)
+}
+
+/// Generates `n` samples of 2D "two circles" data (class 0 = inner, class 1 = outer).
+/// Outputs are one-hot vectors of length 2.
+pub fn builtin_circles(n: usize) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    use std::f64::consts::PI;
+    let mut inputs = Vec::with_capacity(n);
+    let mut labels = Vec::with_capacity(n);
+    for i in 0..n {
+        let class = i % 2;
+        let angle = (i as f64 / n as f64) * 2.0 * PI * 10.0;
+        let radius = if class == 0 { 0.3 } else { 0.8 };
+        // Add small deterministic "noise" via a second sinusoidal.
+        let noise = 0.05 * ((i as f64 * 7.3).sin());
+        let x = (radius + noise) * angle.cos();
+        let y = (radius + noise) * angle.sin();
+        // Normalize to [0, 1].
+        inputs.push(vec![(x + 1.0) / 2.0, (y + 1.0) / 2.0]);
+        let mut oh = vec![0.0, 0.0];
+        oh[class] = 1.0;
+        labels.push(oh);
+    }
+    (inputs, labels)
+}
+
+/// Generates `n` samples of 2D "two blobs" data.
+/// Outputs are one-hot vectors of length 2.
+pub fn builtin_blobs(n: usize) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let mut inputs = Vec::with_capacity(n);
+    let mut labels = Vec::with_capacity(n);
+    // Centers: class 0 at (0.3, 0.3), class 1 at (0.7, 0.7).
+    let centers = [(0.3f64, 0.3f64), (0.7f64, 0.7f64)];
+    for i in 0..n {
+        let class = i % 2;
+        let (cx, cy) = centers[class];
+        // Deterministic "pseudo-random" spread using sin/cos of index.
+        let angle = i as f64 * 2.399; // irrational-ish step
+        let r = 0.12 * (i as f64 * 0.31).sin().abs();
+        let x = (cx + r * angle.cos()).clamp(0.0, 1.0);
+        let y = (cy + r * angle.sin()).clamp(0.0, 1.0);
+        inputs.push(vec![x, y]);
+        let mut oh = vec![0.0, 0.0];
+        oh[class] = 1.0;
+        labels.push(oh);
+    }
+    (inputs, labels)
+}
@@ -1,6 +1,4 @@
 pub mod form;
 pub mod multipart;
-pub mod csv;
-pub mod idx;
+pub mod builtin;
 pub mod sse;
-pub mod image;